@@ -2,13 +2,11 @@
 extern crate criterion;
 extern crate typed_arena;
 
-use criterion::{Criterion, ParameterizedBenchmark, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 
-#[derive(Default)]
-struct Small(usize);
+type Small = usize;
 
-#[derive(Default)]
-struct Big([usize; 32]);
+type Big = [usize; 32];
 
 fn allocate<T: Default>(n: usize) {
     let arena = typed_arena::Arena::new();
@@ -18,26 +16,40 @@ fn allocate<T: Default>(n: usize) {
     }
 }
 
+fn nested_scopes(depth: usize) {
+    fn open(arena: &typed_arena::Arena<Small>, depth: usize) {
+        if depth > 0 {
+            let sub_arena = typed_arena::SubArena::new(arena);
+            open(&sub_arena, depth - 1);
+        }
+    }
+    let arena = typed_arena::Arena::new();
+    arena.alloc(0);
+    open(&arena, depth);
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench(
-        "allocate",
-        ParameterizedBenchmark::new(
-            "allocate-small",
-            |b, n| b.iter(|| allocate::<Small>(*n)),
-            (1..5).map(|n| n * 1000).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
-
-    c.bench(
-        "allocate",
-        ParameterizedBenchmark::new(
-            "allocate-big",
-            |b, n| b.iter(|| allocate::<Big>(*n)),
-            (1..5).map(|n| n * 1000).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
+    let mut group = c.benchmark_group("allocate");
+    for n in (1..5).map(|n| n * 1000) {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("allocate-small", n), &n, |b, n| {
+            b.iter(|| allocate::<Small>(*n))
+        });
+        group.bench_with_input(BenchmarkId::new("allocate-big", n), &n, |b, n| {
+            b.iter(|| allocate::<Big>(*n))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("sub-arena");
+    for depth in (1..5).map(|n| n * 10) {
+        group.bench_with_input(
+            BenchmarkId::new("empty-nested", depth),
+            &depth,
+            |b, depth| b.iter(|| nested_scopes(*depth)),
+        );
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
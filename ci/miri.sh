@@ -8,4 +8,11 @@ cargo clean
 rustup component add miri
 cargo miri setup
 
+# `Arena::grow_last` extends the slice it is given in place, which Stacked
+# Borrows rejects even though nothing else can access that slice.
+export MIRIFLAGS=-Zmiri-tree-borrows
 cargo miri test
+cargo miri test --features allocator_api
+
+# Leaking a `SubArena` on purpose also leaks the chunks it added.
+MIRIFLAGS="$MIRIFLAGS -Zmiri-ignore-leaks" cargo miri test --lib -- --ignored leaked_sub_arena
@@ -0,0 +1,95 @@
+use crate::bump::try_alloc_layout_in;
+use crate::{Arena, Bump, SubArena};
+
+use core::alloc::{AllocError, Allocator, Layout};
+use core::mem::MaybeUninit;
+use core::ptr::{self, NonNull};
+
+/// With the nightly-only `allocator_api` feature, an arena of uninitialized
+/// bytes can back standard collections, as in
+/// `Vec<T, &Arena<MaybeUninit<u8>>>`.
+///
+/// The bytes are `MaybeUninit`, since collections write padding and leave
+/// spare capacity uninitialized, and the arena hands its values out again.
+/// Deallocating does nothing: the memory is only reclaimed when the arena is
+/// dropped. Allocating fails if the arena runs out of memory, or if it is
+/// borrowed, for example by a [`SubArena`].
+///
+/// ## Example
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use std::mem::MaybeUninit;
+/// use typed_arena::Arena;
+///
+/// let arena = Arena::<MaybeUninit<u8>>::new();
+/// let mut squares = Vec::new_in(&arena);
+/// squares.extend((1..=4_u64).map(|n| n * n));
+/// assert_eq!(squares, [1, 4, 9, 16]);
+/// ```
+unsafe impl Allocator for Arena<MaybeUninit<u8>> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let block = try_alloc_layout_in(self, layout).map_err(|_| AllocError)?;
+        let block = ptr::slice_from_raw_parts_mut(block.as_ptr(), layout.size());
+        Ok(unsafe { NonNull::new_unchecked(block) })
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
+
+/// A sub-arena of an arena of uninitialized bytes is an allocator too, as
+/// in `Vec<T, &SubArena<MaybeUninit<u8>>>`, for temporary collections whose
+/// memory goes back to the arena when the sub-arena ends.
+///
+/// The collections borrow the sub-arena, so they cannot outlive it.
+///
+/// ## Example
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use std::mem::MaybeUninit;
+/// use typed_arena::{Arena, SubArena};
+///
+/// let arena = Arena::new();
+/// arena.alloc(MaybeUninit::new(0));
+/// {
+///     let scratch = SubArena::new(&arena);
+///     let mut stack: Vec<u32, &SubArena<MaybeUninit<u8>>> = Vec::new_in(&scratch);
+///     stack.extend(0..100);
+///     assert_eq!(stack.pop(), Some(99));
+/// }
+/// assert_eq!(arena.len(), 1);
+/// ```
+unsafe impl<'a> Allocator for SubArena<'a, MaybeUninit<u8>> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Arena::allocate(self, layout)
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
+
+/// A [`Bump`] is an allocator, as in `Vec<T, &Bump>`, like an arena of
+/// uninitialized bytes.
+///
+/// ## Example
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use typed_arena::Bump;
+///
+/// let bump = Bump::new();
+/// let name = bump.alloc_str("squares");
+/// let mut squares = Vec::new_in(&bump);
+/// squares.extend((1..=4_u64).map(|n| n * n));
+/// assert_eq!((&*name, &*squares), ("squares", &[1, 4, 9, 16][..]));
+/// ```
+unsafe impl Allocator for Bump {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.bytes.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
@@ -0,0 +1,129 @@
+use crate::Arena;
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use core::any::{Any, TypeId};
+use core::cell::RefCell;
+use core::fmt;
+use core::ptr::NonNull;
+
+/// A set of arenas, one for each type of value allocated in it.
+///
+/// The arena for a type is created the first time a value of that type is
+/// allocated, and all of them are dropped together with the `AnyArena`.
+/// This replaces a struct with one `Arena` field per type, as found in
+/// compilers with many kinds of nodes.
+///
+/// Unlike in a [`Bump`][crate::Bump], the values are dropped, and each of
+/// the arenas keeps its values together.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::AnyArena;
+///
+/// struct Function {
+///     name: String,
+/// }
+///
+/// let arena = AnyArena::new();
+/// let main = arena.alloc(Function { name: String::from("main") });
+/// let numbers = arena.arena::<u64>().alloc_extend(0..4);
+/// assert_eq!(main.name, "main");
+/// assert_eq!(numbers, [0, 1, 2, 3]);
+/// assert_eq!((arena.arena::<Function>().len(), arena.type_count()), (1, 2));
+/// ```
+pub struct AnyArena {
+    arenas: RefCell<BTreeMap<TypeId, Erased>>,
+}
+
+// An `Arena<T>` on the heap. It is never moved or dropped while the
+// `AnyArena` lives, so references to it stay valid as the map changes.
+struct Erased(NonNull<dyn Any>);
+
+impl Drop for Erased {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.0.as_ptr())) }
+    }
+}
+
+impl AnyArena {
+    /// Construct a new, empty set of arenas.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::AnyArena;
+    ///
+    /// let arena = AnyArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> AnyArena {
+        AnyArena {
+            arenas: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the arena for values of type `T`, creating it if needed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::AnyArena;
+    ///
+    /// let arena = AnyArena::new();
+    /// let words = arena.arena::<&str>().alloc_extend(vec!["a", "b"]);
+    /// assert_eq!(words.len(), arena.arena::<&str>().len());
+    /// ```
+    pub fn arena<T: 'static>(&self) -> &Arena<T> {
+        let mut arenas = self.arenas.borrow_mut();
+        let erased = arenas.entry(TypeId::of::<T>()).or_insert_with(|| {
+            let arena: Box<dyn Any> = Box::new(Arena::<T>::new());
+            Erased(NonNull::from(Box::leak(arena)))
+        });
+        // The key is the `TypeId` of `T`, so this is an `Arena<T>`.
+        unsafe { &*erased.0.as_ptr().cast::<Arena<T>>() }
+    }
+
+    /// Allocates a value in the arena for its type, and returns a mutable
+    /// reference to it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::AnyArena;
+    ///
+    /// let arena = AnyArena::new();
+    /// let x = arena.alloc(1_i32);
+    /// let name = arena.alloc(String::from("x"));
+    /// *x += 1;
+    /// assert_eq!((*x, name.as_str()), (2, "x"));
+    /// ```
+    pub fn alloc<T: 'static>(&self, value: T) -> &mut T {
+        self.arena::<T>().alloc(value)
+    }
+
+    /// Return the number of types that have an arena.
+    pub fn type_count(&self) -> usize {
+        self.arenas.borrow().len()
+    }
+}
+
+impl Default for AnyArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AnyArena {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AnyArena")
+            .field("type_count", &self.type_count())
+            .finish()
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{Arena, FrozenArena, Id, IdArena, SyncArena};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use rkyv::ser::{ScratchSpace, Serializer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Deserialize, Fallible, Serialize};
+
+/// Archives the values as an `ArchivedVec`, in allocation order. Only
+/// available with the `rkyv` feature.
+///
+/// The archive can be read in place, without deserializing it, and copied
+/// back into an arena with [`Arena::deserialize_archived`].
+///
+/// ## Example
+///
+/// ```
+/// # extern crate rkyv;
+/// use typed_arena::{Arena, FrozenArena};
+///
+/// let mut arena = Arena::new();
+/// arena.alloc_extend(1..4_u32);
+/// let bytes = rkyv::to_bytes::<_, 256>(&arena.share()).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<FrozenArena<u32>>(&bytes) };
+/// assert_eq!(archived.as_slice(), [1, 2, 3]);
+/// ```
+impl<'a, T: Archive> Archive for SyncArena<'a, T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    unsafe fn resolve(&self, pos: usize, resolver: VecResolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<'a, S, T> Serialize<S> for SyncArena<'a, T>
+where
+    S: ScratchSpace + Serializer + ?Sized,
+    T: Serialize<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        ArchivedVec::serialize_from_iter::<T, _, _, _>(self.iter(), serializer)
+    }
+}
+
+/// Archives the values as an `ArchivedVec`, in allocation order. Only
+/// available with the `rkyv` feature.
+impl<T: Archive> Archive for FrozenArena<T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    unsafe fn resolve(&self, pos: usize, resolver: VecResolver, out: *mut Self::Archived) {
+        self.share().resolve(pos, resolver, out);
+    }
+}
+
+impl<S, T> Serialize<S> for FrozenArena<T>
+where
+    S: ScratchSpace + Serializer + ?Sized,
+    T: Serialize<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        self.share().serialize(serializer)
+    }
+}
+
+/// Archives the values as an `ArchivedVec`, in allocation order, so that
+/// the index of each value is its id. Only available with the `rkyv`
+/// feature.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate rkyv;
+/// use typed_arena::IdArena;
+///
+/// let names = IdArena::new();
+/// names.alloc("a".to_string());
+/// let b = names.alloc("b".to_string());
+/// let bytes = rkyv::to_bytes::<_, 256>(&names).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<IdArena<String>>(&bytes) };
+/// assert_eq!(archived[b.index()], "b");
+/// ```
+impl<T: Archive> Archive for IdArena<T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    unsafe fn resolve(&self, pos: usize, resolver: VecResolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<S, T> Serialize<S> for IdArena<T>
+where
+    S: ScratchSpace + Serializer + ?Sized,
+    T: Serialize<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        // Values are only modified through `&mut self`.
+        let chunks = self.values.chunks.borrow();
+        SyncArena::new(&chunks.rest, &chunks.current).serialize(serializer)
+    }
+}
+
+impl<T: Archive> Arena<T> {
+    /// Deserializes archived values, allocating each of them in the arena,
+    /// and returns them as a slice.
+    ///
+    /// The values are written right after the last allocation, without
+    /// going through a `Vec`. On error, the values deserialized so far are
+    /// dropped, and the arena is left as it was. Only available with the
+    /// `rkyv` feature.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a value allocates in the arena while it is deserialized.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # extern crate rkyv;
+    /// use typed_arena::{Arena, FrozenArena};
+    ///
+    /// let arena = Arena::new();
+    /// arena.alloc_extend(vec!["a".to_string(), "b".to_string()]);
+    /// let frozen = arena.freeze();
+    /// let bytes = rkyv::to_bytes::<_, 256>(&frozen).unwrap();
+    ///
+    /// let archived = unsafe { rkyv::archived_root::<FrozenArena<String>>(&bytes) };
+    /// let arena: Arena<String> = Arena::new();
+    /// let names = arena.deserialize_archived(archived, &mut rkyv::Infallible).unwrap();
+    /// assert_eq!(names, ["a", "b"]);
+    /// ```
+    pub fn deserialize_archived<D>(
+        &self,
+        archived: &ArchivedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<&mut [T], D::Error>
+    where
+        T::Archived: Deserialize<T, D>,
+        D: Fallible + ?Sized,
+    {
+        let mut builder = self.slice_builder();
+        builder.reserve(archived.len());
+        for value in archived.iter() {
+            builder.push(value.deserialize(deserializer)?);
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl<T: Archive> IdArena<T> {
+    /// Deserializes archived values, allocating each of them in the arena,
+    /// and returns their ids, in order.
+    ///
+    /// On error, the values deserialized so far stay in the arena. Only
+    /// available with the `rkyv` feature.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the arena would hold more than `u32::MAX` values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # extern crate rkyv;
+    /// use typed_arena::IdArena;
+    ///
+    /// let cached: IdArena<u32> = IdArena::new();
+    /// cached.alloc(1);
+    /// cached.alloc(2);
+    /// let bytes = rkyv::to_bytes::<_, 256>(&cached).unwrap();
+    ///
+    /// let archived = unsafe { rkyv::archived_root::<IdArena<u32>>(&bytes) };
+    /// let arena = IdArena::new();
+    /// arena.alloc(0_u32);
+    /// let ids = arena.deserialize_archived(archived, &mut rkyv::Infallible).unwrap();
+    /// assert_eq!(ids.iter().map(|id| id.index()).collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(arena[ids[1]], 2);
+    /// ```
+    pub fn deserialize_archived<D>(
+        &self,
+        archived: &ArchivedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<Id<T>>, D::Error>
+    where
+        T::Archived: Deserialize<T, D>,
+        D: Fallible + ?Sized,
+    {
+        let mut ids = Vec::with_capacity(archived.len());
+        for value in archived.iter() {
+            ids.push(self.alloc(value.deserialize(deserializer)?));
+        }
+        Ok(ids)
+    }
+}
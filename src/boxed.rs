@@ -0,0 +1,147 @@
+use crate::Bump;
+
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+/// An owning pointer to a value in a [`Bump`][crate::Bump].
+///
+/// Created by [`Bump::alloc_box`][crate::Bump::alloc_box]. Like a `Box`, it
+/// drops its value when it is dropped, which lets values that hold
+/// resources, like files, be released early. Its memory is only freed with
+/// the bump arena.
+///
+/// ## Example
+///
+/// ```
+/// use std::cell::Cell;
+/// use typed_arena::Bump;
+///
+/// struct Guard<'a>(&'a Cell<bool>);
+/// impl Drop for Guard<'_> {
+///     fn drop(&mut self) {
+///         self.0.set(true);
+///     }
+/// }
+///
+/// let released = Cell::new(false);
+/// let bump = Bump::new();
+/// let guard = bump.alloc_box(Guard(&released));
+/// drop(guard);
+/// assert!(released.get());
+/// ```
+pub struct ArenaBox<'a, T: ?Sized> {
+    value: &'a mut T,
+}
+
+impl<'a, T: ?Sized> ArenaBox<'a, T> {
+    // The caller must own `*value`: nothing else may use or drop it once it
+    // is in the box.
+    pub(crate) unsafe fn from_mut(value: &'a mut T) -> Self {
+        ArenaBox { value }
+    }
+
+    /// Consumes the box without dropping its value, and returns a mutable
+    /// reference to it, which lives as long as the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaBox, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let x: &mut i32 = ArenaBox::leak(bump.alloc_box(1));
+    /// *x += 1;
+    /// assert_eq!(*x, 2);
+    /// ```
+    pub fn leak(this: Self) -> &'a mut T {
+        let this = ManuallyDrop::new(this);
+        unsafe { ptr::read(&this.value) }
+    }
+}
+
+impl<'a, T> ArenaBox<'a, T> {
+    /// Consumes the box, and moves its value out.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaBox, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let name = bump.alloc_box(String::from("Ferris"));
+    /// assert_eq!(ArenaBox::into_inner(name), "Ferris");
+    /// ```
+    pub fn into_inner(this: Self) -> T {
+        let value: *mut T = ArenaBox::leak(this);
+        unsafe { ptr::read(value) }
+    }
+
+    /// Moves the value of the box into `to`, and returns a box that lives
+    /// as long as `to`.
+    ///
+    /// This promotes a value from a short-lived scratch bump to a
+    /// long-lived one.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaBox, Bump};
+    ///
+    /// let long_lived = Bump::new();
+    /// let kept = {
+    ///     let scratch = Bump::new();
+    ///     let value = scratch.alloc_box(vec![1, 2, 3]);
+    ///     ArenaBox::transfer(value, &long_lived)
+    /// };
+    /// assert_eq!(*kept, [1, 2, 3]);
+    /// ```
+    pub fn transfer<'b>(this: Self, to: &'b Bump) -> ArenaBox<'b, T> {
+        to.alloc_box(ArenaBox::into_inner(this))
+    }
+}
+
+impl<'a, T: ?Sized> Drop for ArenaBox<'a, T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.value) }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for ArenaBox<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for ArenaBox<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized> AsRef<T> for ArenaBox<'a, T> {
+    fn as_ref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized> AsMut<T> for ArenaBox<'a, T> {
+    fn as_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug> fmt::Debug for ArenaBox<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Display> fmt::Display for ArenaBox<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,336 @@
+#[cfg(all(feature = "mmap", target_os = "linux"))]
+use crate::mmap::Advice;
+use crate::{Arena, GrowthStrategy, MIN_CAPACITY};
+#[cfg(feature = "std")]
+use crate::{ChunkRecycler, Recycler};
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cmp;
+use core::marker::PhantomData;
+use core::mem;
+use core::panic::AssertUnwindSafe;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// A builder to configure how an [`Arena`] sizes its chunks.
+///
+/// Created with [`Arena::builder`].
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Arena;
+///
+/// let arena = Arena::<u64>::builder()
+///     .initial_capacity(16)
+///     .max_chunk_size(64)
+///     .build();
+/// for i in 0..1000 {
+///     arena.alloc(i);
+/// }
+/// assert_eq!(arena.capacity(), 16 + 32 + 64 * 15);
+/// ```
+pub struct ArenaBuilder<T> {
+    initial_capacity: Option<usize>,
+    max_chunk_size: usize,
+    growth: Option<Box<dyn GrowthStrategy + Send>>,
+    alloc_error_handler: Option<Box<dyn Fn(usize) -> bool + Send>>,
+    label: Option<&'static str>,
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    advice: Advice,
+    #[cfg(feature = "std")]
+    recycler: Option<Arc<ChunkRecycler>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ArenaBuilder<T> {
+    /// Create a builder for an arena with the default chunk sizes, as used
+    /// by [`Arena::new`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ArenaBuilder;
+    ///
+    /// let arena = ArenaBuilder::new().build();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> Self {
+        ArenaBuilder {
+            initial_capacity: None,
+            max_chunk_size: usize::MAX,
+            growth: None,
+            alloc_error_handler: None,
+            label: None,
+            #[cfg(all(feature = "mmap", target_os = "linux"))]
+            advice: Advice::NONE,
+            #[cfg(feature = "std")]
+            recycler: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pre-allocate the first chunk with capacity for `n` values, like
+    /// [`Arena::with_capacity`].
+    ///
+    /// By default, the first chunk is allocated along with the first value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u32>::builder().initial_capacity(10).build();
+    /// assert_eq!(arena.capacity(), 10);
+    /// ```
+    pub fn initial_capacity(mut self, n: usize) -> Self {
+        self.initial_capacity = Some(n);
+        self
+    }
+
+    /// Stop doubling the size of new chunks once they have room for `n`
+    /// values.
+    ///
+    /// Past that size, new chunks all have capacity for `n` values, which
+    /// avoids asking the allocator for huge contiguous blocks. Allocations
+    /// of more than `n` values at once still get a chunk large enough to
+    /// hold them.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u8>::builder()
+    ///     .initial_capacity(1)
+    ///     .max_chunk_size(4)
+    ///     .build();
+    /// for i in 0..15 {
+    ///     arena.alloc(i);
+    /// }
+    /// // Chunks of 1, 2, 4, 4 and 4 bytes.
+    /// assert_eq!(arena.chunk_count(), 5);
+    /// ```
+    pub fn max_chunk_size(mut self, n: usize) -> Self {
+        self.max_chunk_size = cmp::max(MIN_CAPACITY, n);
+        self
+    }
+
+    /// Like [`max_chunk_size`][ArenaBuilder::max_chunk_size], but with the
+    /// ceiling given in bytes rather than in values.
+    ///
+    /// For zero-sized types, this has no effect.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// // Never ask the allocator for more than 64 MiB at once.
+    /// let arena = Arena::<[u8; 1024]>::builder()
+    ///     .max_chunk_bytes(64 << 20)
+    ///     .build();
+    /// # arena.alloc([0; 1024]);
+    /// ```
+    pub fn max_chunk_bytes(self, bytes: usize) -> Self {
+        match mem::size_of::<T>() {
+            0 => self,
+            size => self.max_chunk_size(bytes / size),
+        }
+    }
+
+    /// Size new chunks with `strategy` instead of doubling their capacity.
+    ///
+    /// See [`GrowthStrategy`] for the available strategies.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, Fixed};
+    ///
+    /// let arena = Arena::<u64>::builder().growth_strategy(Fixed(512)).build();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn growth_strategy<S>(mut self, strategy: S) -> Self
+    where
+        S: GrowthStrategy + Send + 'static,
+    {
+        self.growth = Some(Box::new(strategy));
+        self
+    }
+
+    /// Call `handler` when the allocator fails to provide a new chunk,
+    /// before the arena gives up.
+    ///
+    /// The handler is given the size of the failed allocation in bytes. It
+    /// may free some memory, for example by dropping caches, and return
+    /// `true` to have the allocation tried again, or `false` to give up:
+    /// the `try_` methods then return [`AllocError`][crate::AllocError],
+    /// and the others abort like `Vec` does. A handler that always returns
+    /// `true` retries forever. It is not called for sizes that overflow.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use typed_arena::Arena;
+    ///
+    /// let failures = Arc::new(AtomicUsize::new(0));
+    /// let counter = failures.clone();
+    /// let arena = Arena::<u8>::builder()
+    ///     .on_alloc_error(move |_bytes| {
+    ///         // Shed memory here, then retry once.
+    ///         counter.fetch_add(1, Ordering::Relaxed) == 0
+    ///     })
+    ///     .build();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn on_alloc_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(usize) -> bool + Send + 'static,
+    {
+        self.alloc_error_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Name the arena, so that its [`ArenaStats`][crate::ArenaStats], its
+    /// `Debug` output and, with the `tracing` and `metrics` features, its
+    /// events and metrics can be told apart from those of other arenas.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u64>::builder().label("tokens").build();
+    /// assert_eq!(arena.stats().label, Some("tokens"));
+    /// ```
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Ask the kernel to back chunks of at least 2 MiB with transparent huge
+    /// pages (`MADV_HUGEPAGE`), which reduces TLB misses in huge arenas.
+    ///
+    /// Only available on Linux, with the `mmap` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u64>::builder()
+    ///     .initial_capacity(1 << 20)
+    ///     .huge_pages(true)
+    ///     .build();
+    /// # arena.alloc(1);
+    /// ```
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    pub fn huge_pages(mut self, enabled: bool) -> Self {
+        self.advice.huge_pages = enabled;
+        self
+    }
+
+    /// Hand the memory of the chunks emptied by [`Arena::clear`] and
+    /// [`Arena::reset`] back to the kernel (`MADV_DONTNEED`).
+    ///
+    /// The chunks are still kept for reuse, but use no physical memory
+    /// until they are filled again.
+    ///
+    /// Only available on Linux, with the `mmap` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::<u64>::builder()
+    ///     .release_cleared_chunks(true)
+    ///     .build();
+    /// arena.alloc_extend(0..1_000_000);
+    /// arena.clear();
+    /// assert!(arena.capacity() >= 1_000_000);
+    /// ```
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    pub fn release_cleared_chunks(mut self, enabled: bool) -> Self {
+        self.advice.release_emptied = enabled;
+        self
+    }
+
+    /// Take new chunks from a [`ChunkRecycler`] shared with other arenas
+    /// when it has a large enough one, before asking the allocator.
+    ///
+    /// The first chunk, if [`initial_capacity`][Self::initial_capacity] is
+    /// set, is always allocated.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use typed_arena::{Arena, ChunkRecycler};
+    ///
+    /// let recycler = Arc::new(ChunkRecycler::new());
+    /// recycler.recycle(Arena::<u64>::with_capacity(1000));
+    ///
+    /// let arena = Arena::<i64>::builder()
+    ///     .chunk_recycler(recycler.clone())
+    ///     .build();
+    /// arena.alloc(1);
+    /// assert_eq!(arena.capacity(), 1000);
+    /// assert_eq!(recycler.cached_bytes(), 0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn chunk_recycler(mut self, recycler: Arc<ChunkRecycler>) -> Self {
+        self.recycler = Some(recycler);
+        self
+    }
+
+    /// Create the configured arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ArenaBuilder;
+    ///
+    /// let arena = ArenaBuilder::new().initial_capacity(1337).build();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn build(self) -> Arena<T> {
+        let mut arena = Arena::new();
+        let chunks = arena.chunks.get_mut();
+        chunks.max_chunk_size = self.max_chunk_size;
+        chunks.growth = self.growth.map(AssertUnwindSafe);
+        chunks.alloc_error_handler = self.alloc_error_handler.map(AssertUnwindSafe);
+        chunks.label = self.label;
+        #[cfg(feature = "std")]
+        {
+            chunks.recycler = self.recycler.map(Recycler::new);
+        }
+        // Like `Arena::with_capacity`, once the label is set.
+        if let Some(n) = self.initial_capacity {
+            chunks.current = Vec::with_capacity(cmp::max(MIN_CAPACITY, n));
+            #[cfg(any(feature = "metrics", feature = "tracing"))]
+            chunks.record_alloc(0);
+        }
+        #[cfg(all(feature = "mmap", target_os = "linux"))]
+        {
+            chunks.advice = self.advice;
+            chunks.advise_current();
+        }
+        arena
+    }
+}
+
+impl<T> Default for ArenaBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,613 @@
+use crate::rc::RcBox;
+#[cfg(feature = "allocator_api")]
+use crate::AllocError;
+use crate::{Arena, ArenaBox, ArenaRc, SubArena};
+
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::fmt;
+use core::iter;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ptr::{self, NonNull};
+use core::slice;
+use core::str;
+
+/// An arena of values of any type, placed one after the other in untyped
+/// chunks of memory.
+///
+/// Where an [`Arena`] holds values of a single type, a `Bump` can mix many
+/// types, such as the different kinds of nodes of a syntax tree. Each
+/// allocation is just a bump of the end of the current chunk, past whatever
+/// padding the value's alignment requires.
+///
+/// The values are **never dropped**: dropping the `Bump` frees their memory
+/// without running their destructors. It is best suited to types that have
+/// none, like `Copy` types and references into the bump itself.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Bump;
+///
+/// enum Expr<'a> {
+///     Number(f64),
+///     Name(&'a str),
+///     Add(&'a Expr<'a>, &'a Expr<'a>),
+/// }
+///
+/// let bump = Bump::new();
+/// let x = bump.alloc(Expr::Name(bump.alloc_str("x")));
+/// let one = bump.alloc(Expr::Number(1.0));
+/// let sum = bump.alloc(Expr::Add(x, one));
+/// let lengths = bump.alloc_slice_copy(&[1_u16, 2, 3]);
+/// # let _ = (sum, lengths);
+/// ```
+pub struct Bump {
+    pub(crate) bytes: Arena<MaybeUninit<u8>>,
+}
+
+impl Bump {
+    /// Construct a new bump arena.
+    ///
+    /// Like [`Arena::new`], this doesn't allocate until the first value is
+    /// allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// # bump.alloc(1);
+    /// ```
+    pub const fn new() -> Bump {
+        Bump {
+            bytes: Arena::new(),
+        }
+    }
+
+    /// Construct a new bump arena with `bytes` bytes pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::with_capacity(4096);
+    /// assert_eq!(bump.allocated_bytes(), 4096);
+    /// ```
+    pub fn with_capacity(bytes: usize) -> Bump {
+        Bump {
+            bytes: Arena::with_capacity(bytes),
+        }
+    }
+
+    /// Allocates a value in the bump arena, and returns a mutable reference
+    /// to that value.
+    ///
+    /// The value will not be dropped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let byte = bump.alloc(1_u8);
+    /// let float = bump.alloc(2.5_f64);
+    /// *byte += 1;
+    /// assert_eq!((*byte, *float), (2, 2.5));
+    /// ```
+    #[inline]
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        let ptr = self.alloc_layout(Layout::new::<T>()).cast::<T>();
+        unsafe {
+            ptr::write(ptr.as_ptr(), value);
+            &mut *ptr.as_ptr()
+        }
+    }
+
+    /// Allocates a value in the bump arena, and returns an [`ArenaBox`]
+    /// that drops the value when it is dropped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let mut names = bump.alloc_box(vec!["a"]);
+    /// names.push("b");
+    /// assert_eq!(*names, ["a", "b"]);
+    /// ```
+    pub fn alloc_box<T>(&self, value: T) -> ArenaBox<'_, T> {
+        // Nothing else refers to the value, and the bump never drops it.
+        unsafe { ArenaBox::from_mut(self.alloc(value)) }
+    }
+
+    /// Allocates a value in the bump arena, and returns an [`ArenaRc`] to it.
+    /// The value is dropped with the last clone of the pointer.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaRc, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let shared = bump.alloc_rc([1, 2, 3]);
+    /// let other = ArenaRc::clone(&shared);
+    /// assert_eq!(other[1], 2);
+    /// ```
+    pub fn alloc_rc<T>(&self, value: T) -> ArenaRc<'_, T> {
+        // The count starts at one for the pointer, and the bump never drops
+        // the value.
+        unsafe { ArenaRc::from_box(self.alloc(RcBox::new(value))) }
+    }
+
+    /// Copies a slice into the bump arena, and returns a mutable reference
+    /// to the copy.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let primes = bump.alloc_slice_copy(&[2_u32, 3, 5, 7]);
+    /// primes[0] = 1;
+    /// assert_eq!(primes, [1, 3, 5, 7]);
+    /// ```
+    pub fn alloc_slice_copy<T: Copy>(&self, src: &[T]) -> &mut [T] {
+        // `src` already exists, so its layout is valid.
+        let layout = Layout::for_value(src);
+        let dst = self.alloc_layout(layout).cast::<T>().as_ptr();
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+            slice::from_raw_parts_mut(dst, src.len())
+        }
+    }
+
+    /// Allocates a string slice in the bump arena, and returns a mutable
+    /// reference to it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let hello = bump.alloc_str("Hello world");
+    /// assert_eq!(hello, "Hello world");
+    /// ```
+    pub fn alloc_str(&self, s: &str) -> &mut str {
+        let bytes = self.alloc_slice_copy(s.as_bytes());
+        // Can't fail the utf8 validation, it already came in as utf8
+        unsafe { str::from_utf8_unchecked_mut(bytes) }
+    }
+
+    /// Allocates a slice of `len` values in the bump arena, each created by
+    /// calling `f` with its index, and returns a mutable reference to it.
+    ///
+    /// Like other values in the bump, the values will not be dropped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the slice would be larger than `isize::MAX` bytes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let names = bump.alloc_slice_fill_with(3, |i| ["a", "b", "c"][i]);
+    /// assert_eq!(names, ["a", "b", "c"]);
+    /// ```
+    pub fn alloc_slice_fill_with<T, F>(&self, len: usize, f: F) -> &mut [T]
+    where
+        F: FnMut(usize) -> T,
+    {
+        let layout = Layout::array::<T>(len).expect("slice is too large");
+        let dst = self.alloc_layout(layout).cast::<T>().as_ptr();
+        unsafe {
+            fill(dst, len, f);
+            slice::from_raw_parts_mut(dst, len)
+        }
+    }
+
+    /// Clones a slice into the bump arena, and returns a mutable reference
+    /// to the clone.
+    ///
+    /// Like other values in the bump, the clones will not be dropped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let words: [Cow<str>; 2] = [Cow::Borrowed("a"), Cow::Borrowed("b")];
+    /// let copy = bump.alloc_slice_clone(&words);
+    /// assert_eq!(copy, words);
+    /// ```
+    pub fn alloc_slice_clone<T: Clone>(&self, src: &[T]) -> &mut [T] {
+        self.alloc_slice_fill_with(src.len(), |i| src[i].clone())
+    }
+
+    /// Allocates a [`HeaderSlice`], an unsized value made of `header` and a
+    /// trailing slice of `len` values created by calling `f` with their
+    /// index, and returns a mutable reference to it.
+    ///
+    /// The whole value is a single allocation, with no pointer from the
+    /// header to the slice. Like other values in the bump, it will not be
+    /// dropped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the value would be larger than `isize::MAX` bytes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Bump, HeaderSlice};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Kind {
+    ///     Call,
+    ///     Index,
+    /// }
+    ///
+    /// let bump = Bump::new();
+    /// let args = [1_u32, 2, 3];
+    /// let node: &HeaderSlice<Kind, u32> =
+    ///     bump.alloc_header_slice(Kind::Call, args.len(), |i| args[i] * 10);
+    /// assert_eq!(node.header, Kind::Call);
+    /// assert_eq!(node.slice, [10, 20, 30]);
+    /// assert_eq!(std::mem::size_of_val(node), 16);
+    /// ```
+    pub fn alloc_header_slice<H, T, F>(&self, header: H, len: usize, f: F) -> &mut HeaderSlice<H, T>
+    where
+        F: FnMut(usize) -> T,
+    {
+        let (layout, offset) = Layout::array::<T>(len)
+            .and_then(|slice| Layout::new::<H>().extend(slice))
+            .expect("value is too large");
+        let start = self.alloc_layout(layout.pad_to_align()).as_ptr();
+        unsafe {
+            let values = start.add(offset) as *mut T;
+            fill(values, len, f);
+            ptr::write(start as *mut H, header);
+            // The cast keeps the address and the length of the slice.
+            let value = ptr::slice_from_raw_parts_mut(start as *mut T, len);
+            &mut *(value as *mut HeaderSlice<H, T>)
+        }
+    }
+
+    /// Allocates `len` zeroed bytes whose address is a multiple of `align`,
+    /// and returns a mutable reference to them.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// bump.alloc(1_u8);
+    /// let buffer = bump.alloc_bytes_aligned(100, 32);
+    /// assert_eq!(buffer.as_ptr() as usize % 32, 0);
+    /// assert!(buffer.iter().all(|&b| b == 0));
+    /// ```
+    pub fn alloc_bytes_aligned(&self, len: usize, align: usize) -> &mut [u8] {
+        let layout = Layout::from_size_align(len, align).expect("invalid size or alignment");
+        let ptr = self.alloc_layout(layout).as_ptr();
+        unsafe {
+            ptr.write_bytes(0, len);
+            slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+
+    /// Returns the number of bytes of memory allocated for the chunks of
+    /// the bump arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::with_capacity(16);
+    /// bump.alloc(0_u8);
+    /// assert_eq!(bump.allocated_bytes(), 16);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        self.bytes.allocated_bytes()
+    }
+
+    /// Returns the number of bytes used by values, including the padding
+    /// between them.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// bump.alloc(0_u32);
+    /// bump.alloc(0_u8);
+    /// assert!(bump.used_bytes() >= 5);
+    /// ```
+    pub fn used_bytes(&self) -> usize {
+        self.bytes.used_bytes()
+    }
+
+    /// Allocates an uninitialized block of memory with the size and
+    /// alignment of `layout`, and returns a pointer to its start.
+    ///
+    /// The block stays valid for as long as the bump arena lives, and is
+    /// never touched by the arena again. This is useful for FFI buffers and
+    /// type-erased values. Zero-sized layouts get a dangling, well-aligned
+    /// pointer.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::alloc::Layout;
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let layout = Layout::from_size_align(24, 8).unwrap();
+    /// let block = bump.alloc_layout(layout);
+    /// assert_eq!(block.as_ptr() as usize % 8, 0);
+    /// unsafe { block.as_ptr().write_bytes(0xFF, 24) };
+    /// ```
+    pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        alloc_layout_in(&self.bytes, layout)
+    }
+
+    /// Runs `f` with a [`BumpScope`] that allocates values of type `U` in
+    /// the memory of the bump arena, and rolls them back when `f` returns
+    /// or panics.
+    ///
+    /// Unlike the other values of the bump, the values of the scope are
+    /// dropped, and their memory is reused by the next allocations. `U`
+    /// doesn't have to be a type the bump otherwise holds, so scratch
+    /// buffers of any type can borrow from the long-lived values of the
+    /// bump. While the scope is alive, the bump cannot allocate.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the bump already runs a scope.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// let words: Vec<&str> = "b a c".split(' ').map(|w| &*bump.alloc_str(w)).collect();
+    /// let used = bump.used_bytes();
+    ///
+    /// let sorted = bump.scoped::<Vec<&str>, _, _>(|scratch| {
+    ///     let sorted = scratch.alloc(words.clone());
+    ///     sorted.sort();
+    ///     sorted.join(",")
+    /// });
+    /// assert_eq!(sorted, "a,b,c");
+    /// assert_eq!(bump.used_bytes(), used);
+    /// ```
+    pub fn scoped<U, R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&BumpScope<'_, U>) -> R,
+    {
+        let scope = BumpScope {
+            bytes: SubArena::new(&self.bytes),
+            len: Cell::new(0),
+            _marker: PhantomData,
+        };
+        f(&scope)
+    }
+}
+
+// Bumps the end of the current chunk of `bytes` past a block of `layout`.
+fn alloc_layout_in(bytes: &Arena<MaybeUninit<u8>>, layout: Layout) -> NonNull<u8> {
+    if layout.size() == 0 {
+        // A dangling, but well-aligned, pointer.
+        let dangling = ptr::null_mut::<u8>().wrapping_add(layout.align());
+        return unsafe { NonNull::new_unchecked(dangling) };
+    }
+
+    let mut chunks = bytes.chunks.borrow_mut();
+    let mut start =
+        chunks.current.len() + padding(chunks.current.as_ptr(), chunks.current.len(), layout);
+    if start + layout.size() > chunks.current.capacity() {
+        // `Layout` guarantees that this doesn't overflow. Whatever the
+        // alignment of the new chunk, the block fits.
+        chunks.reserve(layout.size() + layout.align() - 1);
+        start = padding(chunks.current.as_ptr(), 0, layout);
+    }
+
+    unsafe {
+        // The bytes are `MaybeUninit`, so they need no initialization.
+        chunks.current.set_len(start + layout.size());
+        // Avoid going through `Vec::deref_mut`, which overlaps
+        // other references we have already handed out!
+        NonNull::new_unchecked(chunks.current.as_mut_ptr().add(start) as *mut u8)
+    }
+}
+
+// Like `alloc_layout_in`, but fails instead of panicking if `bytes` is
+// borrowed, or aborting if it runs out of memory.
+#[cfg(feature = "allocator_api")]
+pub(crate) fn try_alloc_layout_in(
+    bytes: &Arena<MaybeUninit<u8>>,
+    layout: Layout,
+) -> Result<NonNull<u8>, AllocError> {
+    if layout.size() == 0 {
+        let dangling = ptr::null_mut::<u8>().wrapping_add(layout.align());
+        return Ok(unsafe { NonNull::new_unchecked(dangling) });
+    }
+
+    let mut chunks = bytes.chunks.try_borrow_mut().map_err(|_| AllocError)?;
+    let mut start =
+        chunks.current.len() + padding(chunks.current.as_ptr(), chunks.current.len(), layout);
+    if start + layout.size() > chunks.current.capacity() {
+        chunks.try_reserve(layout.size() + layout.align() - 1)?;
+        start = padding(chunks.current.as_ptr(), 0, layout);
+    }
+
+    unsafe {
+        chunks.current.set_len(start + layout.size());
+        Ok(NonNull::new_unchecked(
+            chunks.current.as_mut_ptr().add(start) as *mut u8,
+        ))
+    }
+}
+
+// The padding needed after the first `len` bytes of the chunk at `chunk` to
+// align a block of `layout`. This takes a pointer, not a slice, so as not to
+// reborrow the bytes already handed out.
+fn padding(chunk: *const MaybeUninit<u8>, len: usize, layout: Layout) -> usize {
+    let end = chunk as usize + len;
+    end.wrapping_neg() & (layout.align() - 1)
+}
+
+/// A scope of a [`Bump`] that allocates and drops values of type `U`,
+/// created by [`Bump::scoped`].
+///
+/// The values are placed in the chunks of the bump, after its own values,
+/// and only live as long as the scope: when it ends, they are dropped, and
+/// the bump reuses their memory, like with a [`SubArena`].
+pub struct BumpScope<'a, U> {
+    bytes: SubArena<'a, MaybeUninit<u8>>,
+    // The number of values allocated.
+    len: Cell<usize>,
+    _marker: PhantomData<U>,
+}
+
+impl<'a, U> BumpScope<'a, U> {
+    /// Allocates a value in the scope, and returns a mutable reference to
+    /// that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Bump;
+    ///
+    /// let bump = Bump::new();
+    /// bump.scoped(|scope| {
+    ///     let x = scope.alloc(String::from("x"));
+    ///     x.push('y');
+    ///     assert_eq!(x, "xy");
+    ///     assert_eq!(scope.len(), 1);
+    /// });
+    /// ```
+    pub fn alloc(&self, value: U) -> &mut U {
+        let ptr = alloc_layout_in(&self.bytes, Layout::new::<U>()).cast::<U>();
+        unsafe {
+            ptr::write(ptr.as_ptr(), value);
+            self.len.set(self.len.get() + 1);
+            &mut *ptr.as_ptr()
+        }
+    }
+
+    /// Return the number of values allocated in the scope.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if no value was allocated in the scope.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, U> Drop for BumpScope<'a, U> {
+    fn drop(&mut self) {
+        if !mem::needs_drop::<U>() {
+            return;
+        }
+        let layout = Layout::new::<U>();
+        if layout.size() == 0 {
+            for _ in 0..self.len.get() {
+                unsafe { ptr::drop_in_place(NonNull::<U>::dangling().as_ptr()) };
+            }
+            return;
+        }
+
+        // Only values of type `U` were allocated, so they follow one
+        // another in each chunk, after the padding in front of the first.
+        let mut start = self.bytes.parent_len();
+        let mut chunks = self.bytes.chunks.borrow_mut();
+        let chunks = &mut *chunks;
+        for chunk in chunks
+            .rest
+            .iter_mut()
+            .chain(iter::once(&mut chunks.current))
+        {
+            let first = start + padding(chunk.as_ptr(), start, layout);
+            if chunk.len() > first {
+                let len = (chunk.len() - first) / layout.size();
+                unsafe {
+                    let values = chunk.as_mut_ptr().add(first) as *mut U;
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(values, len));
+                }
+            }
+            start = 0;
+        }
+    }
+}
+
+impl<'a, U> fmt::Debug for BumpScope<'a, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BumpScope")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// A value made of a header followed by a slice, like the nodes of a syntax
+/// tree with their children inline.
+///
+/// It is unsized, so it lives behind a reference, as returned by
+/// [`Bump::alloc_header_slice`].
+#[derive(Debug)]
+#[repr(C)]
+pub struct HeaderSlice<H, T> {
+    /// The fixed-size part of the value.
+    pub header: H,
+    /// The trailing slice.
+    pub slice: [T],
+}
+
+// Writes `len` values returned by `f` from `dst` on.
+unsafe fn fill<T, F>(dst: *mut T, len: usize, mut f: F)
+where
+    F: FnMut(usize) -> T,
+{
+    for i in 0..len {
+        ptr::write(dst.add(i), f(i));
+    }
+}
+
+impl Default for Bump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.bytes.chunks.try_borrow().is_err() {
+            return f.debug_struct("Bump").finish_non_exhaustive();
+        }
+        f.debug_struct("Bump")
+            .field("chunk_count", &self.bytes.chunk_count())
+            .field("used_bytes", &self.used_bytes())
+            .finish()
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{INITIAL_SIZE, MIN_CAPACITY};
+
+use std::cmp;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// A lock-free arena that can be shared between threads.
+///
+/// Threads bump-allocate from the current chunk with atomic operations,
+/// and never wait for each other: when the chunk is full, the first thread
+/// to install a new one wins and the others retry in it. Chunks are linked
+/// together, and only freed with the arena.
+///
+/// Unlike [`MutexArena`][crate::MutexArena], allocating never blocks, which
+/// suits many threads allocating small values at once.
+///
+/// Only available with the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use typed_arena::ConcurrentArena;
+///
+/// let arena = Arc::new(ConcurrentArena::new());
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let arena = Arc::clone(&arena);
+///         thread::spawn(move || *arena.alloc(i) * 2)
+///     })
+///     .collect();
+/// let sum: i32 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+/// assert_eq!(sum, 12);
+/// assert_eq!(arena.len(), 4);
+/// ```
+pub struct ConcurrentArena<T> {
+    // The newest chunk, which links to the older ones. Null until the
+    // first allocation.
+    current: AtomicPtr<Chunk<T>>,
+}
+
+struct Chunk<T> {
+    // Only the capacity is used, the length stays zero until the chunk is
+    // dropped.
+    storage: Vec<T>,
+    start: *mut T,
+    // The number of slots claimed, which are all initialized: a thread
+    // only claims the slots it is about to write.
+    len: AtomicUsize,
+    previous: *mut Chunk<T>,
+}
+
+// The arena owns its values, like a `Vec<T>`, and hands out each of them
+// to only one thread.
+unsafe impl<T: Send> Send for ConcurrentArena<T> {}
+unsafe impl<T: Send> Sync for ConcurrentArena<T> {}
+
+impl<T> Chunk<T> {
+    fn new(capacity: usize, previous: *mut Chunk<T>) -> Box<Chunk<T>> {
+        let mut storage = Vec::with_capacity(capacity);
+        Box::new(Chunk {
+            start: storage.as_mut_ptr(),
+            storage,
+            len: AtomicUsize::new(0),
+            previous,
+        })
+    }
+
+    fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    // Claims `n` consecutive slots, and returns a pointer to the first one.
+    fn claim(&self, n: usize) -> Option<*mut T> {
+        let mut len = self.len.load(Ordering::Relaxed);
+        loop {
+            if self.capacity() - len < n {
+                return None;
+            }
+            match self
+                .len
+                .compare_exchange_weak(len, len + n, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => return Some(unsafe { self.start.add(len) }),
+                Err(actual) => len = actual,
+            }
+        }
+    }
+}
+
+impl<T> ConcurrentArena<T> {
+    /// Construct a new concurrent arena.
+    ///
+    /// This doesn't allocate until the first value is allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ConcurrentArena;
+    ///
+    /// let arena = ConcurrentArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub const fn new() -> ConcurrentArena<T> {
+        ConcurrentArena {
+            current: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Allocates a value in the arena, and returns a mutable reference
+    /// to that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ConcurrentArena;
+    ///
+    /// let arena = ConcurrentArena::new();
+    /// let x = arena.alloc(42);
+    /// assert_eq!(*x, 42);
+    /// ```
+    pub fn alloc(&self, value: T) -> &mut T {
+        unsafe {
+            let slot = self.claim(1);
+            ptr::write(slot, value);
+            &mut *slot
+        }
+    }
+
+    /// Uses the contents of an iterator to allocate values in the arena.
+    /// Returns a mutable slice that contains these values.
+    ///
+    /// The values are collected before being moved into the arena, so the
+    /// iterator may allocate from this same arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ConcurrentArena;
+    ///
+    /// let arena = ConcurrentArena::new();
+    /// let abc = arena.alloc_extend("abcdefg".chars().take(3));
+    /// assert_eq!(abc, ['a', 'b', 'c']);
+    /// ```
+    pub fn alloc_extend<I>(&self, iterable: I) -> &mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut values: Vec<T> = iterable.into_iter().collect();
+        let len = values.len();
+        if len == 0 {
+            return &mut [];
+        }
+        unsafe {
+            let start = self.claim(len);
+            ptr::copy_nonoverlapping(values.as_ptr(), start, len);
+            values.set_len(0);
+            std::slice::from_raw_parts_mut(start, len)
+        }
+    }
+
+    // Claims `n` consecutive slots, installing a new chunk if needed.
+    fn claim(&self, n: usize) -> *mut T {
+        let mut current = self.current.load(Ordering::Acquire);
+        loop {
+            let capacity = match unsafe { current.as_ref() } {
+                Some(chunk) => match chunk.claim(n) {
+                    Some(slot) => return slot,
+                    None => chunk.capacity().checked_mul(2).expect("capacity overflow"),
+                },
+                None => cmp::max(
+                    INITIAL_SIZE / cmp::max(mem::size_of::<T>(), 1),
+                    MIN_CAPACITY,
+                ),
+            };
+            let chunk = Chunk::new(cmp::max(capacity, n), current);
+            // Claim the slots before anyone else can see the chunk.
+            chunk.len.store(n, Ordering::Relaxed);
+            let new = Box::into_raw(chunk);
+            match self
+                .current
+                .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return unsafe { (*new).start },
+                Err(actual) => {
+                    // Another thread installed a chunk first: retry in it.
+                    drop(unsafe { Box::from_raw(new) });
+                    current = actual;
+                }
+            }
+        }
+    }
+
+    /// Return the number of values allocated in the arena.
+    ///
+    /// While other threads are allocating, this is only a snapshot.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ConcurrentArena;
+    ///
+    /// let arena = ConcurrentArena::new();
+    /// arena.alloc_extend(0..3);
+    /// arena.alloc(3);
+    /// assert_eq!(arena.len(), 4);
+    /// ```
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        let mut chunk = self.current.load(Ordering::Acquire);
+        while let Some(c) = unsafe { chunk.as_ref() } {
+            len += c.len.load(Ordering::Relaxed);
+            chunk = c.previous;
+        }
+        len
+    }
+
+    /// Returns `true` if no values have been allocated in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Convert this `ConcurrentArena` into a `Vec<T>`.
+    ///
+    /// Items in the resulting `Vec<T>` appear in allocation order for the
+    /// values allocated from one thread, but the values of different
+    /// threads are interleaved.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ConcurrentArena;
+    ///
+    /// let arena = ConcurrentArena::new();
+    /// arena.alloc("a");
+    /// arena.alloc("b");
+    /// assert_eq!(arena.into_vec(), ["a", "b"]);
+    /// ```
+    pub fn into_vec(mut self) -> Vec<T> {
+        let mut chunks = Vec::new();
+        let mut chunk = mem::replace(self.current.get_mut(), ptr::null_mut());
+        while !chunk.is_null() {
+            let mut c = unsafe { Box::from_raw(chunk) };
+            chunk = c.previous;
+            unsafe {
+                let len = *c.len.get_mut();
+                c.storage.set_len(len);
+            }
+            chunks.push(mem::take(&mut c.storage));
+        }
+        let mut vec = Vec::with_capacity(chunks.iter().map(Vec::len).sum());
+        for chunk in chunks.into_iter().rev() {
+            vec.extend(chunk);
+        }
+        vec
+    }
+}
+
+impl<T> Drop for ConcurrentArena<T> {
+    fn drop(&mut self) {
+        let mut chunk = *self.current.get_mut();
+        while !chunk.is_null() {
+            let mut c = unsafe { Box::from_raw(chunk) };
+            chunk = c.previous;
+            // Drops the values along with the chunk.
+            unsafe {
+                let len = *c.len.get_mut();
+                c.storage.set_len(len);
+            }
+        }
+    }
+}
+
+impl<T> Default for ConcurrentArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ConcurrentArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConcurrentArena")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,218 @@
+use crate::Arena;
+#[cfg(feature = "allocator_api")]
+use crate::{Allocator, Global};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+/// A reference to a value of an arena that may not be initialized yet.
+///
+/// [`Arena::alloc_cyclic`] gives one to its closure before the value
+/// exists. It can be copied into the value itself, or into other values
+/// allocated meanwhile, and it resolves to the value once that is
+/// initialized.
+pub struct Cyclic<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone = Global> {
+    arena: &'a in_alloc!(Arena<T>),
+    value: NonNull<T>,
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Cyclic<'a, T>) {
+    /// Returns a reference to the value, or `None` while it is being
+    /// constructed.
+    ///
+    /// It stays `None` if the construction panicked.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, Cyclic};
+    ///
+    /// struct Node<'a> {
+    ///     this: Cyclic<'a, Node<'a>>,
+    ///     ready: bool,
+    /// }
+    ///
+    /// let arena = Arena::new();
+    /// let node = arena.alloc_cyclic(|this| {
+    ///     assert!(this.get().is_none());
+    ///     Node { this, ready: true }
+    /// });
+    /// assert!(node.this.get().unwrap().ready);
+    /// ```
+    pub fn get(self) -> Option<&'a T> {
+        let address = self.value.as_ptr() as usize;
+        let chunks = self.arena.chunks.borrow();
+        if chunks.pending.contains(&address) || chunks.abandoned.contains(&address) {
+            None
+        } else {
+            // Initialized, and only ever shared.
+            Some(unsafe { &*self.value.as_ptr() })
+        }
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> Clone
+    for in_alloc!(Cyclic<'a, T>)
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> Copy
+    for in_alloc!(Cyclic<'a, T>)
+{
+}
+
+impl<'a, T: fmt::Debug, #[cfg(feature = "allocator_api")] A: Allocator + Clone> fmt::Debug
+    for in_alloc!(Cyclic<'a, T>)
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("Cyclic").field(value).finish(),
+            None => f.write_str("Cyclic(<uninit>)"),
+        }
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Allocates a value that refers to itself, or is referred to by
+    /// values allocated while it is being built.
+    ///
+    /// Like `Rc::new_cyclic`, the slot of the value is allocated first, and
+    /// `f` gets a [`Cyclic`] reference to it, which it can store in the
+    /// value. `f` may allocate other values in the arena meanwhile, such as
+    /// the children of a node that point back to their parent.
+    ///
+    /// The value is only shared once it is built, so this returns `&T`.
+    ///
+    /// If `f` panics, the slot is never initialized. The values allocated
+    /// before it are kept, but those that `f` allocated after it in the same
+    /// chunk are leaked: they are never dropped, and are no longer counted
+    /// by the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, Cyclic};
+    ///
+    /// struct Node<'a> {
+    ///     name: &'static str,
+    ///     parent: Option<Cyclic<'a, Node<'a>>>,
+    ///     children: Vec<&'a Node<'a>>,
+    /// }
+    ///
+    /// let arena = Arena::new();
+    /// let root = arena.alloc_cyclic(|root| Node {
+    ///     name: "root",
+    ///     parent: None,
+    ///     children: vec![arena.alloc(Node {
+    ///         name: "child",
+    ///         parent: Some(root),
+    ///         children: Vec::new(),
+    ///     })],
+    /// });
+    ///
+    /// let child = root.children[0];
+    /// assert_eq!(child.parent.unwrap().get().unwrap().name, "root");
+    /// ```
+    pub fn alloc_cyclic<'a, F>(&'a self, f: F) -> &'a T
+    where
+        F: FnOnce(in_alloc!(Cyclic<'a, T>)) -> T,
+    {
+        let slot = self.alloc_slot();
+        let value = slot.value;
+        let init = f(Cyclic { arena: self, value });
+        slot.fill(init)
+    }
+
+    // Allocates the slot of a value that is not built yet, and releases the
+    // arena, so that other values can be allocated after it meanwhile.
+    pub(crate) fn alloc_slot(&self) -> in_alloc!(Slot<'_, T>) {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.current.len() == chunks.current.capacity() {
+            chunks.reserve(1);
+        }
+        let len = chunks.current.len();
+        let value = unsafe {
+            // The slot counts as allocated, so that other values go after
+            // it, but it is pending until it is initialized.
+            chunks.current.set_len(len + 1);
+            NonNull::new_unchecked(chunks.current.as_mut_ptr().add(len))
+        };
+        let address = value.as_ptr() as usize;
+        // The slot of a value that failed to build may be reused.
+        chunks.abandoned.retain(|&a| a != address);
+        chunks.pending.push(address);
+        Slot { arena: self, value }
+    }
+}
+
+// A slot allocated by `alloc_slot`. If it is dropped instead of filled,
+// because building the value panicked, the slot is given back, and the
+// `Cyclic` references to it stay `None`.
+pub(crate) struct Slot<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone = Global> {
+    arena: &'a in_alloc!(Arena<T>),
+    value: NonNull<T>,
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Slot<'a, T>) {
+    // Initializes the slot with `value`.
+    pub(crate) fn fill(self, value: T) -> &'a mut T {
+        let this = mem::ManuallyDrop::new(self);
+        let slot = this.value.as_ptr();
+        unsafe { ptr::write(slot, value) };
+        let address = slot as usize;
+        let mut chunks = this.arena.chunks.borrow_mut();
+        let index = chunks.pending.iter().rposition(|&a| a == address).unwrap();
+        chunks.pending.swap_remove(index);
+        unsafe { &mut *slot }
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> Drop
+    for in_alloc!(Slot<'a, T>)
+{
+    fn drop(&mut self) {
+        let mut chunks = self.arena.chunks.borrow_mut();
+        let address = self.value.as_ptr() as usize;
+        if let Some(index) = chunks.pending.iter().rposition(|&a| a == address) {
+            chunks.pending.swap_remove(index);
+        }
+        chunks.abandoned.push(address);
+        let size = mem::size_of::<T>();
+        if size == 0 {
+            // Zero-sized values are all alike, so any slot will do.
+            let len = chunks.current.len();
+            unsafe { chunks.current.set_len(len - 1) };
+            return;
+        }
+        let holds_slot = |chunk: &in_alloc!(Vec<T>)| {
+            let start = chunk.as_ptr() as usize;
+            address >= start && address < start + chunk.len() * size
+        };
+        let in_current = holds_slot(&chunks.current);
+        let chunk = if in_current {
+            &mut chunks.current
+        } else if let Some(i) = chunks.rest.iter().position(holds_slot) {
+            &mut chunks.rest[i]
+        } else {
+            return;
+        };
+        let index = (address - chunk.as_ptr() as usize) / size;
+        let is_last = index + 1 == chunk.len();
+        // The values after the slot may still be borrowed, so they are
+        // leaked along with it, but those before it are kept.
+        unsafe { chunk.set_len(index) };
+        if in_current && !is_last {
+            // Nothing may be allocated over the leaked values.
+            let empty = chunks.empty_chunk();
+            let chunk = mem::replace(&mut chunks.current, empty);
+            chunks.rest.push(chunk);
+        }
+    }
+}
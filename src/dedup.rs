@@ -0,0 +1,190 @@
+use crate::Arena;
+
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// An arena that allocates each distinct value once.
+///
+/// Allocating a value equal to one already in the arena drops the new one,
+/// and returns a reference to the old one, so equal values share an
+/// address. This is hash-consing: for example, a type checker can compare
+/// interned types by pointer. The values are found through a hash set, and
+/// only shared references are handed out. Only available with the `std`
+/// feature.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::DedupArena;
+///
+/// #[derive(Hash, PartialEq, Eq)]
+/// enum Type<'a> {
+///     Int,
+///     Ref(&'a Type<'a>),
+/// }
+///
+/// let types = DedupArena::new();
+/// let int = types.alloc(Type::Int);
+/// let a = types.alloc(Type::Ref(int));
+/// let b = types.alloc(Type::Ref(types.alloc(Type::Int)));
+/// assert!(std::ptr::eq(a, b));
+/// assert_eq!(types.len(), 2);
+/// ```
+pub struct DedupArena<T> {
+    // Point into `values`, which never moves or drops them while the
+    // arena lives.
+    index: RefCell<HashSet<Shared<T>>>,
+    slices: RefCell<HashSet<SharedSlice<T>>>,
+    values: Arena<T>,
+}
+
+// The arena owns its values, like a `Vec<T>`.
+unsafe impl<T: Send> Send for DedupArena<T> {}
+
+struct Shared<T>(*const T);
+
+impl<T> Borrow<T> for Shared<T> {
+    fn borrow(&self) -> &T {
+        unsafe { &*self.0 }
+    }
+}
+
+impl<T: Hash> Hash for Shared<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Borrow::<T>::borrow(self).hash(state)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Borrow::<T>::borrow(self) == Borrow::<T>::borrow(other)
+    }
+}
+
+impl<T: Eq> Eq for Shared<T> {}
+
+struct SharedSlice<T>(*const [T]);
+
+impl<T> Borrow<[T]> for SharedSlice<T> {
+    fn borrow(&self) -> &[T] {
+        unsafe { &*self.0 }
+    }
+}
+
+impl<T: Hash> Hash for SharedSlice<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Borrow::<[T]>::borrow(self).hash(state)
+    }
+}
+
+impl<T: PartialEq> PartialEq for SharedSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Borrow::<[T]>::borrow(self) == Borrow::<[T]>::borrow(other)
+    }
+}
+
+impl<T: Eq> Eq for SharedSlice<T> {}
+
+impl<T: Hash + Eq> DedupArena<T> {
+    /// Construct a new deduplicating arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DedupArena;
+    ///
+    /// let arena = DedupArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> DedupArena<T> {
+        DedupArena {
+            index: RefCell::new(HashSet::new()),
+            slices: RefCell::new(HashSet::new()),
+            values: Arena::new(),
+        }
+    }
+
+    /// Returns a reference to the value in the arena equal to `value`,
+    /// allocating `value` if there is none.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DedupArena;
+    ///
+    /// let arena = DedupArena::new();
+    /// let a = arena.alloc(String::from("x"));
+    /// let b = arena.alloc(String::from("x"));
+    /// assert!(std::ptr::eq(a, b));
+    /// ```
+    pub fn alloc(&self, value: T) -> &T {
+        if let Some(existing) = self.get(&value) {
+            return existing;
+        }
+        let allocated: &T = self.values.alloc(value);
+        self.index.borrow_mut().insert(Shared(allocated));
+        allocated
+    }
+
+    /// Returns a reference to the value in the arena equal to `value`, or
+    /// `None` if there is none.
+    pub fn get(&self, value: &T) -> Option<&T> {
+        let index = self.index.borrow();
+        index.get(value).map(|shared| unsafe { &*shared.0 })
+    }
+
+    /// Return the number of distinct values allocated with
+    /// [`alloc`][DedupArena::alloc].
+    pub fn len(&self) -> usize {
+        self.index.borrow().len()
+    }
+
+    /// Returns `true` if no value was allocated.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0 && self.slices.borrow().is_empty()
+    }
+}
+
+impl<T: Hash + Eq + Clone> DedupArena<T> {
+    /// Returns a reference to the slice in the arena equal to `values`,
+    /// cloning `values` into the arena if there is none.
+    ///
+    /// Slices are deduplicated separately from single values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DedupArena;
+    ///
+    /// let arena = DedupArena::new();
+    /// let a = arena.alloc_slice(&[1, 2, 3]);
+    /// let b = arena.alloc_slice(&vec![1, 2, 3]);
+    /// assert!(std::ptr::eq(a, b));
+    /// ```
+    pub fn alloc_slice(&self, values: &[T]) -> &[T] {
+        if let Some(existing) = self.slices.borrow().get(values) {
+            return unsafe { &*existing.0 };
+        }
+        let allocated: &[T] = self.values.alloc_extend(values.iter().cloned());
+        self.slices.borrow_mut().insert(SharedSlice(allocated));
+        allocated
+    }
+}
+
+impl<T: Hash + Eq> Default for DedupArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for DedupArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DedupArena")
+            .field("len", &self.index.borrow().len())
+            .field("slices", &self.slices.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,256 @@
+#[cfg(all(feature = "mmap", unix))]
+use crate::mmap::{self, Mapping};
+use crate::{INITIAL_SIZE, MIN_CAPACITY};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cell::{Cell, RefCell};
+use core::cmp;
+use core::mem;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// A leaner arena for values that don't need to be dropped.
+///
+/// Since `T: Copy`, a `DroplessArena` never has to run destructors, and
+/// keeps no per-chunk bookkeeping for them: allocating only compares and
+/// bumps a pointer into the current chunk, without borrowing a `RefCell`.
+/// It is a good fit for tokens, spans and other plain data.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::DroplessArena;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Token {
+///     kind: u8,
+///     start: u32,
+///     len: u32,
+/// }
+///
+/// let tokens = DroplessArena::new();
+/// let first = tokens.alloc(Token { kind: 1, start: 0, len: 3 });
+/// let rest = tokens.alloc_slice_copy(&[Token { kind: 2, start: 4, len: 1 }; 2]);
+/// assert_eq!(first.len + rest[0].len, 4);
+/// ```
+pub struct DroplessArena<T: Copy> {
+    // The free part of the current chunk.
+    ptr: Cell<*mut T>,
+    end: Cell<*mut T>,
+    chunks: RefCell<Vec<Chunk<T>>>,
+    #[cfg(all(feature = "mmap", unix))]
+    mapped: bool,
+}
+
+enum Chunk<T> {
+    // Only the capacity is used, the length stays zero.
+    Heap(Vec<T>),
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(Mapping),
+}
+
+impl<T> Chunk<T> {
+    fn as_mut_ptr(&mut self) -> *mut T {
+        match *self {
+            Chunk::Heap(ref mut vec) => vec.as_mut_ptr(),
+            #[cfg(all(feature = "mmap", unix))]
+            Chunk::Mapped(ref mapping) => mapping.as_ptr() as *mut T,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match *self {
+            Chunk::Heap(ref vec) => vec.capacity(),
+            #[cfg(all(feature = "mmap", unix))]
+            Chunk::Mapped(ref mapping) => mapping.len() / mem::size_of::<T>(),
+        }
+    }
+}
+
+// The arena owns its values, like a `Vec<T>`.
+unsafe impl<T: Copy + Send> Send for DroplessArena<T> {}
+
+impl<T: Copy> DroplessArena<T> {
+    /// Construct a new dropless arena.
+    ///
+    /// This doesn't allocate until the first value is allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DroplessArena;
+    ///
+    /// let arena = DroplessArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> DroplessArena<T> {
+        DroplessArena {
+            ptr: Cell::new(ptr::null_mut()),
+            end: Cell::new(ptr::null_mut()),
+            chunks: RefCell::new(Vec::new()),
+            #[cfg(all(feature = "mmap", unix))]
+            mapped: false,
+        }
+    }
+
+    /// Construct a new dropless arena whose chunks are mapped directly from
+    /// the operating system with `mmap`, instead of coming from the global
+    /// allocator.
+    ///
+    /// This keeps huge arenas out of the malloc heap, and returns their
+    /// memory to the operating system as soon as the arena is dropped.
+    /// Chunks are rounded up to whole pages.
+    ///
+    /// Only available on Unix, with the `mmap` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DroplessArena;
+    ///
+    /// let arena = DroplessArena::new_mapped();
+    /// let samples = arena.alloc_slice_copy(&[0.5_f32; 10_000]);
+    /// assert_eq!(samples.len(), 10_000);
+    /// ```
+    #[cfg(all(feature = "mmap", unix))]
+    pub fn new_mapped() -> DroplessArena<T> {
+        assert!(
+            mem::align_of::<T>() <= mmap::page_size(),
+            "type is aligned to more than a page"
+        );
+        DroplessArena {
+            mapped: true,
+            ..DroplessArena::new()
+        }
+    }
+
+    /// Construct a new dropless arena with capacity for `n` values
+    /// pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DroplessArena;
+    ///
+    /// let arena = DroplessArena::with_capacity(1337);
+    /// # arena.alloc(1);
+    /// ```
+    pub fn with_capacity(n: usize) -> DroplessArena<T> {
+        let arena = DroplessArena::new();
+        arena.push_chunk(Chunk::Heap(Vec::with_capacity(cmp::max(MIN_CAPACITY, n))));
+        arena
+    }
+
+    /// Allocates a value in the arena, and returns a mutable reference to
+    /// that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DroplessArena;
+    ///
+    /// let arena = DroplessArena::new();
+    /// let x = arena.alloc(42);
+    /// *x += 1;
+    /// assert_eq!(*x, 43);
+    /// ```
+    #[inline]
+    pub fn alloc(&self, value: T) -> &mut T {
+        &mut self.alloc_slice_copy(&[value])[0]
+    }
+
+    /// Copies a slice into the arena, and returns a mutable reference to
+    /// the copy.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DroplessArena;
+    ///
+    /// let arena = DroplessArena::new();
+    /// let ranks = arena.alloc_slice_copy(&[3, 1, 2]);
+    /// ranks.sort();
+    /// assert_eq!(ranks, [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn alloc_slice_copy(&self, src: &[T]) -> &mut [T] {
+        if mem::size_of::<T>() == 0 {
+            // Zero-sized values need no memory.
+            let dangling = NonNull::<T>::dangling().as_ptr();
+            return unsafe { slice::from_raw_parts_mut(dangling, src.len()) };
+        }
+
+        if src.len() > self.remaining() {
+            self.grow(src.len());
+        }
+        unsafe {
+            let dst = self.ptr.get();
+            self.ptr.set(dst.add(src.len()));
+            ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+            slice::from_raw_parts_mut(dst, src.len())
+        }
+    }
+
+    /// Returns the number of bytes of memory allocated for the chunks of
+    /// the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DroplessArena;
+    ///
+    /// let arena = DroplessArena::<u32>::with_capacity(10);
+    /// assert_eq!(arena.allocated_bytes(), 40);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        let chunks = self.chunks.borrow();
+        chunks
+            .iter()
+            .map(|chunk| chunk.capacity() * mem::size_of::<T>())
+            .sum()
+    }
+
+    // Room left in the current chunk, which is never called for zero-sized
+    // types.
+    #[inline]
+    fn remaining(&self) -> usize {
+        (self.end.get() as usize - self.ptr.get() as usize) / mem::size_of::<T>()
+    }
+
+    // Starts a new chunk with room for at least `additional` values.
+    #[inline(never)]
+    #[cold]
+    fn grow(&self, additional: usize) {
+        let grown_cap = match self.chunks.borrow().last() {
+            Some(chunk) => chunk.capacity().checked_mul(2).expect("capacity overflow"),
+            None => INITIAL_SIZE / cmp::max(1, mem::size_of::<T>()),
+        };
+        let capacity = cmp::max(grown_cap, additional);
+        #[cfg(all(feature = "mmap", unix))]
+        {
+            if self.mapped {
+                let len = capacity
+                    .checked_mul(mem::size_of::<T>())
+                    .expect("capacity overflow");
+                return self.push_chunk(Chunk::Mapped(Mapping::new(len)));
+            }
+        }
+        self.push_chunk(Chunk::Heap(Vec::with_capacity(capacity)));
+    }
+
+    fn push_chunk(&self, mut chunk: Chunk<T>) {
+        let start = chunk.as_mut_ptr();
+        self.ptr.set(start);
+        self.end.set(unsafe { start.add(chunk.capacity()) });
+        // Moving the chunk leaves its memory in place.
+        self.chunks.borrow_mut().push(chunk);
+    }
+}
+
+impl<T: Copy> Default for DroplessArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
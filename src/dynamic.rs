@@ -0,0 +1,191 @@
+use crate::Bump;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cell::RefCell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+
+/// An arena of values of any type, which are dropped with the arena.
+///
+/// Like a [`Bump`], a `DynArena` mixes values of many types in the same
+/// chunks, but it also remembers the destructor of each value that needs
+/// one, and runs them in allocation order when it is dropped.
+///
+/// The returned `&mut T` coerces to a trait object as usual, so
+/// heterogeneous nodes behind a trait need no `Box` each.
+///
+/// Unlike with an [`Arena`][crate::Arena], the values may only borrow data
+/// that outlives the arena, not each other: the arena does not know their
+/// types when it drops them, so the borrow checker must ensure that no
+/// destructor sees a dropped value.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::DynArena;
+///
+/// trait Shape {
+///     fn area(&self) -> f64;
+/// }
+///
+/// struct Square(f64);
+/// impl Shape for Square {
+///     fn area(&self) -> f64 {
+///         self.0 * self.0
+///     }
+/// }
+///
+/// struct Named(String, f64);
+/// impl Shape for Named {
+///     fn area(&self) -> f64 {
+///         self.1
+///     }
+/// }
+///
+/// let arena = DynArena::new();
+/// let shapes: Vec<&dyn Shape> = vec![
+///     arena.alloc(Square(2.0)),
+///     arena.alloc(Named(String::from("unit"), 1.0)),
+/// ];
+/// assert_eq!(shapes.iter().map(|s| s.area()).sum::<f64>(), 5.0);
+/// // Dropping `arena` drops the `String`.
+/// ```
+pub struct DynArena<'a> {
+    bump: Bump,
+    drops: RefCell<Vec<DropEntry>>,
+    // Invariant, so that the arena can't be coerced to a shorter lifetime
+    // to take values that it would outlive.
+    _marker: PhantomData<fn(&'a ()) -> &'a ()>,
+}
+
+struct DropEntry {
+    value: *mut u8,
+    drop: unsafe fn(*mut u8),
+}
+
+impl Drop for DropEntry {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.value) }
+    }
+}
+
+unsafe fn drop_value<T>(value: *mut u8) {
+    ptr::drop_in_place(value as *mut T);
+}
+
+impl<'a> DynArena<'a> {
+    /// Construct a new dynamic arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DynArena;
+    ///
+    /// let arena = DynArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub const fn new() -> DynArena<'a> {
+        DynArena {
+            bump: Bump::new(),
+            drops: RefCell::new(Vec::new()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Construct a new dynamic arena with `bytes` bytes pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DynArena;
+    ///
+    /// let arena = DynArena::with_capacity(4096);
+    /// # arena.alloc(1);
+    /// ```
+    pub fn with_capacity(bytes: usize) -> DynArena<'a> {
+        DynArena {
+            bump: Bump::with_capacity(bytes),
+            drops: RefCell::new(Vec::new()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allocates a value in the arena, and returns a mutable reference to
+    /// it. The value is dropped with the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::fmt::Display;
+    /// use typed_arena::DynArena;
+    ///
+    /// let arena = DynArena::new();
+    /// let items: [&mut dyn Display; 2] = [arena.alloc(1), arena.alloc("two")];
+    /// assert_eq!(format!("{} {}", items[0], items[1]), "1 two");
+    /// ```
+    pub fn alloc<T: 'a>(&self, value: T) -> &mut T {
+        if !mem::needs_drop::<T>() {
+            return self.bump.alloc(value);
+        }
+        // Make room for the entry first, so that pushing it can't fail once
+        // the value is in the arena.
+        let mut drops = self.drops.borrow_mut();
+        drops.reserve(1);
+        // Keep only a raw pointer until the entry is pushed, so that the
+        // reference we return is the last one derived from it.
+        let value: *mut T = self.bump.alloc(value);
+        drops.push(DropEntry {
+            value: value as *mut u8,
+            drop: drop_value::<T>,
+        });
+        unsafe { &mut *value }
+    }
+
+    /// Return the number of values that the arena will drop.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::DynArena;
+    ///
+    /// let arena = DynArena::new();
+    /// arena.alloc(String::from("dropped"));
+    /// arena.alloc(1);
+    /// assert_eq!(arena.drop_count(), 1);
+    /// ```
+    pub fn drop_count(&self) -> usize {
+        self.drops.borrow().len()
+    }
+
+    /// Return the number of bytes in the chunks of the arena.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
+
+impl<'a> Default for DynArena<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Drop for DynArena<'a> {
+    fn drop(&mut self) {
+        // Dropping the entries runs the destructors in order, and keeps on
+        // with the others if one panics. The chunks are freed afterwards.
+        self.drops.get_mut().clear();
+    }
+}
+
+impl<'a> fmt::Debug for DynArena<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DynArena")
+            .field("drop_count", &self.drop_count())
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish()
+    }
+}
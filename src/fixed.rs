@@ -0,0 +1,215 @@
+use core::cell::{RefCell, RefMut};
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ops::Deref;
+use core::ptr;
+
+/// An arena that allocates from a fixed, caller-provided buffer.
+///
+/// A `FixedArena` never touches the heap: it hands out the slots of the
+/// buffer it was created with, and allocation fails once they are all used.
+/// The buffer can live on the stack or in a `static`, which makes this arena
+/// usable on targets without an allocator.
+///
+/// Like an `Arena`, it drops its values when it is dropped. The buffer can
+/// then be reused.
+///
+/// ## Example
+///
+/// ```
+/// use std::mem::MaybeUninit;
+/// use typed_arena::FixedArena;
+///
+/// let mut buffer = [MaybeUninit::<u32>::uninit(); 2];
+/// let arena = FixedArena::new(&mut buffer);
+/// let x = arena.try_alloc(1).unwrap();
+/// let y = arena.try_alloc(2).unwrap();
+/// assert_eq!(arena.try_alloc(3), Err(3));
+/// assert_eq!(*x + *y, 3);
+/// ```
+pub struct FixedArena<'buf, T> {
+    start: *mut T,
+    capacity: usize,
+    len: RefCell<usize>,
+    _marker: PhantomData<(&'buf mut [MaybeUninit<T>], T)>,
+}
+
+// The arena owns its values, like a `Vec<T>`.
+unsafe impl<'buf, T: Send> Send for FixedArena<'buf, T> {}
+
+impl<'buf, T> FixedArena<'buf, T> {
+    /// Construct a new arena that allocates from `buffer`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use typed_arena::FixedArena;
+    ///
+    /// let mut buffer = [MaybeUninit::<u8>::uninit(); 16];
+    /// let arena = FixedArena::new(&mut buffer);
+    /// assert_eq!(arena.capacity(), 16);
+    /// ```
+    pub fn new(buffer: &'buf mut [MaybeUninit<T>]) -> Self {
+        FixedArena {
+            start: buffer.as_mut_ptr() as *mut T,
+            capacity: buffer.len(),
+            len: RefCell::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allocates a value in the arena, and returns a mutable reference to
+    /// that value.
+    ///
+    /// If the buffer is full, `value` is given back.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use typed_arena::FixedArena;
+    ///
+    /// let mut buffer = [MaybeUninit::<&str>::uninit(); 1];
+    /// let arena = FixedArena::new(&mut buffer);
+    /// assert_eq!(*arena.try_alloc("one").unwrap(), "one");
+    /// assert_eq!(arena.try_alloc("two"), Err("two"));
+    /// ```
+    pub fn try_alloc(&self, value: T) -> Result<&mut T, T> {
+        let mut len = self.len.borrow_mut();
+        if *len == self.capacity {
+            return Err(value);
+        }
+        unsafe {
+            let slot = self.start.add(*len);
+            ptr::write(slot, value);
+            *len += 1;
+            Ok(&mut *slot)
+        }
+    }
+
+    /// Return the number of values allocated in the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use typed_arena::FixedArena;
+    ///
+    /// let mut buffer = [MaybeUninit::<u8>::uninit(); 4];
+    /// let arena = FixedArena::new(&mut buffer);
+    /// arena.try_alloc(1).unwrap();
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(arena.remaining(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        *self.len.borrow()
+    }
+
+    /// Returns `true` if no values have been allocated in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of values the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Return the number of values that can still be allocated.
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.len()
+    }
+
+    /// Create a sub-arena over the unused part of the buffer.
+    ///
+    /// This works like a [`SubArena`][crate::SubArena]: the values allocated
+    /// in the sub-arena are dropped with it, and their slots become free
+    /// again. While the sub-arena is in use, this arena cannot be used;
+    /// trying to do so results in a panic.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use typed_arena::FixedArena;
+    ///
+    /// let mut buffer = [MaybeUninit::<u32>::uninit(); 3];
+    /// let arena = FixedArena::new(&mut buffer);
+    /// let x = arena.try_alloc(1).unwrap();
+    /// {
+    ///     let sub_arena = arena.sub_arena();
+    ///     sub_arena.try_alloc(2).unwrap();
+    ///     sub_arena.try_alloc(3).unwrap();
+    ///     assert_eq!(sub_arena.remaining(), 0);
+    /// }
+    /// assert_eq!(arena.remaining(), 2);
+    /// assert_eq!(*x, 1);
+    /// ```
+    pub fn sub_arena(&self) -> FixedSubArena<'_, T> {
+        let old_len = self.len.borrow_mut();
+        let inner = FixedArena {
+            start: unsafe { self.start.add(*old_len) },
+            capacity: self.capacity - *old_len,
+            len: RefCell::new(0),
+            _marker: PhantomData,
+        };
+        FixedSubArena { inner, old_len }
+    }
+}
+
+impl<'buf, T> Drop for FixedArena<'buf, T> {
+    fn drop(&mut self) {
+        let len = mem::replace(self.len.get_mut(), 0);
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.start, len));
+        }
+    }
+}
+
+impl<'buf, T> fmt::Debug for FixedArena<'buf, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.len.try_borrow() {
+            Ok(len) => f
+                .debug_struct("FixedArena")
+                .field("len", &*len)
+                .field("capacity", &self.capacity)
+                .finish(),
+            Err(_) => f.debug_struct("FixedArena").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// A scoped sub-arena of a [`FixedArena`], created by
+/// [`FixedArena::sub_arena`].
+///
+/// It dereferences to a `FixedArena` over the part of the buffer that was
+/// unused when it was created.
+pub struct FixedSubArena<'a, T> {
+    inner: FixedArena<'a, T>,
+    // Keeps the parent arena borrowed.
+    old_len: RefMut<'a, usize>,
+}
+
+impl<'a, T> Deref for FixedSubArena<'a, T> {
+    type Target = FixedArena<'a, T>;
+
+    fn deref(&self) -> &FixedArena<'a, T> {
+        &self.inner
+    }
+}
+
+impl<'a, T> fmt::Debug for FixedSubArena<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner.len.try_borrow() {
+            Ok(len) => f
+                .debug_struct("FixedSubArena")
+                .field("len", &*len)
+                .field("capacity", &self.inner.capacity)
+                .field("parent_len", &*self.old_len)
+                .finish(),
+            Err(_) => f.debug_struct("FixedSubArena").finish_non_exhaustive(),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::Arena;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+/// An arena that refers to its values by [`Handle`], and can remove them.
+///
+/// `alloc` returns a small, copyable handle instead of a reference. Removing
+/// a value frees its slot for a later value, and bumps the slot's
+/// generation, so handles to the removed value are detected as stale
+/// instead of reaching the new one.
+///
+/// The slots are stored in the chunks of an `Arena`, and never move.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::GenArena;
+///
+/// let mut entities = GenArena::new();
+/// let player = entities.alloc("player");
+/// let monster = entities.alloc("monster");
+///
+/// assert_eq!(entities.remove(monster), Some("monster"));
+/// let item = entities.alloc("item");
+///
+/// assert_eq!(entities.get(monster), None);
+/// assert_eq!(entities.get(item), Some(&"item"));
+/// assert_eq!(entities.get(player), Some(&"player"));
+/// ```
+pub struct GenArena<T> {
+    entries: Arena<Entry<T>>,
+    // The indices of the slots without a value.
+    free: Vec<u32>,
+    len: usize,
+}
+
+struct Entry<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// A handle to a value of a [`GenArena`].
+///
+/// It holds the index of the value's slot, and the generation of the slot
+/// when the value was allocated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    /// Return the index of the slot the handle refers to.
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// Return the generation of the slot when the value was allocated.
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl<T> GenArena<T> {
+    /// Construct a new generational arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::GenArena;
+    ///
+    /// let mut arena = GenArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> GenArena<T> {
+        GenArena {
+            entries: Arena::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Construct a new generational arena with room for `n` values
+    /// pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::GenArena;
+    ///
+    /// let mut arena = GenArena::with_capacity(1337);
+    /// # arena.alloc(1);
+    /// ```
+    pub fn with_capacity(n: usize) -> GenArena<T> {
+        GenArena {
+            entries: Arena::with_capacity(n),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Allocates a value in the arena, and returns a handle to it.
+    ///
+    /// The slot of a removed value is reused if there is one.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the arena would hold more than `u32::MAX` slots.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::GenArena;
+    ///
+    /// let mut arena = GenArena::new();
+    /// let handle = arena.alloc(42);
+    /// assert_eq!(arena[handle], 42);
+    /// ```
+    pub fn alloc(&mut self, value: T) -> Handle {
+        if let Some(index) = self.free.pop() {
+            let entry = self.entries.get_mut(index as usize).unwrap();
+            entry.value = Some(value);
+            self.len += 1;
+            return Handle {
+                index,
+                generation: entry.generation,
+            };
+        }
+        let index = self.entries.len();
+        assert!(index < u32::MAX as usize, "too many slots in GenArena");
+        self.entries.alloc(Entry {
+            generation: 0,
+            value: Some(value),
+        });
+        self.len += 1;
+        Handle {
+            index: index as u32,
+            generation: 0,
+        }
+    }
+
+    fn entry(&self, handle: Handle) -> Option<&Entry<T>> {
+        // Entries are only modified through `&mut self`.
+        unsafe { self.entries.get_shared(handle.index as usize) }
+            .filter(|e| e.generation == handle.generation)
+    }
+
+    fn entry_mut(&mut self, handle: Handle) -> Option<&mut Entry<T>> {
+        self.entries
+            .get_mut(handle.index as usize)
+            .filter(|e| e.generation == handle.generation)
+    }
+
+    /// Returns a reference to the value of `handle`, or `None` if it was
+    /// removed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::GenArena;
+    ///
+    /// let mut arena = GenArena::new();
+    /// let handle = arena.alloc('a');
+    /// assert_eq!(arena.get(handle), Some(&'a'));
+    /// arena.remove(handle);
+    /// assert_eq!(arena.get(handle), None);
+    /// ```
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.entry(handle)?.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value of `handle`, or `None` if
+    /// it was removed.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.entry_mut(handle)?.value.as_mut()
+    }
+
+    /// Returns `true` if the value of `handle` is still in the arena.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Removes the value of `handle` from the arena, and returns it, or
+    /// `None` if it was already removed.
+    ///
+    /// All the handles to the value become stale.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::GenArena;
+    ///
+    /// let mut arena = GenArena::new();
+    /// let handle = arena.alloc(String::from("gone"));
+    /// assert_eq!(arena.remove(handle).as_deref(), Some("gone"));
+    /// assert_eq!(arena.remove(handle), None);
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let entry = self.entry_mut(handle)?;
+        let value = entry.value.take()?;
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Return the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> Default for GenArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Handle> for GenArena<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).expect("stale handle")
+    }
+}
+
+impl<T> IndexMut<Handle> for GenArena<T> {
+    fn index_mut(&mut self, handle: Handle) -> &mut T {
+        self.get_mut(handle).expect("stale handle")
+    }
+}
+
+impl<T> fmt::Debug for GenArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenArena")
+            .field("len", &self.len)
+            .field("slots", &self.entries.len())
+            .finish()
+    }
+}
@@ -0,0 +1,133 @@
+/// Decides how large an arena's chunks get as it grows.
+///
+/// When an arena runs out of room, it asks its strategy for the capacity of
+/// the next chunk, given the capacity of the current one. The result is
+/// only a baseline: a chunk is always large enough for the allocation that
+/// needed it, and never larger than the arena's
+/// [`max_chunk_size`][crate::ArenaBuilder::max_chunk_size] unless that
+/// allocation requires it.
+///
+/// Strategies are set with
+/// [`ArenaBuilder::growth_strategy`][crate::ArenaBuilder::growth_strategy].
+/// Arenas use [`Doubling`] by default. Closures taking and returning a
+/// capacity are strategies too.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Arena;
+///
+/// // Grow by half of the current chunk each time.
+/// let arena = Arena::<u32>::builder()
+///     .initial_capacity(8)
+///     .growth_strategy(|capacity: usize| capacity + capacity / 2)
+///     .build();
+/// for i in 0..30 {
+///     arena.alloc(i);
+/// }
+/// assert_eq!(arena.capacity(), 8 + 12 + 18);
+/// ```
+pub trait GrowthStrategy {
+    /// Returns the capacity of the chunk to allocate after the current one,
+    /// which has capacity for `current` values.
+    fn next_capacity(&mut self, current: usize) -> usize;
+}
+
+impl<F: FnMut(usize) -> usize> GrowthStrategy for F {
+    fn next_capacity(&mut self, current: usize) -> usize {
+        self(current)
+    }
+}
+
+/// Doubles the capacity of each new chunk. This is the default strategy,
+/// which keeps the number of chunks logarithmic in the number of values.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::{Arena, Doubling};
+///
+/// let arena = Arena::<u32>::builder()
+///     .initial_capacity(1)
+///     .growth_strategy(Doubling)
+///     .build();
+/// for i in 0..7 {
+///     arena.alloc(i);
+/// }
+/// assert_eq!(arena.capacity(), 1 + 2 + 4);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Doubling;
+
+impl GrowthStrategy for Doubling {
+    fn next_capacity(&mut self, current: usize) -> usize {
+        current.saturating_mul(2)
+    }
+}
+
+/// Gives every new chunk the same capacity, which suits arenas that never
+/// hold many values, or that want predictable allocations.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::{Arena, Fixed};
+///
+/// let arena = Arena::<u32>::builder()
+///     .initial_capacity(4)
+///     .growth_strategy(Fixed(4))
+///     .build();
+/// for i in 0..12 {
+///     arena.alloc(i);
+/// }
+/// assert_eq!(arena.chunk_count(), 3);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Fixed(pub usize);
+
+impl GrowthStrategy for Fixed {
+    fn next_capacity(&mut self, _current: usize) -> usize {
+        self.0
+    }
+}
+
+/// Makes each new chunk as large as the two previous ones combined, which
+/// grows more gently than [`Doubling`]. The first new chunk, which only has
+/// one predecessor, doubles.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::{Arena, Fibonacci};
+///
+/// let arena = Arena::<u32>::builder()
+///     .initial_capacity(2)
+///     .growth_strategy(Fibonacci::new())
+///     .build();
+/// for i in 0..30 {
+///     arena.alloc(i);
+/// }
+/// assert_eq!(arena.capacity(), 2 + 4 + 6 + 10 + 16);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fibonacci {
+    previous: usize,
+}
+
+impl Fibonacci {
+    /// Create a Fibonacci growth strategy.
+    pub fn new() -> Self {
+        Fibonacci { previous: 0 }
+    }
+}
+
+impl GrowthStrategy for Fibonacci {
+    fn next_capacity(&mut self, current: usize) -> usize {
+        let previous = match self.previous {
+            0 => current,
+            previous => previous,
+        };
+        self.previous = current;
+        current.saturating_add(previous)
+    }
+}
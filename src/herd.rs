@@ -0,0 +1,172 @@
+use crate::Arena;
+
+use std::fmt;
+use std::mem;
+use std::sync::{Mutex, PoisonError};
+
+/// A group of arenas whose values all live as long as the group.
+///
+/// A `Herd` is shared between threads, and hands out [`Member`] arenas to
+/// them. Allocating from a member takes no lock, and returns references that
+/// are tied to the herd rather than to the member, so they outlive it: when a
+/// member is dropped, its arena goes back to the herd, and the values in it
+/// are only dropped with the herd.
+///
+/// Only available with the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use std::thread;
+/// use typed_arena::Herd;
+///
+/// // Freed at the end, once the threads are done with it.
+/// let raw = Box::into_raw(Box::new(Herd::new()));
+/// let herd: &'static Herd<_> = unsafe { &*raw };
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         thread::spawn(move || {
+///             let member = herd.get();
+///             let value: &'static mut i32 = member.alloc(i);
+///             value
+///         })
+///     })
+///     .collect();
+/// let sum: i32 = workers.into_iter().map(|w| *w.join().unwrap()).sum();
+/// assert_eq!(sum, 6);
+/// drop(unsafe { Box::from_raw(raw) });
+/// ```
+pub struct Herd<T> {
+    // The arenas not in use by a member.
+    arenas: Mutex<Vec<Arena<T>>>,
+}
+
+/// An arena of a [`Herd`], created by [`Herd::get`].
+///
+/// The values allocated from a member live as long as its herd.
+pub struct Member<'h, T> {
+    herd: &'h Herd<T>,
+    arena: Arena<T>,
+}
+
+impl<T> Herd<T> {
+    /// Construct a new, empty herd.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Herd;
+    ///
+    /// let herd = Herd::new();
+    /// # herd.get().alloc(1);
+    /// ```
+    pub fn new() -> Herd<T> {
+        Herd {
+            arenas: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get a member arena, to allocate from on the current thread.
+    ///
+    /// This reuses the arena of a dropped member if there is one, so the
+    /// remaining space of its chunks isn't wasted.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Herd;
+    ///
+    /// let herd = Herd::new();
+    /// let x = herd.get().alloc(1);
+    /// let y = herd.get().alloc(2);
+    /// assert_eq!(*x + *y, 3);
+    /// ```
+    pub fn get(&self) -> Member<'_, T> {
+        let arena = self
+            .arenas
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_default();
+        Member { herd: self, arena }
+    }
+}
+
+impl<T> Default for Herd<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Herd<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.arenas.try_lock() {
+            Ok(arenas) => f
+                .debug_struct("Herd")
+                .field("idle_arenas", &arenas.len())
+                .finish(),
+            Err(_) => f.debug_struct("Herd").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl<'h, T> Member<'h, T> {
+    /// Allocates a value in the herd, and returns a mutable reference to
+    /// that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Herd;
+    ///
+    /// let herd = Herd::new();
+    /// let x = {
+    ///     let member = herd.get();
+    ///     member.alloc(42)
+    /// };
+    /// assert_eq!(*x, 42);
+    /// ```
+    pub fn alloc(&self, value: T) -> &'h mut T {
+        let value: *mut T = self.arena.alloc(value);
+        // The chunks of the arena outlive the member, and are only freed
+        // with the herd.
+        unsafe { &mut *value }
+    }
+
+    /// Uses the contents of an iterator to allocate values in the herd.
+    /// Returns a mutable slice that contains these values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Herd;
+    ///
+    /// let herd = Herd::new();
+    /// let abc = herd.get().alloc_extend("abcdefg".chars().take(3));
+    /// assert_eq!(abc, ['a', 'b', 'c']);
+    /// ```
+    pub fn alloc_extend<I>(&self, iterable: I) -> &'h mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let values: *mut [T] = self.arena.alloc_extend(iterable);
+        unsafe { &mut *values }
+    }
+}
+
+impl<'h, T> Drop for Member<'h, T> {
+    fn drop(&mut self) {
+        let arena = mem::take(&mut self.arena);
+        self.herd
+            .arenas
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(arena);
+    }
+}
+
+impl<'h, T> fmt::Debug for Member<'h, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Member").field(&self.arena).finish()
+    }
+}
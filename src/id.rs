@@ -0,0 +1,250 @@
+use crate::Arena;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+
+/// An arena that refers to its values by typed [`Id`]s.
+///
+/// `alloc` returns an `Id<T>`, a `u32` that knows the type of its value,
+/// instead of a reference. Nodes can then refer to each other by id without
+/// borrowing the arena, and a graph of ids is easy to serialize.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::{Id, IdArena};
+///
+/// struct Node {
+///     name: &'static str,
+///     parent: Option<Id<Node>>,
+/// }
+///
+/// let nodes = IdArena::new();
+/// let root = nodes.alloc(Node { name: "root", parent: None });
+/// let child = nodes.alloc(Node { name: "child", parent: Some(root) });
+///
+/// let parent = nodes[child].parent.unwrap();
+/// assert_eq!(nodes[parent].name, "root");
+/// ```
+pub struct IdArena<T> {
+    pub(crate) values: Arena<T>,
+}
+
+/// The id of a value of an [`IdArena<T>`].
+///
+/// It is the index of the value in allocation order.
+pub struct Id<T> {
+    index: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Id<T> {
+    /// Create an id from its raw index, for example after deserializing it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Id, IdArena};
+    ///
+    /// let arena = IdArena::new();
+    /// let id = arena.alloc("a");
+    /// assert_eq!(Id::from_raw(id.to_raw()), id);
+    /// ```
+    pub fn from_raw(index: u32) -> Id<T> {
+        Id {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the raw index of the id.
+    pub fn to_raw(self) -> u32 {
+        self.index
+    }
+
+    /// Return the index of the value in allocation order.
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> PartialOrd for Id<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Id<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Id").field(&self.index).finish()
+    }
+}
+
+impl<T> IdArena<T> {
+    /// Construct a new id arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::IdArena;
+    ///
+    /// let arena = IdArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub const fn new() -> IdArena<T> {
+        IdArena {
+            values: Arena::new(),
+        }
+    }
+
+    /// Construct a new id arena with capacity for `n` values pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::IdArena;
+    ///
+    /// let arena = IdArena::with_capacity(1337);
+    /// # arena.alloc(1);
+    /// ```
+    pub fn with_capacity(n: usize) -> IdArena<T> {
+        IdArena {
+            values: Arena::with_capacity(n),
+        }
+    }
+
+    /// Allocates a value in the arena, and returns its id.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the arena would hold more than `u32::MAX` values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::IdArena;
+    ///
+    /// let arena = IdArena::new();
+    /// let a = arena.alloc('a');
+    /// let b = arena.alloc('b');
+    /// assert_eq!((a.index(), b.index()), (0, 1));
+    /// ```
+    pub fn alloc(&self, value: T) -> Id<T> {
+        let index = self.values.len();
+        assert!(index < u32::MAX as usize, "too many values in IdArena");
+        self.values.alloc(value);
+        Id::from_raw(index as u32)
+    }
+
+    /// Returns a reference to the value of `id`, or `None` if the id is not
+    /// from this arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Id, IdArena};
+    ///
+    /// let arena = IdArena::new();
+    /// let id = arena.alloc(42);
+    /// assert_eq!(arena.get(id), Some(&42));
+    /// assert_eq!(arena.get(Id::from_raw(1)), None);
+    /// ```
+    pub fn get(&self, id: Id<T>) -> Option<&T> {
+        // Mutable references are only handed out through `&mut self`.
+        unsafe { self.values.get_shared(id.index()) }
+    }
+
+    /// Returns a mutable reference to the value of `id`, or `None` if the id
+    /// is not from this arena.
+    pub fn get_mut(&mut self, id: Id<T>) -> Option<&mut T> {
+        self.values.get_mut(id.index())
+    }
+
+    /// Return the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Convert this `IdArena` into a `Vec<T>`, in which the index of each
+    /// value is its id.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::IdArena;
+    ///
+    /// let arena = IdArena::new();
+    /// let b = { arena.alloc("a"); arena.alloc("b") };
+    /// assert_eq!(arena.into_vec()[b.index()], "b");
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.values.into_vec()
+    }
+}
+
+impl<T> Default for IdArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Id<T>> for IdArena<T> {
+    type Output = T;
+
+    fn index(&self, id: Id<T>) -> &T {
+        self.get(id).expect("id out of bounds")
+    }
+}
+
+impl<T> IndexMut<Id<T>> for IdArena<T> {
+    fn index_mut(&mut self, id: Id<T>) -> &mut T {
+        self.get_mut(id).expect("id out of bounds")
+    }
+}
+
+impl<T> fmt::Debug for IdArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IdArena")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
@@ -0,0 +1,125 @@
+use core::mem;
+
+// Emits the events of the `tracing` feature. The target is the crate name,
+// so that subscribers can filter them with `typed_arena=debug`.
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($($field:tt)*) => {
+        tracing::debug!(target: "typed_arena", $($field)*)
+    };
+}
+
+// What an arena allocated, reported when its chunks are dropped or given
+// away, for example by `into_vec`. It doesn't own any value, so that an
+// arena can still hold values that borrow each other.
+pub(crate) struct Meter {
+    // The label of the arena, as of its first allocation.
+    label: Option<&'static str>,
+    chunks: usize,
+    bytes: usize,
+}
+
+impl Meter {
+    pub(crate) const fn new() -> Self {
+        Meter {
+            label: None,
+            chunks: 0,
+            bytes: 0,
+        }
+    }
+
+    // Records a new chunk of `bytes` bytes, or a chunk that grew by `bytes`
+    // in place.
+    pub(crate) fn allocated(&mut self, label: Option<&'static str>, bytes: usize, new_chunk: bool) {
+        if bytes == 0 {
+            // Chunks of zero-sized values don't allocate.
+            return;
+        }
+        if self.bytes == 0 {
+            self.label = label;
+            #[cfg(feature = "metrics")]
+            gauge("typed_arena.arenas_live", label).increment(1);
+        }
+        #[cfg(feature = "metrics")]
+        {
+            counter("typed_arena.bytes_allocated", label).increment(bytes as u64);
+            if new_chunk {
+                counter("typed_arena.chunks_allocated", label).increment(1);
+            }
+        }
+        self.bytes += bytes;
+        if new_chunk {
+            self.chunks += 1;
+            #[cfg(feature = "tracing")]
+            event!(label, bytes, "allocated a chunk");
+        } else {
+            #[cfg(feature = "tracing")]
+            event!(label, bytes, "grew a chunk");
+        }
+    }
+
+    // Takes over what another arena allocated, when its chunks are given to
+    // this one.
+    pub(crate) fn absorb(&mut self, other: &mut Meter) {
+        if other.bytes == 0 {
+            return;
+        }
+        if self.bytes == 0 {
+            self.label = other.label;
+        } else {
+            // The two arenas are one from now on.
+            #[cfg(feature = "metrics")]
+            gauge("typed_arena.arenas_live", other.label).decrement(1);
+        }
+        self.chunks += mem::take(&mut other.chunks);
+        self.bytes += mem::take(&mut other.bytes);
+    }
+}
+
+impl Drop for Meter {
+    fn drop(&mut self) {
+        if self.bytes != 0 {
+            #[cfg(feature = "metrics")]
+            gauge("typed_arena.arenas_live", self.label).decrement(1);
+            #[cfg(feature = "tracing")]
+            event!(
+                label = self.label,
+                chunks_allocated = self.chunks,
+                bytes_allocated = self.bytes,
+                "dropped an arena"
+            );
+        }
+    }
+}
+
+// The counter of the `metrics` feature called `name`, for the arena
+// labelled `label`.
+#[cfg(feature = "metrics")]
+fn counter(name: &'static str, label: Option<&'static str>) -> metrics::Counter {
+    match label {
+        Some(label) => metrics::counter!(name, "arena" => label),
+        None => metrics::counter!(name),
+    }
+}
+
+// Like `counter`, for a gauge.
+#[cfg(feature = "metrics")]
+fn gauge(name: &'static str, label: Option<&'static str>) -> metrics::Gauge {
+    match label {
+        Some(label) => metrics::gauge!(name, "arena" => label),
+        None => metrics::gauge!(name),
+    }
+}
+
+// Reports that `clear` or `reset` dropped `values` values.
+#[cfg(feature = "tracing")]
+pub(crate) fn cleared(label: Option<&'static str>, values: usize) {
+    event!(label, values, "cleared an arena");
+}
+
+// Reports that a sub-arena dropped its `values` values, and gave `chunks`
+// chunks that it added to its parent.
+#[cfg(feature = "tracing")]
+pub(crate) fn rolled_back(label: Option<&'static str>, values: usize, chunks: usize) {
+    event!(label, values, chunks, "rolled back a sub-arena");
+}
@@ -0,0 +1,312 @@
+use crate::Arena;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A string interner, which stores each distinct string once.
+///
+/// Interning a string returns a reference to the copy in the interner, so
+/// equal strings share the same memory, and can be compared by address.
+/// The strings are stored in an `Arena<u8>`, and found again through a hash
+/// set. Only available with the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Interner;
+///
+/// let interner = Interner::new();
+/// let a = interner.intern("main");
+/// let b = interner.intern(&String::from("main"));
+/// assert!(std::ptr::eq(a, b));
+/// assert_eq!(interner.len(), 1);
+/// ```
+pub struct Interner {
+    // The strings point into `bytes`. They are only handed out for as long
+    // as the interner is borrowed, and the arena never moves them.
+    strings: RefCell<HashSet<&'static str>>,
+    bytes: Arena<u8>,
+}
+
+impl Interner {
+    /// Construct a new, empty interner.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Interner;
+    ///
+    /// let interner = Interner::new();
+    /// # interner.intern("a");
+    /// ```
+    pub fn new() -> Interner {
+        Interner {
+            strings: RefCell::new(HashSet::new()),
+            bytes: Arena::new(),
+        }
+    }
+
+    /// Construct a new interner with `bytes` bytes of string storage
+    /// pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Interner;
+    ///
+    /// let interner = Interner::with_capacity(4096);
+    /// # interner.intern("a");
+    /// ```
+    pub fn with_capacity(bytes: usize) -> Interner {
+        Interner {
+            strings: RefCell::new(HashSet::new()),
+            bytes: Arena::with_capacity(bytes),
+        }
+    }
+
+    /// Returns the interned copy of `s`, copying it into the interner if it
+    /// is not there yet.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Interner;
+    ///
+    /// let interner = Interner::new();
+    /// let words: Vec<&str> = "a b a".split(' ').map(|w| interner.intern(w)).collect();
+    /// assert!(std::ptr::eq(words[0], words[2]));
+    /// assert!(!std::ptr::eq(words[0], words[1]));
+    /// ```
+    pub fn intern(&self, s: &str) -> &str {
+        let mut strings = self.strings.borrow_mut();
+        if let Some(&interned) = strings.get(s) {
+            return interned;
+        }
+        let interned: &str = self.bytes.alloc_str(s);
+        // See `strings`: the `'static` never escapes.
+        let interned: &'static str = unsafe { &*(interned as *const str) };
+        strings.insert(interned);
+        interned
+    }
+
+    /// Returns the interned copy of `s`, or `None` if it was never
+    /// interned.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Interner;
+    ///
+    /// let interner = Interner::new();
+    /// interner.intern("known");
+    /// assert_eq!(interner.get("known"), Some("known"));
+    /// assert_eq!(interner.get("unknown"), None);
+    /// ```
+    pub fn get(&self, s: &str) -> Option<&str> {
+        self.strings.borrow().get(s).copied()
+    }
+
+    /// Return the number of distinct strings in the interner.
+    pub fn len(&self) -> usize {
+        self.strings.borrow().len()
+    }
+
+    /// Returns `true` if no string was interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Interner")
+            .field("len", &self.len())
+            .field("bytes", &self.bytes.len())
+            .finish()
+    }
+}
+
+/// A compact id of a string in a [`SymbolTable`].
+///
+/// It is the index of the string in interning order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Create a symbol from its raw index, for example after deserializing
+    /// it.
+    pub fn from_raw(index: u32) -> Symbol {
+        Symbol(index)
+    }
+
+    /// Return the raw index of the symbol.
+    pub fn to_raw(self) -> u32 {
+        self.0
+    }
+
+    /// Return the index of the string in interning order.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A string interner that returns [`Symbol`]s.
+///
+/// Like an [`Interner`], it stores each distinct string once in an arena,
+/// but interning returns a `u32` symbol, which is `Copy`, hashes quickly and
+/// keeps tokens small. [`resolve`][SymbolTable::resolve] gives back the
+/// string. Only available with the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::SymbolTable;
+///
+/// let symbols = SymbolTable::new();
+/// let a = symbols.intern("x");
+/// let b = symbols.intern("y");
+/// assert_eq!(symbols.intern("x"), a);
+/// assert_ne!(a, b);
+/// assert_eq!(symbols.resolve(b), "y");
+/// ```
+pub struct SymbolTable {
+    // Like in `Interner`, the strings point into `bytes`.
+    symbols: RefCell<HashMap<&'static str, Symbol>>,
+    strings: RefCell<Vec<&'static str>>,
+    bytes: Arena<u8>,
+}
+
+impl SymbolTable {
+    /// Construct a new, empty symbol table.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SymbolTable;
+    ///
+    /// let symbols = SymbolTable::new();
+    /// # symbols.intern("a");
+    /// ```
+    pub fn new() -> SymbolTable {
+        SymbolTable {
+            symbols: RefCell::new(HashMap::new()),
+            strings: RefCell::new(Vec::new()),
+            bytes: Arena::new(),
+        }
+    }
+
+    /// Construct a new symbol table with `bytes` bytes of string storage
+    /// pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SymbolTable;
+    ///
+    /// let symbols = SymbolTable::with_capacity(4096);
+    /// # symbols.intern("a");
+    /// ```
+    pub fn with_capacity(bytes: usize) -> SymbolTable {
+        SymbolTable {
+            symbols: RefCell::new(HashMap::new()),
+            strings: RefCell::new(Vec::new()),
+            bytes: Arena::with_capacity(bytes),
+        }
+    }
+
+    /// Returns the symbol of `s`, copying it into the table if it is not
+    /// there yet.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the table would hold more than `u32::MAX` strings.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SymbolTable;
+    ///
+    /// let symbols = SymbolTable::new();
+    /// let ids: Vec<_> = "a b a".split(' ').map(|w| symbols.intern(w).index()).collect();
+    /// assert_eq!(ids, [0, 1, 0]);
+    /// ```
+    pub fn intern(&self, s: &str) -> Symbol {
+        let mut symbols = self.symbols.borrow_mut();
+        if let Some(&symbol) = symbols.get(s) {
+            return symbol;
+        }
+        let mut strings = self.strings.borrow_mut();
+        assert!(
+            strings.len() < u32::MAX as usize,
+            "too many strings in SymbolTable"
+        );
+        let symbol = Symbol(strings.len() as u32);
+        let interned: &str = self.bytes.alloc_str(s);
+        let interned: &'static str = unsafe { &*(interned as *const str) };
+        symbols.insert(interned, symbol);
+        strings.push(interned);
+        symbol
+    }
+
+    /// Returns the symbol of `s`, or `None` if it was never interned.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.borrow().get(s).copied()
+    }
+
+    /// Returns the string of `symbol`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the symbol is not from this table.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SymbolTable;
+    ///
+    /// let symbols = SymbolTable::new();
+    /// let main = symbols.intern("main");
+    /// assert_eq!(symbols.resolve(main), "main");
+    /// ```
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.try_resolve(symbol).expect("symbol out of bounds")
+    }
+
+    /// Returns the string of `symbol`, or `None` if the symbol is not from
+    /// this table.
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&str> {
+        self.strings.borrow().get(symbol.index()).copied()
+    }
+
+    /// Return the number of distinct strings in the table.
+    pub fn len(&self) -> usize {
+        self.strings.borrow().len()
+    }
+
+    /// Returns `true` if no string was interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SymbolTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SymbolTable")
+            .field("len", &self.len())
+            .field("bytes", &self.bytes.len())
+            .finish()
+    }
+}
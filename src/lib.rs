@@ -56,6 +56,9 @@
 
 #![deny(missing_docs)]
 #![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+// Handing out `&mut T` from `&self` is the whole point of an arena.
+#![allow(clippy::mut_from_ref)]
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
@@ -63,31 +66,235 @@ extern crate alloc;
 #[cfg(any(feature = "std", test))]
 extern crate core;
 
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(feature = "rkyv")]
+extern crate rkyv;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "metrics")]
+extern crate metrics;
+
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+#[cfg(not(feature = "std"))]
+use alloc::alloc::handle_alloc_error;
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "std")]
+use std::alloc::handle_alloc_error;
+#[cfg(feature = "std")]
+use std::vec;
 
+use core::alloc::Layout;
 use core::cell::RefCell;
 use core::cmp;
-use core::iter;
+use core::fmt;
+use core::iter::{self, FromIterator, FusedIterator};
 use core::mem;
+use core::panic::AssertUnwindSafe;
+use core::ptr;
 use core::slice;
 use core::str;
 
 use mem::MaybeUninit;
 
+#[cfg(all(feature = "allocator_api", not(feature = "std")))]
+use alloc::alloc::Global;
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+#[cfg(all(feature = "allocator_api", feature = "std"))]
+use std::alloc::Global;
+
+// The type `$ty<$T>`, with the allocator `A` of the arena as its last type
+// parameter with the `allocator_api` feature. Type parameters can only be
+// configured out where they are declared, not where they are used.
+#[cfg(feature = "allocator_api")]
+macro_rules! in_alloc {
+    ($ty:ident<$lt:lifetime, $T:ty>) => { $ty<$lt, $T, A> };
+    ($ty:ident<$T:ty>) => { $ty<$T, A> };
+}
+#[cfg(not(feature = "allocator_api"))]
+macro_rules! in_alloc {
+    ($ty:ident<$lt:lifetime, $T:ty>) => { $ty<$lt, $T> };
+    ($ty:ident<$T:ty>) => { $ty<$T> };
+}
+
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "allocator_api")]
+mod allocator;
+
+mod any;
+pub use any::AnyArena;
+
+#[cfg(feature = "rkyv")]
+mod archive;
+
+mod boxed;
+pub use boxed::ArenaBox;
+
+mod builder;
+pub use builder::ArenaBuilder;
+
+mod bump;
+pub use bump::{Bump, BumpScope, HeaderSlice};
+
+#[cfg(feature = "std")]
+mod concurrent;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentArena;
+
+#[cfg(feature = "std")]
+mod dedup;
+#[cfg(feature = "std")]
+pub use dedup::DedupArena;
+
+mod dropless;
+pub use dropless::DroplessArena;
+
+mod cyclic;
+pub use cyclic::Cyclic;
+
+mod dynamic;
+pub use dynamic::DynArena;
+
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+
+mod fixed;
+pub use fixed::{FixedArena, FixedSubArena};
+
+mod generational;
+pub use generational::{GenArena, Handle};
+
+mod growth;
+pub use growth::{Doubling, Fibonacci, Fixed, GrowthStrategy};
+
+mod id;
+pub use id::{Id, IdArena};
+
+#[cfg(any(feature = "metrics", feature = "tracing"))]
+mod instrument;
+
+#[cfg(feature = "std")]
+mod intern;
+#[cfg(feature = "std")]
+pub use intern::{Interner, Symbol, SymbolTable};
+
+#[cfg(feature = "std")]
+mod herd;
+#[cfg(feature = "std")]
+pub use herd::{Herd, Member};
+
+mod marker;
+pub use marker::Marker;
+
+#[cfg(feature = "std")]
+mod mutex;
+#[cfg(feature = "std")]
+pub use mutex::MutexArena;
+
+#[cfg(all(feature = "rayon", feature = "std"))]
+mod par;
+#[cfg(all(feature = "rayon", feature = "std"))]
+pub use par::ParIterMut;
+
+mod pinned;
+pub use pinned::PinnedArena;
+
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+pub use pool::{ArenaPool, PooledArena};
+
+mod rc;
+pub use rc::ArenaRc;
+
+#[cfg(feature = "std")]
+mod recycler;
+#[cfg(feature = "std")]
+pub use recycler::ChunkRecycler;
+#[cfg(feature = "std")]
+use recycler::Recycler;
+
 mod scoped;
 pub use scoped::{SubArena, SubArenaBuilder};
 
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "serde")]
+pub use serialize::SeqSeed;
+
+mod slots;
+pub use slots::{Pool, PoolBox};
+
+mod small;
+pub use small::SmallArena;
+
+mod snapshot;
+pub use snapshot::CloneMap;
+
+mod stats;
+pub use stats::ArenaStats;
+
+mod string;
+pub use string::ArenaString;
+
+mod sync;
+pub use sync::{FrozenArena, SyncArena, SyncIter};
+
+mod tail;
+pub use tail::{SliceBuilder, StrBuilder};
+
+mod uninit;
+pub use uninit::UninitSlot;
+
+mod vector;
+pub use vector::ArenaVec;
+
 // Initial size in bytes.
 const INITIAL_SIZE: usize = 1024;
 // Minimum capacity. Must be larger than 0.
 const MIN_CAPACITY: usize = 1;
 
+// Told the size in bytes of a failed allocation, returns whether to try
+// again. See `ArenaBuilder::on_alloc_error`.
+pub(crate) type AllocErrorHandler = AssertUnwindSafe<Box<dyn Fn(usize) -> bool + Send>>;
+
+// The current and spare chunks of an arena while a `SubArena` holds them.
+// The arena cannot change until they are given back, so this stays exact.
+#[derive(Clone, Copy)]
+pub(crate) struct Lent {
+    // The start of the current chunk, and the number of values of the
+    // arena in it. A pointer rather than an address, so that the chunk can
+    // be taken back from it if the `SubArena` was leaked.
+    pub(crate) start: *mut u8,
+    pub(crate) len: usize,
+    // The capacity of the current chunk.
+    pub(crate) capacity: usize,
+    // The number of spare chunks, and their total capacity.
+    pub(crate) spare: usize,
+    pub(crate) spare_capacity: usize,
+}
+
+// The chunk is owned by the `SubArena` until it is given back, and only
+// its address is read in the meantime.
+unsafe impl Send for Lent {}
+unsafe impl Sync for Lent {}
+
 /// An arena of objects of type `T`.
 ///
+/// With the nightly-only `allocator_api` feature, the chunks of the arena
+/// come from the allocator `A`, see [`Arena::new_in`].
+///
 /// ## Example
 ///
 /// ```
@@ -102,29 +309,73 @@ const MIN_CAPACITY: usize = 1;
 /// let vegeta = monsters.alloc(Monster { level: 9001 });
 /// assert!(vegeta.level > 9000);
 /// ```
-pub struct Arena<T> {
-    pub(crate) chunks: RefCell<ChunkList<T>>,
+pub struct Arena<T, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    pub(crate) chunks: RefCell<in_alloc!(ChunkList<T>)>,
 }
 
-pub(crate) struct ChunkList<T> {
-    pub(crate) current: Vec<T>,
-    pub(crate) rest: Vec<Vec<T>>,
+pub(crate) struct ChunkList<T, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    pub(crate) current: in_alloc!(Vec<T>),
+    pub(crate) rest: Vec<in_alloc!(Vec<T>)>,
+    // Empty chunks set aside for future growth. The next one to use is last.
+    pub(crate) spare: Vec<in_alloc!(Vec<T>)>,
+    // New chunks stop growing past this capacity.
+    pub(crate) max_chunk_size: usize,
+    // `None` doubles the capacity of each new chunk. A strategy that
+    // panicked can at worst pick odd chunk sizes, so it doesn't keep the
+    // arena from being `UnwindSafe`.
+    pub(crate) growth: Option<AssertUnwindSafe<Box<dyn GrowthStrategy + Send>>>,
+    // See `ArenaBuilder::on_alloc_error`.
+    pub(crate) alloc_error_handler: Option<AllocErrorHandler>,
+    // The addresses of the slots that `alloc_cyclic` has not initialized.
+    pub(crate) pending: Vec<usize>,
+    // The addresses of the slots that `alloc_cyclic` gave up on because its
+    // closure panicked, which are never initialized.
+    pub(crate) abandoned: Vec<usize>,
+    // The number of chunks that hold the values of a forgotten `SubArena`,
+    // which markers can't roll back past.
+    pub(crate) forgotten: usize,
+    // The most values the arena held before it last dropped some, see
+    // `ArenaStats`.
+    pub(crate) peak: usize,
+    // See `ArenaBuilder::label`.
+    pub(crate) label: Option<&'static str>,
+    // What a `SubArena` took from the arena, so that the arena can still be
+    // inspected while the sub-arena is alive.
+    pub(crate) lent: Option<Lent>,
+    // The address of the chunk reserved by `SubArena::with_capacity`, kept
+    // up to date when that chunk grows in place.
+    pub(crate) reserved: Option<usize>,
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    pub(crate) advice: mmap::Advice,
+    // What the arena allocated, see the `metrics` and `tracing` features.
+    #[cfg(any(feature = "metrics", feature = "tracing"))]
+    pub(crate) meter: instrument::Meter,
+    // Where new chunks come from first, see `ArenaBuilder`.
+    #[cfg(feature = "std")]
+    pub(crate) recycler: Option<in_alloc!(Recycler<T>)>,
 }
 
 impl<T> Arena<T> {
     /// Construct a new arena.
     ///
+    /// This doesn't allocate: the first chunk is allocated along with the
+    /// first value, so empty arenas are free. Being a `const fn`, it can also
+    /// be used to initialize a `static`, for example behind a `Mutex`.
+    ///
     /// ## Example
     ///
     /// ```
     /// use typed_arena::Arena;
     ///
     /// let arena = Arena::new();
-    /// # arena.alloc(1);
+    /// assert_eq!(arena.allocated_bytes(), 0);
+    /// arena.alloc(1);
+    /// assert!(arena.allocated_bytes() > 0);
     /// ```
-    pub fn new() -> Arena<T> {
-        let size = cmp::max(1, mem::size_of::<T>());
-        Arena::with_capacity(INITIAL_SIZE / size)
+    pub const fn new() -> Arena<T> {
+        Arena {
+            chunks: RefCell::new(ChunkList::new(Vec::new())),
+        }
     }
 
     /// Construct a new arena with capacity for `n` values pre-allocated.
@@ -139,14 +390,126 @@ impl<T> Arena<T> {
     /// ```
     pub fn with_capacity(n: usize) -> Arena<T> {
         let n = cmp::max(MIN_CAPACITY, n);
+        let arena = Arena {
+            chunks: RefCell::new(ChunkList::new(Vec::with_capacity(n))),
+        };
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        arena.chunks.borrow_mut().record_alloc(0);
+        arena
+    }
+
+    /// Construct a new arena that takes ownership of `vec` as its first
+    /// chunk.
+    ///
+    /// The elements of `vec` become values of the arena, without being
+    /// moved or copied, and its spare capacity is used for the next
+    /// allocations.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut numbers = Vec::with_capacity(8);
+    /// numbers.extend_from_slice(&[1, 2, 3]);
+    /// let arena = Arena::from_vec(numbers);
+    /// arena.alloc(4);
+    /// assert_eq!(arena.len(), 4);
+    /// assert_eq!(arena.into_vec(), [1, 2, 3, 4]);
+    /// ```
+    pub fn from_vec(mut vec: Vec<T>) -> Arena<T> {
+        if vec.capacity() == 0 {
+            // There is nothing to keep in place.
+            vec.reserve(MIN_CAPACITY);
+        }
+        Arena {
+            chunks: RefCell::new(ChunkList::new(vec)),
+        }
+    }
+
+    /// Create a builder, to configure how the arena sizes its chunks.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::builder().max_chunk_size(4096).build();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn builder() -> ArenaBuilder<T> {
+        ArenaBuilder::new()
+    }
+
+    /// Converts a slice returned by
+    /// [`alloc_uninit_slice`][Arena::alloc_uninit_slice] into a slice of the
+    /// initialized values.
+    ///
+    /// ## Safety
+    ///
+    /// Every element of the slice must have been fully initialized.
+    #[inline]
+    pub unsafe fn assume_init_slice(uninit: &mut [MaybeUninit<T>]) -> &mut [T] {
+        &mut *(uninit as *mut [MaybeUninit<T>] as *mut [T])
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Construct a new arena whose chunks come from `alloc`. Only available
+    /// with the nightly-only `allocator_api` feature.
+    ///
+    /// Like [`Arena::new`], this doesn't allocate. Arenas built this way have
+    /// the allocating, iterating and clearing methods of `Arena<T>`, but not
+    /// the ones built on a [`SubArena`], a [`SliceBuilder`] or a chunk
+    /// recycler, nor [`share`][Arena::share], [`freeze`][Arena::freeze] or
+    /// the `serde` seeds: those are only on `Arena<T>`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::System;
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new_in(System);
+    /// arena.alloc_extend(1..4);
+    /// let numbers: Vec<i32, System> = arena.into_vec();
+    /// assert_eq!(numbers, [1, 2, 3]);
+    /// ```
+    #[cfg(feature = "allocator_api")]
+    pub const fn new_in(alloc: A) -> Self {
         Arena {
-            chunks: RefCell::new(ChunkList {
-                current: Vec::with_capacity(n),
-                rest: Vec::new(),
-            }),
+            chunks: RefCell::new(ChunkList::new(Vec::new_in(alloc))),
         }
     }
 
+    /// Construct a new arena whose chunks come from `alloc`, with capacity
+    /// for `n` values pre-allocated. Only available with the nightly-only
+    /// `allocator_api` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::System;
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::with_capacity_in(1337, System);
+    /// # arena.alloc(1);
+    /// ```
+    #[cfg(feature = "allocator_api")]
+    pub fn with_capacity_in(n: usize, alloc: A) -> Self {
+        let n = cmp::max(MIN_CAPACITY, n);
+        let arena = Arena {
+            chunks: RefCell::new(ChunkList::new(Vec::with_capacity_in(n, alloc))),
+        };
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        arena.chunks.borrow_mut().record_alloc(0);
+        arena
+    }
+
     /// Return the size of the arena
     ///
     /// This is useful for using the size of previous typed arenas to build new typed arenas with large enough spaces.
@@ -163,14 +526,169 @@ impl<T> Arena<T> {
     ///  assert_eq!(arena.len(), 2);
     /// ```
     pub fn len(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+
+    /// Returns `true` if no values have been allocated in the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// assert!(arena.is_empty());
+    /// arena.alloc(1);
+    /// assert!(!arena.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
         let chunks = self.chunks.borrow();
+        chunks.current.is_empty()
+            && chunks.rest.iter().all(Vec::is_empty)
+            && chunks.lent.map_or(0, |lent| lent.len) == 0
+    }
 
-        let mut res = 0;
-        for vec in chunks.rest.iter() {
-            res += vec.len()
-        }
+    /// Returns the number of values the arena's chunks can hold, including the
+    /// values already allocated.
+    ///
+    /// Space left unused at the end of older chunks is counted, even though
+    /// it will never be handed out.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::with_capacity(16);
+    /// arena.alloc(1);
+    /// assert_eq!(arena.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        let chunks = self.chunks.borrow();
+        let lent = chunks
+            .lent
+            .map_or(0, |lent| lent.capacity.saturating_add(lent.spare_capacity));
+        chunks
+            .rest
+            .iter()
+            .chain(&chunks.spare)
+            .fold(chunks.current.capacity(), |a, v| {
+                a.saturating_add(v.capacity())
+            })
+            .saturating_add(lent)
+    }
+
+    /// Returns the number of chunks the arena has obtained from the
+    /// allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::with_capacity(1);
+    /// arena.alloc(1);
+    /// assert_eq!(arena.chunk_count(), 1);
+    /// arena.alloc(2);
+    /// assert_eq!(arena.chunk_count(), 2);
+    /// ```
+    pub fn chunk_count(&self) -> usize {
+        let chunks = self.chunks.borrow();
+        let lent = chunks
+            .lent
+            .map_or(0, |lent| lent.spare + (lent.capacity != 0) as usize);
+        // The first chunk of `Arena::new` is not counted until it is
+        // allocated.
+        let allocated = chunks
+            .rest
+            .iter()
+            .chain(&chunks.spare)
+            .chain(iter::once(&chunks.current))
+            .filter(|chunk| chunk.capacity() != 0)
+            .count();
+        allocated + lent
+    }
+
+    /// Returns the number of bytes of chunk memory the arena has obtained
+    /// from the allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<u32> = Arena::with_capacity(16);
+    /// assert_eq!(arena.allocated_bytes(), 64);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        let chunks = self.chunks.borrow();
+        let chunk_bytes = |chunk: &in_alloc!(Vec<T>)| chunk.capacity() * mem::size_of::<T>();
+        let lent = chunks.lent.map_or(0, |lent| {
+            (lent.capacity + lent.spare_capacity) * mem::size_of::<T>()
+        });
+        chunks
+            .rest
+            .iter()
+            .chain(&chunks.spare)
+            .fold(chunk_bytes(&chunks.current) + lent, |a, v| {
+                a + chunk_bytes(v)
+            })
+    }
+
+    /// Returns the number of bytes taken up by the values allocated in the
+    /// arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<u32> = Arena::with_capacity(16);
+    /// arena.alloc_extend(0..3);
+    /// assert_eq!(arena.used_bytes(), 12);
+    /// ```
+    pub fn used_bytes(&self) -> usize {
+        self.len() * mem::size_of::<T>()
+    }
 
-        res + chunks.current.len()
+    /// Returns `true` if `value` refers to a value allocated in this arena.
+    ///
+    /// This compares addresses against the range of each chunk, which makes
+    /// it cheap enough for debug assertions. It always returns `false` for
+    /// zero-sized types, since they don't occupy any memory.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let other = Arena::new();
+    /// let x = &*arena.alloc(1);
+    /// let y = &*other.alloc(1);
+    /// assert!(arena.contains(x));
+    /// assert!(!arena.contains(y));
+    /// ```
+    pub fn contains(&self, value: &T) -> bool {
+        let size = mem::size_of::<T>();
+        let address = value as *const T as usize;
+        let chunks = self.chunks.borrow();
+        let in_lent = match chunks.lent {
+            Some(lent) => {
+                let start = lent.start as usize;
+                address >= start && address < start + lent.len * size
+            }
+            None => false,
+        };
+        in_lent
+            || chunks
+                .rest
+                .iter()
+                .chain(iter::once(&chunks.current))
+                .any(|chunk| {
+                    let start = chunk.as_ptr() as usize;
+                    address >= start && address < start + chunk.len() * size
+                })
     }
 
     /// Allocates a value in the arena, and returns a mutable reference
@@ -210,170 +728,400 @@ impl<T> Arena<T> {
         &mut self.alloc_extend(iter::once(value))[0]
     }
 
-    /// Uses the contents of an iterator to allocate values in the arena.
-    /// Returns a mutable slice that contains these values.
+    /// Allocates a value in the arena, and returns its index along with a
+    /// mutable reference to it.
+    ///
+    /// The index is the number of values allocated before this one. It stays
+    /// valid as the arena grows, and can be used later with
+    /// [`get`][Arena::get] and [`get_mut`][Arena::get_mut], which makes it a
+    /// compact alternative to storing references.
     ///
     /// ## Example
     ///
     /// ```
     /// use typed_arena::Arena;
     ///
-    /// let arena = Arena::new();
-    /// let abc = arena.alloc_extend("abcdefg".chars().take(3));
-    /// assert_eq!(abc, ['a', 'b', 'c']);
+    /// let mut arena = Arena::new();
+    /// let (a, _) = arena.alloc_indexed("a");
+    /// let (b, _) = arena.alloc_indexed("b");
+    /// assert_eq!((a, b), (0, 1));
+    /// assert_eq!(arena.get(b), Some(&"b"));
     /// ```
-    pub fn alloc_extend<I>(&self, iterable: I) -> &mut [T]
-    where
-        I: IntoIterator<Item = T>,
-    {
-        let mut iter = iterable.into_iter();
-
-        let mut chunks = self.chunks.borrow_mut();
-
-        let iter_min_len = iter.size_hint().0;
-        let mut next_item_index;
-        debug_assert!(
-            chunks.current.capacity() >= chunks.current.len(),
-            "capacity is always greater than or equal to len, so we don't need to worry about underflow"
-        );
-        if iter_min_len > chunks.current.capacity() - chunks.current.len() {
-            chunks.reserve(iter_min_len);
-            chunks.current.extend(iter);
-            next_item_index = 0;
-        } else {
-            next_item_index = chunks.current.len();
-            let mut i = 0;
-            while let Some(elem) = iter.next() {
-                if chunks.current.len() == chunks.current.capacity() {
-                    // The iterator was larger than we could fit into the current chunk.
-                    let chunks = &mut *chunks;
-                    // Create a new chunk into which we can freely push the entire iterator into
-                    chunks.reserve(i + 1);
-                    let previous_chunk = chunks.rest.last_mut().unwrap();
-                    let previous_chunk_len = previous_chunk.len();
-                    // Move any elements we put into the previous chunk into this new chunk
-                    chunks
-                        .current
-                        .extend(previous_chunk.drain(previous_chunk_len - i..));
-                    chunks.current.push(elem);
-                    // And the remaining elements in the iterator
-                    chunks.current.extend(iter);
-                    next_item_index = 0;
-                    break;
-                } else {
-                    chunks.current.push(elem);
-                }
-                i += 1;
-            }
-        }
-        let new_slice_ref = &mut chunks.current[next_item_index..];
-
-        // Extend the lifetime from that of `chunks_borrow` to that of `self`.
-        // This is OK because we’re careful to never move items
-        // by never pushing to inner `Vec`s beyond their initial capacity.
-        // The returned reference is unique (`&mut`):
-        // the `Arena` never gives away references to existing items.
-        unsafe { mem::transmute::<&mut [T], &mut [T]>(new_slice_ref) }
+    #[inline]
+    pub fn alloc_indexed(&self, value: T) -> (usize, &mut T) {
+        let index = self.len();
+        (index, self.alloc(value))
     }
 
-    /// Allocates space for a given number of values, but doesn't initialize it.
-    ///
-    /// ## Safety
-    ///
-    /// After calling this method, the arena considers the elements initialized. If you fail to
-    /// initialize them (which includes because of panicking during the initialization), the arena
-    /// will run destructors on the uninitialized memory. Therefore, you must initialize them.
+    /// Allocates a value in the arena, and returns a mutable reference
+    /// to that value, or an error if memory for a new chunk could not be
+    /// obtained.
     ///
-    /// Considering how easy it is to cause undefined behaviour using this, you're advised to
-    /// prefer the other (safe) methods, like [`alloc_extend`][Arena::alloc_extend].
+    /// Where [`alloc`][Arena::alloc] aborts the process when the allocator
+    /// fails, this method gives the caller a chance to recover. On failure,
+    /// `value` is dropped and the arena is left unchanged.
     ///
     /// ## Example
     ///
-    /// ```rust
-    /// use std::mem::{self, MaybeUninit};
-    /// use std::ptr;
+    /// ```
     /// use typed_arena::Arena;
     ///
-    /// // Transmute from MaybeUninit slice to slice of initialized T.
-    /// // It is a separate function to preserve the lifetime of the reference.
-    /// unsafe fn transmute_uninit<A>(r: &mut [MaybeUninit<A>]) -> &mut [A] {
-    ///     mem::transmute(r)
-    /// }
+    /// let arena = Arena::new();
+    /// let x = arena.try_alloc(42).expect("out of memory");
+    /// assert_eq!(*x, 42);
+    /// ```
+    #[inline]
+    pub fn try_alloc(&self, value: T) -> Result<&mut T, AllocError> {
+        match self.alloc_fast_path(value) {
+            Ok(value) => Ok(value),
+            Err(value) => self.try_alloc_slow_path(value),
+        }
+    }
+
+    fn try_alloc_slow_path(&self, value: T) -> Result<&mut T, AllocError> {
+        self.chunks.borrow_mut().try_reserve(1)?;
+        match self.alloc_fast_path(value) {
+            Ok(value) => Ok(value),
+            Err(_) => unreachable!("a fresh chunk always has room for one value"),
+        }
+    }
+    /// Allocates a value in the arena, and returns a mutable reference to
+    /// that value, or gives the value back if the arena is locked.
+    ///
+    /// The arena is locked while a [`SubArena`], [`SliceBuilder`] or
+    /// `StrBuilder` of it is alive, or while it runs user code, such as the
+    /// iterator given to [`alloc_extend`][Arena::alloc_extend]. There,
+    /// [`alloc`][Arena::alloc] panics, which library code that cannot know
+    /// whether its caller opened a sub-arena may want to avoid.
+    ///
+    /// ## Example
     ///
-    /// let arena: Arena<bool> = Arena::new();
-    /// let slice: &mut [bool];
-    /// unsafe {
-    ///     let uninitialized = arena.alloc_uninitialized(10);
-    ///     for elem in uninitialized.iter_mut() {
-    ///         ptr::write(elem.as_mut_ptr(), true);
-    ///     }
-    ///     slice = transmute_uninit(uninitialized);
-    /// }
     /// ```
+    /// use typed_arena::{Arena, SubArena};
     ///
-    /// ## Alternative allocation pattern
+    /// let arena = Arena::new();
+    /// assert!(arena.try_alloc_unlocked(1).is_ok());
     ///
-    /// To avoid the problem of dropping assumed to be initialized elements on panic, it is also
-    /// possible to combine the [`reserve_extend`][Arena::reserve_extend] with
-    /// [`uninitialized_array`][Arena::uninitialized_array], initialize the elements and confirm
-    /// them by this method. In such case, when there's a panic during initialization, the already
-    /// initialized elements would leak but it wouldn't cause UB.
+    /// let sub_arena = SubArena::new(&arena);
+    /// let err = arena.try_alloc_unlocked(2).unwrap_err();
+    /// assert_eq!(*sub_arena.alloc(err.into_inner()), 2);
+    /// ```
+    pub fn try_alloc_unlocked(&self, value: T) -> Result<&mut T, ArenaLocked<T>> {
+        if self.is_locked() {
+            return Err(ArenaLocked(value));
+        }
+        Ok(self.alloc(value))
+    }
+
+    /// Returns `true` if the arena is locked, so that allocating in it
+    /// would panic.
     ///
-    /// ```rust
-    /// use std::mem::{self, MaybeUninit};
-    /// use std::ptr;
-    /// use typed_arena::Arena;
+    /// See [`try_alloc_unlocked`][Arena::try_alloc_unlocked].
+    pub fn is_locked(&self) -> bool {
+        self.chunks.try_borrow_mut().is_err()
+    }
+
+    /// Allocates a value in the arena by calling `f`, and returns a mutable
+    /// reference to that value.
     ///
-    /// unsafe fn transmute_uninit<A>(r: &mut [MaybeUninit<A>]) -> &mut [A] {
-    ///     mem::transmute(r)
-    /// }
+    /// Unlike [`alloc`][Arena::alloc], the result of `f` is written directly
+    /// into the arena's memory, which lets the compiler avoid building large
+    /// values on the stack and then moving them.
     ///
-    /// const COUNT: usize = 2;
+    /// The slot of the value is allocated before `f` runs, and the arena is
+    /// not borrowed meanwhile, so `f` may allocate other values in it, which
+    /// come after the slot. If `f` panics, the slot is given back, and the
+    /// values `f` allocated are leaked as with
+    /// [`alloc_cyclic`][Arena::alloc_cyclic].
     ///
-    /// let arena: Arena<String> = Arena::new();
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let big = arena.alloc_with(|| [42u8; 4096]);
+    /// assert!(big.iter().all(|&b| b == 42));
+    ///
+    /// let first = arena.alloc_with(|| {
+    ///     arena.alloc([1; 4096]);
+    ///     [0; 4096]
+    /// });
+    /// assert_eq!(first[0], 0);
+    /// assert_eq!(arena.len(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_with<F>(&self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        // The slot is given back if `f` panics.
+        self.alloc_slot().fill(f())
+    }
+
+    /// Allocates a slice of `len` values in the arena, where the value at
+    /// index `i` is produced by `f(i)`, and returns a mutable reference to
+    /// the slice.
+    ///
+    /// The values are written directly into the arena's memory. If `f`
+    /// panics, the values produced so far are dropped and the arena is left
+    /// as it was. Unlike with [`alloc_with`][Arena::alloc_with], `f` must not
+    /// allocate from this same arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let squares = arena.alloc_slice_fill_with(5, |i| i * i);
+    /// assert_eq!(squares, [0, 1, 4, 9, 16]);
+    /// ```
+    pub fn alloc_slice_fill_with<F>(&self, len: usize, mut f: F) -> &mut [T]
+    where
+        F: FnMut(usize) -> T,
+    {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        tail.reserve(len);
+        for i in 0..len {
+            tail.push(f(i));
+        }
+        unsafe { &mut *tail.finish() }
+    }
+
+    /// Allocates an array of `N` values in the arena, where the value at
+    /// index `i` is produced by `f(i)`, and returns a mutable reference to
+    /// the array.
+    ///
+    /// This behaves like [`alloc_slice_fill_with`][Arena::alloc_slice_fill_with],
+    /// but the length is part of the returned type.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let steps: &mut [f64; 4] = arena.alloc_array(|i| i as f64 * 0.5);
+    /// assert_eq!(*steps, [0.0, 0.5, 1.0, 1.5]);
+    /// ```
+    pub fn alloc_array<const N: usize, F>(&self, f: F) -> &mut [T; N]
+    where
+        F: FnMut(usize) -> T,
+    {
+        let slice = self.alloc_slice_fill_with(N, f);
+        // The slice has exactly `N` elements.
+        unsafe { &mut *(slice.as_mut_ptr() as *mut [T; N]) }
+    }
+    /// Uses the contents of an iterator to allocate values in the arena.
+    /// Returns a mutable slice that contains these values.
+    ///
+    /// Room for the lower bound of the iterator's [`size_hint`] is reserved
+    /// up front, so iterators of known length, like most
+    /// [`ExactSizeIterator`]s, are written in place in a single pass.
+    ///
+    /// If the iterator panics, the values it has already produced are
+    /// dropped, and the arena is left as it was.
+    ///
+    /// [`size_hint`]: Iterator::size_hint
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let abc = arena.alloc_extend("abcdefg".chars().take(3));
+    /// assert_eq!(abc, ['a', 'b', 'c']);
+    /// ```
+    pub fn alloc_extend<I>(&self, iterable: I) -> &mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        tail.extend(iterable.into_iter());
+        unsafe { &mut *tail.finish() }
+    }
+
+    /// Like [`alloc_extend`][Arena::alloc_extend], but returns an error
+    /// instead of aborting when a new chunk cannot be allocated.
+    ///
+    /// On failure, the values already taken from the iterator are dropped,
+    /// and the arena is left as it was.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let evens = arena.try_alloc_extend((0..10).filter(|n| n % 2 == 0));
+    /// assert_eq!(evens, Ok(&mut [0, 2, 4, 6, 8][..]));
+    /// ```
+    pub fn try_alloc_extend<I>(&self, iterable: I) -> Result<&mut [T], AllocError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iterable.into_iter();
+
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        let min_len = iter.size_hint().0;
+        tail.try_reserve(min_len)?;
+        tail.extend_reserved(&mut iter, min_len);
+        while let Some(value) = iter.next() {
+            if tail.is_full() {
+                tail.try_reserve(iter.size_hint().0.saturating_add(1))?;
+            }
+            tail.push(value);
+        }
+        Ok(unsafe { &mut *tail.finish() })
+    }
+    /// Allocates space for a given number of values, but doesn't initialize it.
+    ///
+    /// Once the values are written, [`Arena::assume_init_slice`] turns the
+    /// reference into a `&mut [T]`. Only the `len` requested values are
+    /// considered allocated: any unused capacity left at the end of a chunk
+    /// stays uninitialized and is never dropped.
+    ///
+    /// ## Safety
+    ///
+    /// After calling this method, the arena considers the elements initialized. If you fail to
+    /// initialize them (which includes because of panicking during the initialization), the arena
+    /// will run destructors on the uninitialized memory. Therefore, you must initialize them.
+    ///
+    /// Considering how easy it is to cause undefined behaviour using this, you're advised to
+    /// prefer the other (safe) methods, like [`alloc_extend`][Arena::alloc_extend].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<bool> = Arena::new();
+    /// let slice: &mut [bool];
+    /// unsafe {
+    ///     let uninitialized = arena.alloc_uninit_slice(10);
+    ///     for elem in uninitialized.iter_mut() {
+    ///         elem.write(true);
+    ///     }
+    ///     slice = Arena::assume_init_slice(uninitialized);
+    /// }
+    /// assert_eq!(slice, [true; 10]);
+    /// ```
+    ///
+    /// ## Alternative allocation pattern
+    ///
+    /// To avoid the problem of dropping assumed to be initialized elements on panic, it is also
+    /// possible to combine the [`reserve_extend`][Arena::reserve_extend] with
+    /// [`uninitialized_array`][Arena::uninitialized_array], initialize the elements and confirm
+    /// them by this method. In such case, when there's a panic during initialization, the already
+    /// initialized elements would leak but it wouldn't cause UB.
+    ///
+    /// ```rust
+    /// use typed_arena::Arena;
+    ///
+    /// const COUNT: usize = 2;
+    ///
+    /// let arena: Arena<String> = Arena::new();
     ///
     /// arena.reserve_extend(COUNT);
     /// let slice: &mut [String];
     /// unsafe {
     ///     // Perform initialization before we claim the memory.
     ///     let uninitialized = arena.uninitialized_array();
-    ///     assert!((*uninitialized).len() >= COUNT); // Ensured by the reserve_extend
-    ///     for elem in &mut (*uninitialized)[..COUNT] {
-    ///         ptr::write(elem.as_mut_ptr(), "Hello".to_owned());
+    ///     assert!((&*uninitialized).len() >= COUNT); // Ensured by the reserve_extend
+    ///     for elem in &mut (&mut *uninitialized)[..COUNT] {
+    ///         elem.write("Hello".to_owned());
     ///     }
-    ///     let addr = (*uninitialized).as_ptr() as usize;
+    ///     let addr = (&*uninitialized).as_ptr() as usize;
     ///
-    ///     // The alloc_uninitialized returns the same memory, but "confirms" its allocation.
-    ///     slice = transmute_uninit(arena.alloc_uninitialized(COUNT));
+    ///     // The alloc_uninit_slice returns the same memory, but "confirms" its allocation.
+    ///     slice = Arena::assume_init_slice(arena.alloc_uninit_slice(COUNT));
     ///     assert_eq!(addr, slice.as_ptr() as usize);
     ///     assert_eq!(slice, &["Hello".to_owned(), "Hello".to_owned()]);
     /// }
     /// ```
-    pub unsafe fn alloc_uninitialized(&self, num: usize) -> &mut [MaybeUninit<T>] {
+    pub unsafe fn alloc_uninit_slice(&self, len: usize) -> &mut [MaybeUninit<T>] {
         let mut chunks = self.chunks.borrow_mut();
 
         debug_assert!(
             chunks.current.capacity() >= chunks.current.len(),
             "capacity is always greater than or equal to len, so we don't need to worry about underflow"
         );
-        if num > chunks.current.capacity() - chunks.current.len() {
-            chunks.reserve(num);
+        if len > chunks.current.capacity() - chunks.current.len() {
+            chunks.reserve(len);
         }
 
         // At this point, the current chunk must have free capacity.
         let next_item_index = chunks.current.len();
-        chunks.current.set_len(next_item_index + num);
+        chunks.current.set_len(next_item_index + len);
 
         // Go through pointers, to make sure we never create a reference to uninitialized T.
-        let start = chunks.current.as_mut_ptr().offset(next_item_index as isize);
+        let start = chunks.current.as_mut_ptr().add(next_item_index);
         let start_uninit = start as *mut MaybeUninit<T>;
-        slice::from_raw_parts_mut(start_uninit, num)
+        slice::from_raw_parts_mut(start_uninit, len)
+    }
+
+    /// Allocates space for a given number of values, but doesn't initialize it.
+    ///
+    /// This is [`alloc_uninit_slice`][Arena::alloc_uninit_slice], under the
+    /// name it had before.
+    ///
+    /// ## Safety
+    ///
+    /// See [`alloc_uninit_slice`][Arena::alloc_uninit_slice].
+    pub unsafe fn alloc_uninitialized(&self, num: usize) -> &mut [MaybeUninit<T>] {
+        self.alloc_uninit_slice(num)
+    }
+
+    /// Makes sure the arena can hold at least `additional` more values without
+    /// asking the allocator for more memory.
+    ///
+    /// Unlike [`reserve_extend`][Arena::reserve_extend], the space does not
+    /// have to be contiguous, so the room left in the current chunk is not
+    /// wasted. This is meant for calling before a loop of single-value
+    /// allocations; slices allocated with [`alloc_extend`][Arena::alloc_extend]
+    /// and friends may still need a new chunk if they don't fit.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::with_capacity(4);
+    /// arena.reserve(100);
+    /// let bytes = arena.allocated_bytes();
+    /// for i in 0..100 {
+    ///     arena.alloc(i);
+    /// }
+    /// assert_eq!(arena.allocated_bytes(), bytes);
+    /// ```
+    pub fn reserve(&self, additional: usize) {
+        let mut chunks = self.chunks.borrow_mut();
+        let available = chunks
+            .spare
+            .iter()
+            .fold(chunks.current.capacity() - chunks.current.len(), |a, v| {
+                a.saturating_add(v.capacity())
+            });
+        if additional > available {
+            let capacity = chunks
+                .next_capacity(additional - available)
+                .expect("capacity overflow");
+            // Spare chunks are used from the back, and this one should come
+            // after the smaller ones already set aside.
+            let chunk = chunks.new_chunk(capacity);
+            chunks.spare.insert(0, chunk);
+        }
     }
 
     /// Makes sure there's enough continuous space for at least `num` elements.
     ///
     /// This may save some work if called before [`alloc_extend`][Arena::alloc_extend]. It also
-    /// allows somewhat safer use pattern of [`alloc_uninitialized`][Arena::alloc_uninitialized].
+    /// allows somewhat safer use pattern of [`alloc_uninit_slice`][Arena::alloc_uninit_slice].
     /// On the other hand this might waste up to `n - 1` elements of space. In case new allocation
     /// is needed, the unused ones in current chunk are never used.
     pub fn reserve_extend(&self, num: usize) {
@@ -392,7 +1140,7 @@ impl<T> Arena<T> {
     ///
     /// *This unused space is still not considered "allocated".* Therefore, it
     /// won't be dropped unless there are further calls to `alloc`,
-    /// [`alloc_uninitialized`][Arena::alloc_uninitialized], or
+    /// [`alloc_uninit_slice`][Arena::alloc_uninit_slice], or
     /// [`alloc_extend`][Arena::alloc_extend] which is why the method is safe.
     ///
     /// It returns a raw pointer to avoid creating multiple mutable references to the same place.
@@ -404,12 +1152,191 @@ impl<T> Arena<T> {
 
         unsafe {
             // Go through pointers, to make sure we never create a reference to uninitialized T.
-            let start = chunks.current.as_mut_ptr().offset(next_item_index as isize);
+            let start = chunks.current.as_mut_ptr().add(next_item_index);
             let start_uninit = start as *mut MaybeUninit<T>;
-            slice::from_raw_parts_mut(start_uninit, len) as *mut _
+            ptr::slice_from_raw_parts_mut(start_uninit, len)
+        }
+    }
+
+    /// Drops all the values in the arena, but keeps its chunks for reuse.
+    ///
+    /// Later allocations fill the chunks again in the order they were first
+    /// used, so an arena that is cleared and refilled in a loop stops asking
+    /// the allocator for memory once it is warmed up.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(4);
+    /// arena.alloc_extend(0..100);
+    /// let bytes = arena.allocated_bytes();
+    /// for _ in 0..10 {
+    ///     arena.clear();
+    ///     assert!(arena.is_empty());
+    ///     arena.alloc_extend(0..100);
+    ///     assert_eq!(arena.allocated_bytes(), bytes);
+    /// }
+    /// ```
+    pub fn clear(&mut self) {
+        let chunks = self.chunk_list_mut();
+        chunks.record_peak();
+        #[cfg(feature = "tracing")]
+        instrument::cleared(chunks.label, chunks.len());
+        for chunk in &mut chunks.rest {
+            chunk.clear();
+        }
+        chunks.current.clear();
+        chunks.pending.clear();
+        chunks.abandoned.clear();
+        chunks.forgotten = 0;
+        // Including those `shrink_to_fit` freed.
+        chunks.rest.retain(|chunk| chunk.capacity() != 0);
+
+        if !chunks.rest.is_empty() {
+            // Start again from the first chunk and set the others aside, so
+            // that they are reused in the same order.
+            let first = chunks.rest.remove(0);
+            let last = mem::replace(&mut chunks.current, first);
+            // Unless `alloc_cyclic` retired it, and it was never allocated.
+            if last.capacity() != 0 {
+                chunks.spare.push(last);
+            }
+            while let Some(chunk) = chunks.rest.pop() {
+                chunks.spare.push(chunk);
+            }
+        }
+        chunks.advise_emptied();
+    }
+
+    /// Drops all the values in the arena, and frees every chunk but the
+    /// largest one, which is kept for reuse.
+    ///
+    /// Unlike [`clear`][Arena::clear], this consolidates the arena into a
+    /// single chunk, so that a long series of fills and resets doesn't keep a
+    /// fragmented list of ever smaller chunks around.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(1);
+    /// for i in 0..100 {
+    ///     arena.alloc(i);
+    /// }
+    /// assert!(arena.chunk_count() > 1);
+    ///
+    /// arena.reset();
+    /// assert!(arena.is_empty());
+    /// assert_eq!(arena.chunk_count(), 1);
+    /// assert_eq!(arena.capacity(), 64);
+    /// ```
+    pub fn reset(&mut self) {
+        let chunks = self.chunk_list_mut();
+        chunks.record_peak();
+        #[cfg(feature = "tracing")]
+        instrument::cleared(chunks.label, chunks.len());
+        for chunk in &mut chunks.rest {
+            chunk.clear();
+        }
+        chunks.current.clear();
+        chunks.pending.clear();
+        chunks.abandoned.clear();
+        chunks.forgotten = 0;
+
+        let empty = chunks.empty_chunk();
+        let mut largest = mem::replace(&mut chunks.current, empty);
+        for chunk in chunks.rest.drain(..).chain(chunks.spare.drain(..)) {
+            if chunk.capacity() > largest.capacity() {
+                largest = chunk;
+            }
+        }
+        chunks.current = largest;
+        chunks.advise_emptied();
+    }
+
+    /// Returns unused chunk memory to the allocator.
+    ///
+    /// Spare and empty chunks are freed, and the remaining chunks are shrunk
+    /// to fit the values they hold. The values themselves are kept, but may
+    /// be moved in memory; this is fine since taking `&mut self` proves no
+    /// references to them are alive. The freed empty chunks keep their place
+    /// among the others, so [markers][Arena::marker] stay valid.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(1024);
+    /// arena.alloc_extend(0..10);
+    /// arena.shrink_to_fit();
+    /// assert_eq!(arena.capacity(), 10);
+    /// assert_eq!(arena.into_vec(), (0..10).collect::<Vec<_>>());
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let chunks = self.chunk_list_mut();
+        chunks.spare = Vec::new();
+        // Empty chunks are freed, and left in place for the markers.
+        for chunk in &mut chunks.rest {
+            chunk.shrink_to_fit();
         }
+        chunks.rest.shrink_to_fit();
+        let len = chunks.current.len();
+        chunks.current.shrink_to(cmp::max(MIN_CAPACITY, len));
     }
 
+    /// Takes over the chunks of `other`, so that its values live and drop
+    /// with this arena.
+    ///
+    /// No value is moved or copied. The values of `other` come after those
+    /// of this arena in allocation order, and new values go into the last
+    /// chunk of `other`, so whatever room is left in the current chunk of
+    /// this arena is no longer used. This combines the arenas filled by
+    /// parallel workers.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::thread;
+    /// use typed_arena::Arena;
+    ///
+    /// let workers: Vec<_> = (0..4)
+    ///     .map(|i| {
+    ///         thread::spawn(move || {
+    ///             let arena = Arena::new();
+    ///             arena.alloc_extend(i * 10..i * 10 + 3);
+    ///             arena
+    ///         })
+    ///     })
+    ///     .collect();
+    ///
+    /// let mut all = Arena::new();
+    /// for worker in workers {
+    ///     all.absorb(worker.join().unwrap());
+    /// }
+    /// assert_eq!(all.len(), 12);
+    /// assert_eq!(all.get(4), Some(&11));
+    /// ```
+    pub fn absorb(&mut self, other: in_alloc!(Arena<T>)) {
+        let mut other = other.into_chunk_list();
+        if other.rest.is_empty() && other.current.is_empty() {
+            return;
+        }
+        let chunks = self.chunk_list_mut();
+        let previous_chunk = mem::replace(&mut chunks.current, other.current);
+        if previous_chunk.capacity() != 0 {
+            chunks.rest.push(previous_chunk);
+        }
+        if other.forgotten != 0 {
+            chunks.forgotten = chunks.rest.len() + other.forgotten;
+        }
+        chunks.rest.append(&mut other.rest);
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        chunks.meter.absorb(&mut other.meter);
+    }
     /// Convert this `Arena` into a `Vec<T>`.
     ///
     /// Items in the resulting `Vec<T>` appear in the order that they were
@@ -430,70 +1357,233 @@ impl<T> Arena<T> {
     ///
     /// assert_eq!(easy_as_123, vec!["a", "b", "c"]);
     /// ```
-    pub fn into_vec(self) -> Vec<T> {
-        let mut chunks = self.chunks.into_inner();
+    pub fn into_vec(self) -> in_alloc!(Vec<T>) {
+        let mut chunks = self.into_chunk_list();
         // keep order of allocation in the resulting Vec
         let n = chunks
             .rest
             .iter()
             .fold(chunks.current.len(), |a, v| a + v.len());
-        let mut result = Vec::with_capacity(n);
+        let mut result = chunks.empty_chunk();
+        result.reserve_exact(n);
         for mut vec in chunks.rest {
             result.append(&mut vec);
         }
         result.append(&mut chunks.current);
         result
     }
-
-    /// Returns an iterator that allows modifying each value.
+    /// Returns a reference to the value at `index` in allocation order, or
+    /// `None` if fewer values have been allocated.
     ///
-    /// Items are yielded in the order that they were allocated.
+    /// This takes `&mut self` to prove that no mutable reference to the
+    /// value is still alive. Lookups walk the list of chunks, which grows
+    /// logarithmically with the number of values.
     ///
     /// ## Example
     ///
     /// ```
     /// use typed_arena::Arena;
     ///
-    /// #[derive(Debug, PartialEq, Eq)]
-    /// struct Point { x: i32, y: i32 };
-    ///
     /// let mut arena = Arena::new();
+    /// arena.alloc_extend(10..20);
+    /// assert_eq!(arena.get(3), Some(&13));
+    /// assert_eq!(arena.get(10), None);
+    /// ```
+    pub fn get(&mut self, index: usize) -> Option<&T> {
+        self.get_mut(index).map(|value| &*value)
+    }
+
+    /// Returns a mutable reference to the value at `index` in allocation
+    /// order, or `None` if fewer values have been allocated.
     ///
-    /// arena.alloc(Point { x: 0, y: 0 });
-    /// arena.alloc(Point { x: 1, y: 1 });
+    /// ## Example
     ///
-    /// for point in arena.iter_mut() {
-    ///     point.x += 10;
-    /// }
+    /// ```
+    /// use typed_arena::Arena;
     ///
-    /// let points = arena.into_vec();
+    /// let mut arena = Arena::new();
+    /// let (index, _) = arena.alloc_indexed(1);
+    /// *arena.get_mut(index).unwrap() += 1;
+    /// assert_eq!(arena.get(index), Some(&2));
+    /// ```
+    pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        let chunks = self.chunk_list_mut();
+        for chunk in chunks
+            .rest
+            .iter_mut()
+            .chain(iter::once(&mut chunks.current))
+        {
+            if index < chunk.len() {
+                return chunk.get_mut(index);
+            }
+            index -= chunk.len();
+        }
+        None
+    }
+
+    // Like `get`, but through a shared reference. The caller must make sure
+    // that no mutable reference to the value is alive, for as long as the
+    // returned reference is.
+    pub(crate) unsafe fn get_shared(&self, mut index: usize) -> Option<&T> {
+        let chunks = self.chunks.borrow();
+        for chunk in chunks.rest.iter().chain(iter::once(&chunks.current)) {
+            if index < chunk.len() {
+                // Values never move, so the reference outlives the borrow.
+                return Some(&*chunk.as_ptr().add(index));
+            }
+            index -= chunk.len();
+        }
+        None
+    }
+
+    /// Returns a reference to the first value allocated in the arena, or
+    /// `None` if it is empty.
     ///
-    /// assert_eq!(points, vec![Point { x: 10, y: 0 }, Point { x: 11, y: 1 }]);
+    /// This takes `&mut self` to prove that no mutable reference to the
+    /// value is still alive.
+    ///
+    /// ## Example
     ///
     /// ```
+    /// use typed_arena::Arena;
     ///
-    /// ## Immutable Iteration
+    /// let mut arena = Arena::new();
+    /// assert_eq!(arena.first(), None);
+    /// arena.alloc(1);
+    /// arena.alloc(2);
+    /// assert_eq!(arena.first(), Some(&1));
+    /// ```
+    pub fn first(&mut self) -> Option<&T> {
+        let chunks = self.chunk_list_mut();
+        chunks
+            .rest
+            .iter()
+            .chain(iter::once(&chunks.current))
+            .find_map(|chunk| chunk.first())
+    }
+
+    /// Returns a reference to the most recently allocated value, or `None`
+    /// if the arena is empty.
     ///
-    /// Note that there is no corresponding `iter` method. Access to the arena's contents
-    /// requries mutable access to the arena itself.
+    /// This takes `&mut self` to prove that no mutable reference to the
+    /// value is still alive.
     ///
-    /// ```compile_fail
+    /// ## Example
+    ///
+    /// ```
     /// use typed_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let x = arena.alloc(1);
+    /// assert_eq!(arena.last(), None);
+    /// arena.alloc(1);
+    /// arena.alloc(2);
+    /// assert_eq!(arena.last(), Some(&2));
+    /// ```
+    pub fn last(&mut self) -> Option<&T> {
+        self.last_mut().map(|last| &*last)
+    }
+
+    /// Returns a mutable reference to the most recently allocated value, or
+    /// `None` if the arena is empty.
     ///
-    /// // borrow error!
-    /// for i in arena.iter_mut() {
-    ///     println!("i: {}", i);
-    /// }
+    /// ## Example
     ///
-    /// // borrow error!
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc(1);
+    /// arena.alloc(2);
+    /// *arena.last_mut().unwrap() += 40;
+    /// assert_eq!(arena.into_vec(), [1, 42]);
+    /// ```
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        let chunks = self.chunk_list_mut();
+        iter::once(&mut chunks.current)
+            .chain(chunks.rest.iter_mut().rev())
+            .find_map(|chunk| chunk.last_mut())
+    }
+
+    /// Moves the most recently allocated value into `to`, and returns a
+    /// mutable reference to it there, or `None` if the arena is empty.
+    ///
+    /// This promotes a speculative value from a scratch arena to a
+    /// long-lived one once it turns out to be needed: the value is moved,
+    /// not cloned, and only drops with `to`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let long_lived = Arena::new();
+    /// let mut scratch = Arena::new();
+    /// scratch.alloc(String::from("discarded"));
+    /// scratch.alloc(String::from("kept"));
+    ///
+    /// let kept = scratch.transfer_last(&long_lived).unwrap();
+    /// scratch.clear();
+    /// assert_eq!(kept, "kept");
+    /// assert_eq!(long_lived.len(), 1);
+    /// ```
+    pub fn transfer_last<'b>(&mut self, to: &'b in_alloc!(Arena<T>)) -> Option<&'b mut T> {
+        let chunks = self.chunk_list_mut();
+        chunks.record_peak();
+        let value = iter::once(&mut chunks.current)
+            .chain(chunks.rest.iter_mut().rev())
+            .find_map(|chunk| chunk.pop())?;
+        Some(to.alloc(value))
+    }
+
+    /// Returns an iterator that allows modifying each value.
+    ///
+    /// Items are yielded in the order that they were allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Point { x: i32, y: i32 };
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// arena.alloc(Point { x: 0, y: 0 });
+    /// arena.alloc(Point { x: 1, y: 1 });
+    ///
+    /// for point in arena.iter_mut() {
+    ///     point.x += 10;
+    /// }
+    ///
+    /// let points = arena.into_vec();
+    ///
+    /// assert_eq!(points, vec![Point { x: 10, y: 0 }, Point { x: 11, y: 1 }]);
+    ///
+    /// ```
+    ///
+    /// ## Immutable Iteration
+    ///
+    /// Note that the corresponding [`iter`][Arena::iter] method takes `&mut self` as well. Access
+    /// to the arena's contents requries mutable access to the arena itself.
+    ///
+    /// ```compile_fail
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let x = arena.alloc(1);
+    ///
+    /// // borrow error!
+    /// for i in arena.iter_mut() {
+    ///     println!("i: {}", i);
+    /// }
+    ///
+    /// // borrow error!
     /// *x = 2;
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        let chunks = self.chunks.get_mut();
+    pub fn iter_mut(&mut self) -> in_alloc!(IterMut<'_, T>) {
+        let chunks = self.chunk_list_mut();
         let position = if !chunks.rest.is_empty() {
             let index = 0;
             let inner_iter = chunks.rest[index].iter_mut();
@@ -501,11 +1591,14 @@ impl<T> Arena<T> {
             // This is OK because we borrow the arena mutably to prevent new allocations
             // and we take care here to never move items inside the arena while the
             // iterator is alive.
-            let inner_iter = unsafe { mem::transmute(inner_iter) };
+            let inner_iter =
+                unsafe { mem::transmute::<slice::IterMut<T>, slice::IterMut<T>>(inner_iter) };
             IterMutState::ChunkListRest { index, inner_iter }
         } else {
             // Extend the lifetime of the individual elements to that of the arena.
-            let iter = unsafe { mem::transmute(chunks.current.iter_mut()) };
+            let iter = unsafe {
+                mem::transmute::<slice::IterMut<T>, slice::IterMut<T>>(chunks.current.iter_mut())
+            };
             IterMutState::ChunkListCurrent { iter }
         };
         IterMut {
@@ -513,9 +1606,303 @@ impl<T> Arena<T> {
             state: position,
         }
     }
+
+    /// Returns an iterator over shared references to each value.
+    ///
+    /// Items are yielded in the order that they were allocated.
+    ///
+    /// Like [`iter_mut`][Arena::iter_mut], this borrows the arena mutably,
+    /// which proves that no references to its values are still alive.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc(1);
+    /// arena.alloc(2);
+    ///
+    /// let sum: i32 = arena.iter().sum();
+    /// assert_eq!(sum, 3);
+    /// ```
+    #[inline]
+    pub fn iter(&mut self) -> in_alloc!(Iter<'_, T>) {
+        Iter {
+            inner: self.iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over the values of each chunk, as slices.
+    ///
+    /// The values of a chunk are contiguous, so batch operations can work
+    /// on whole slices instead of one value at a time. Slices are yielded
+    /// in allocation order, and empty chunks are skipped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(2);
+    /// arena.alloc_extend(0..2);
+    /// arena.alloc_extend(2..5);
+    /// let chunks: Vec<&[i32]> = arena.chunks().collect();
+    /// assert_eq!(chunks, [&[0, 1][..], &[2, 3, 4]]);
+    /// ```
+    pub fn chunks(&mut self) -> in_alloc!(Chunks<'_, T>) {
+        Chunks {
+            inner: self.chunks_mut(),
+        }
+    }
+
+    /// Returns an iterator over the values of each chunk, as mutable
+    /// slices.
+    ///
+    /// See [`chunks`][Arena::chunks].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(2);
+    /// arena.alloc_extend(0..10);
+    /// for chunk in arena.chunks_mut() {
+    ///     chunk.reverse();
+    /// }
+    /// # assert_eq!(arena.len(), 10);
+    /// ```
+    pub fn chunks_mut(&mut self) -> in_alloc!(ChunksMut<'_, T>) {
+        let chunks = self.chunk_list_mut();
+        ChunksMut {
+            rest: chunks.rest.iter_mut(),
+            current: Some(&mut chunks.current),
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    /// Returns a read-only view of the arena's values, which can be shared
+    /// between threads when `T: Sync`.
+    ///
+    /// The arena is borrowed mutably for as long as the view is used, so no
+    /// values can be allocated meanwhile.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let (index, _) = arena.alloc_indexed("hello");
+    /// let shared = arena.share();
+    /// assert_eq!(shared.get(index), Some(&"hello"));
+    /// assert_eq!(shared.len(), 1);
+    /// ```
+    pub fn share(&mut self) -> SyncArena<'_, T> {
+        let chunks = self.chunk_list_mut();
+        SyncArena::new(&chunks.rest, &chunks.current)
+    }
+
+    /// Turn the arena into a [`FrozenArena`], which can't allocate anymore
+    /// but is `Sync` when `T: Sync`.
+    ///
+    /// The values stay where they are. Spare chunks are freed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// arena.alloc_extend(["a", "b", "c"]);
+    /// let frozen = arena.freeze();
+    /// assert_eq!(frozen.iter().copied().collect::<String>(), "abc");
+    /// ```
+    pub fn freeze(self) -> FrozenArena<T> {
+        let chunks = self.into_chunk_list();
+        FrozenArena::new(chunks.rest, chunks.current)
+    }
+}
+
+impl<T: Default, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Allocates a default-initialized value in the arena, and returns a
+    /// mutable reference to that value.
+    ///
+    /// The value is constructed in place, as with
+    /// [`alloc_with`][Arena::alloc_with].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<Vec<u32>> = Arena::new();
+    /// let v = arena.alloc_default();
+    /// v.push(1);
+    /// assert_eq!(*v, [1]);
+    /// ```
+    #[inline]
+    pub fn alloc_default(&self) -> &mut T {
+        self.alloc_with(T::default)
+    }
+}
+
+impl<T: Clone, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Clones a slice into the arena, and returns a mutable reference to
+    /// the clones.
+    ///
+    /// If cloning one of the elements panics, the clones made so far are
+    /// dropped and the arena is left as it was.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let names = ["Goku".to_string(), "Vegeta".to_string()];
+    /// let copy = arena.alloc_slice_clone(&names);
+    /// copy[1].push_str(" Jr.");
+    /// assert_eq!(copy, ["Goku", "Vegeta Jr."]);
+    /// ```
+    pub fn alloc_slice_clone(&self, src: &[T]) -> &mut [T] {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        tail.reserve(src.len());
+        // There is enough capacity, so this never reallocates.
+        tail.chunks.current.extend_from_slice(src);
+        unsafe { &mut *tail.finish() }
+    }
+
+    /// Appends the values of `iterable` to `slice`, and returns the longer
+    /// slice.
+    ///
+    /// If `slice` is the last allocation of the arena, the new values are
+    /// written right after it, and it only moves if the chunk is full.
+    /// Otherwise, `slice` is cloned into a new allocation along with the new
+    /// values, and the old values stay where they are. Slices of zero-sized
+    /// values are always cloned.
+    ///
+    /// If the iterator panics, the new values are dropped, but those of
+    /// `slice` stay in the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let mut args = arena.alloc_extend(vec![1, 2]);
+    /// let start = args.as_ptr();
+    /// args = arena.grow_last(args, Some(3));
+    /// assert_eq!(args, [1, 2, 3]);
+    /// assert_eq!(args.as_ptr(), start);
+    /// ```
+    pub fn grow_last<'a, I>(&'a self, slice: &'a mut [T], iterable: I) -> &'a mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut chunks = self.chunks.borrow_mut();
+        let len = slice.len();
+        let current_len = chunks.current.len();
+        // Slices of zero-sized values all start at the same dangling
+        // address, whatever arena they come from, so they are always cloned.
+        let at_end = len > 0
+            && len <= current_len
+            && mem::size_of::<T>() != 0
+            && ptr::eq(slice.as_ptr(), unsafe {
+                chunks.current.as_ptr().add(current_len - len)
+            });
+        if at_end {
+            // `slice` was moved in, so nothing else refers to its values,
+            // which may move along with the new ones. It stays borrowed
+            // until we return, though, so they are only ever accessed
+            // through it: the tail only holds the new values, and the chunk
+            // never grows in place.
+            let mut values: *const T = slice.as_ptr();
+            let mut tail = Tail::new(&mut chunks);
+            let mut iter = iterable.into_iter();
+            let room = tail.chunks.current.capacity() - current_len;
+            let min_len = cmp::min(iter.size_hint().0, room);
+            tail.extend_reserved(&mut iter, min_len);
+            while let Some(value) = iter.next() {
+                if tail.is_full() {
+                    let additional = iter.size_hint().0.saturating_add(1);
+                    tail.chunks
+                        .move_slice_and_tail(values, len, &mut tail.start, additional);
+                    // Out of the memory `slice` borrows.
+                    values = tail.chunks.current.as_ptr();
+                }
+                tail.push(value);
+            }
+            // Wherever it is now, `slice` is right before the new values.
+            let new_len = tail.chunks.current.len() - tail.start;
+            let new_values = tail.finish() as *mut T;
+            return unsafe {
+                &mut *ptr::slice_from_raw_parts_mut(new_values.sub(len), len + new_len)
+            };
+        }
+        drop(chunks);
+        self.alloc_extend(slice.iter().cloned().chain(iterable))
+    }
+
+    /// Allocates `n` clones of `value` contiguously in the arena, and returns
+    /// a mutable reference to them.
+    ///
+    /// For byte-like types, this compiles down to a `memset`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let row = arena.alloc_many(4, 0.0);
+    /// row[2] = 1.0;
+    /// assert_eq!(row, [0.0, 0.0, 1.0, 0.0]);
+    /// ```
+    pub fn alloc_many(&self, n: usize, value: T) -> &mut [T] {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        tail.reserve(n);
+        let new_len = tail.chunks.current.len() + n;
+        // There is enough capacity, so this never reallocates.
+        tail.chunks.current.resize(new_len, value);
+        unsafe { &mut *tail.finish() }
+    }
 }
 
-impl Arena<u8> {
+impl<T: Copy, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Copies a slice into the arena, and returns a mutable reference to
+    /// the copy.
+    ///
+    /// This is a single `memcpy`, which is faster than going through
+    /// [`alloc_extend`][Arena::alloc_extend] with an iterator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let copy = arena.alloc_slice_copy(&[1, 2, 3]);
+    /// copy[0] = 4;
+    /// assert_eq!(copy, [4, 2, 3]);
+    /// ```
+    pub fn alloc_slice_copy(&self, src: &[T]) -> &mut [T] {
+        unsafe {
+            // `T: Copy`, so it has no destructor and nothing can panic before
+            // the memory is fully initialized.
+            let dst = self.alloc_uninit_slice(src.len()).as_mut_ptr() as *mut T;
+            ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+            slice::from_raw_parts_mut(dst, src.len())
+        }
+    }
+}
+
+impl<#[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<u8>) {
     /// Allocates a string slice and returns a mutable reference to it.
     ///
     /// This is on `Arena<u8>`, because string slices use byte slices (`[u8]`) as their backing
@@ -532,10 +1919,223 @@ impl Arena<u8> {
     /// ```
     #[inline]
     pub fn alloc_str(&self, s: &str) -> &mut str {
-        let buffer = self.alloc_extend(s.bytes());
+        let buffer = self.alloc_slice_copy(s.as_bytes());
         // Can't fail the utf8 validation, it already came in as utf8
         unsafe { str::from_utf8_unchecked_mut(buffer) }
     }
+
+    /// Formats `args` directly into the arena, and returns a mutable
+    /// reference to the resulting string.
+    ///
+    /// The arena is borrowed while formatting, so the `Display` (or other
+    /// formatting) implementations involved must not allocate from this same
+    /// arena; doing so panics.
+    ///
+    /// ## Panics
+    ///
+    /// Like [`format!`], this panics if a formatting trait implementation
+    /// returns an error. Nothing is allocated in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<u8> = Arena::new();
+    /// let (file, line) = ("main.rs", 42);
+    /// let location = arena.alloc_fmt(format_args!("{}:{}", file, line));
+    /// assert_eq!(location, "main.rs:42");
+    /// ```
+    pub fn alloc_fmt(&self, args: fmt::Arguments) -> &mut str {
+        if let Some(s) = args.as_str() {
+            return self.alloc_str(s);
+        }
+
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        fmt::Write::write_fmt(&mut tail, args)
+            .expect("a formatting trait implementation returned an error");
+        unsafe {
+            let bytes = &mut *tail.finish();
+            // Only whole `str`s were written.
+            str::from_utf8_unchecked_mut(bytes)
+        }
+    }
+
+    /// Allocates `len` zeroed bytes whose address is a multiple of `align`,
+    /// and returns a mutable reference to them.
+    ///
+    /// This is meant for buffers with stricter alignment requirements than
+    /// bytes, such as SIMD or DMA buffers. Up to `align - 1` bytes of padding
+    /// are allocated before the buffer.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<u8> = Arena::new();
+    /// arena.alloc(1);
+    /// let buffer = arena.alloc_bytes_aligned(256, 64);
+    /// assert_eq!(buffer.as_ptr() as usize % 64, 0);
+    /// assert_eq!(buffer.len(), 256);
+    /// ```
+    pub fn alloc_bytes_aligned(&self, len: usize, align: usize) -> &mut [u8] {
+        assert!(align.is_power_of_two(), "alignment must be a power of two");
+        let padded_len = {
+            let chunks = self.chunks.borrow();
+            let end = chunks.current.as_ptr() as usize + chunks.current.len();
+            let padding = end.wrapping_neg() & (align - 1);
+            let room = chunks.current.capacity() - chunks.current.len();
+            match padding.checked_add(len) {
+                Some(padded_len) if padded_len <= room => padded_len,
+                // The alignment of a new chunk is unknown.
+                _ => len.checked_add(align - 1).expect("capacity overflow"),
+            }
+        };
+        let bytes = self.alloc_many(padded_len, 0);
+        let offset = bytes.as_ptr().align_offset(align);
+        &mut bytes[offset..offset + len]
+    }
+}
+
+/// The error returned by fallible allocation methods, such as
+/// [`Arena::try_alloc`], when the allocator could not provide a new chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
+
+/// The error returned by [`Arena::try_alloc_unlocked`] when the arena is
+/// locked, for example by a [`SubArena`]. It holds the value that was not
+/// allocated.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ArenaLocked<T>(T);
+
+impl<T> ArenaLocked<T> {
+    /// Returns the value that was not allocated.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for ArenaLocked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ArenaLocked(..)")
+    }
+}
+
+impl<T> fmt::Display for ArenaLocked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the arena is in use by a sub-arena or builder")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for ArenaLocked<T> {}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> IntoIterator
+    for in_alloc!(Arena<T>)
+{
+    type Item = T;
+    type IntoIter = in_alloc!(IntoIter<T>);
+
+    /// Returns an iterator that moves the values out of the arena.
+    ///
+    /// Items are yielded in the order that they were allocated, without
+    /// first being copied into a single `Vec` like
+    /// [`into_vec`][Arena::into_vec] does.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::with_capacity(1);
+    /// arena.alloc("a".to_string());
+    /// arena.alloc("b".to_string());
+    ///
+    /// let mut strings = arena.into_iter();
+    /// assert_eq!(strings.next().as_deref(), Some("a"));
+    /// assert_eq!(strings.next().as_deref(), Some("b"));
+    /// assert_eq!(strings.next(), None);
+    /// ```
+    fn into_iter(self) -> in_alloc!(IntoIter<T>) {
+        let chunks = self.into_chunk_list();
+        let mut all = chunks.rest;
+        all.push(chunks.current);
+        let len = all.iter().map(Vec::len).sum();
+        IntoIter {
+            iter: all.into_iter().flatten(),
+            len,
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Arena<T> {
+    /// Creates an arena holding the values of an iterator, all in one chunk.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<_> = (1..=3).map(|n| n * 10).collect();
+    /// assert_eq!(arena.into_vec(), [10, 20, 30]);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
+        Arena::from_vec(iterable.into_iter().collect())
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> Extend<T> for in_alloc!(Arena<T>) {
+    /// Allocates the values of an iterator in the arena, like
+    /// [`alloc_extend`][Arena::alloc_extend].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(vec![1, 2]);
+    /// arena.extend(3..5);
+    /// assert_eq!(arena.into_vec(), [1, 2, 3, 4]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        self.alloc_extend(iterable);
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> Extend<T> for &in_alloc!(Arena<T>) {
+    /// Allocates the values of an iterator in the arena, like
+    /// [`alloc_extend`][Arena::alloc_extend], through a shared reference.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let first = arena.alloc(1);
+    /// (&arena).extend(2..4);
+    /// *first += 10;
+    /// assert_eq!(arena.into_vec(), [11, 2, 3]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        self.alloc_extend(iterable);
+    }
 }
 
 impl<T> Default for Arena<T> {
@@ -544,21 +2144,577 @@ impl<T> Default for Arena<T> {
     }
 }
 
-impl<T> ChunkList<T> {
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> fmt::Debug for in_alloc!(Arena<T>) {
+    /// Shows how many values the arena holds and how much memory they use,
+    /// without requiring `T: Debug`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u32>::with_capacity(4);
+    /// arena.alloc_extend(0..3);
+    /// assert_eq!(
+    ///     format!("{:?}", arena),
+    ///     "Arena { len: 3, chunk_count: 1, used_bytes: 12 }",
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.chunks.try_borrow().is_err() {
+            // Formatting from within an allocation, or while a
+            // `SliceBuilder` is alive.
+            return f.debug_struct("Arena").finish_non_exhaustive();
+        }
+        let mut f = f.debug_struct("Arena");
+        if let Some(label) = self.chunks.borrow().label {
+            f.field("label", &label);
+        }
+        f.field("len", &self.len())
+            .field("chunk_count", &self.chunk_count())
+            .field("used_bytes", &self.used_bytes())
+            .finish()
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    // The chunks of the arena, when `&mut self` proves that nothing borrows
+    // them, not even a `SubArena` or a `SliceBuilder` that was leaked with
+    // `mem::forget`, and whose borrow of the arena is reset.
+    pub(crate) fn chunk_list_mut(&mut self) -> &mut in_alloc!(ChunkList<T>) {
+        if self.chunks.try_borrow_mut().is_err() {
+            let empty = ChunkList::new(self.chunks.get_mut().empty_chunk());
+            let chunks = mem::replace(self.chunks.get_mut(), empty);
+            self.chunks = RefCell::new(chunks);
+        }
+        let chunks = self.chunks.get_mut();
+        chunks.reclaim_lent();
+        chunks
+    }
+
+    // Like `chunk_list_mut`, but takes the chunks.
+    pub(crate) fn into_chunk_list(self) -> in_alloc!(ChunkList<T>) {
+        let mut chunks = self.chunks.into_inner();
+        chunks.reclaim_lent();
+        chunks
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(ChunkList<T>) {
+    pub(crate) const fn new(current: in_alloc!(Vec<T>)) -> Self {
+        ChunkList {
+            current,
+            rest: Vec::new(),
+            spare: Vec::new(),
+            max_chunk_size: usize::MAX,
+            growth: None,
+            alloc_error_handler: None,
+            pending: Vec::new(),
+            abandoned: Vec::new(),
+            forgotten: 0,
+            peak: 0,
+            label: None,
+            lent: None,
+            reserved: None,
+            #[cfg(all(feature = "mmap", target_os = "linux"))]
+            advice: mmap::Advice::NONE,
+            #[cfg(any(feature = "metrics", feature = "tracing"))]
+            meter: instrument::Meter::new(),
+            #[cfg(feature = "std")]
+            recycler: None,
+        }
+    }
+
+    // Takes back the chunk lent to a `SubArena` that was leaked with
+    // `mem::forget`. The values of the sub-arena, and the chunks it was
+    // lent or added, are leaked along with it, but the values of the arena
+    // are still at the start of that chunk, which didn't move: a sub-arena
+    // never moves the values of its parent, and a chunk only grows in place
+    // while it holds nothing but the allocation in progress.
+    fn reclaim_lent(&mut self) {
+        if let Some(lent) = self.lent.take() {
+            if lent.len != 0 {
+                self.current = unsafe {
+                    self.chunk_from_raw_parts(lent.start as *mut T, lent.len, lent.capacity)
+                };
+            }
+        }
+    }
+
+    // An empty chunk, which allocates from the same allocator as the others
+    // once it grows.
+    #[cfg(not(feature = "allocator_api"))]
+    fn empty_chunk(&self) -> Vec<T> {
+        Vec::new()
+    }
+
+    #[cfg(feature = "allocator_api")]
+    fn empty_chunk(&self) -> Vec<T, A> {
+        Vec::new_in(self.current.allocator().clone())
+    }
+
+    // Like `Vec::from_raw_parts`, for memory from the allocator of the
+    // chunks.
+    #[cfg(not(feature = "allocator_api"))]
+    unsafe fn chunk_from_raw_parts(&self, ptr: *mut T, len: usize, capacity: usize) -> Vec<T> {
+        Vec::from_raw_parts(ptr, len, capacity)
+    }
+
+    #[cfg(feature = "allocator_api")]
+    unsafe fn chunk_from_raw_parts(&self, ptr: *mut T, len: usize, capacity: usize) -> Vec<T, A> {
+        Vec::from_raw_parts_in(ptr, len, capacity, self.current.allocator().clone())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        let lent = self.lent.map_or(0, |lent| lent.len);
+        self.rest.iter().map(Vec::len).sum::<usize>() + self.current.len() + lent
+    }
+
+    // Remembers the number of values before some of them are dropped.
+    pub(crate) fn record_peak(&mut self) {
+        self.peak = cmp::max(self.peak, self.len());
+    }
+
+    // Allocates a chunk for `capacity` elements, reusing one from the
+    // recycler if possible.
+    fn new_chunk(&mut self, capacity: usize) -> in_alloc!(Vec<T>) {
+        #[cfg(feature = "std")]
+        if let Some(chunk) = self.recycler.as_ref().and_then(|r| r.take(capacity)) {
+            return chunk;
+        }
+        let mut chunk = self.empty_chunk();
+        if self.alloc_error_handler.is_none() {
+            chunk.reserve_exact(capacity);
+        } else {
+            Self::grow_or_abort(&self.alloc_error_handler, &mut chunk, capacity, true);
+        }
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        self.record_chunk(&chunk);
+        chunk
+    }
+
+    // Reports that the current chunk was just allocated, or grown from
+    // `previous_capacity`, see the `metrics` and `tracing` features.
+    #[cfg(any(feature = "metrics", feature = "tracing"))]
+    pub(crate) fn record_alloc(&mut self, previous_capacity: usize) {
+        let bytes = (self.current.capacity() - previous_capacity) * mem::size_of::<T>();
+        self.meter
+            .allocated(self.label, bytes, previous_capacity == 0);
+    }
+
+    // Reports that `chunk` was just allocated, see the `metrics` and
+    // `tracing` features.
+    #[cfg(any(feature = "metrics", feature = "tracing"))]
+    fn record_chunk(&mut self, chunk: &in_alloc!(Vec<T>)) {
+        let bytes = chunk.capacity() * mem::size_of::<T>();
+        self.meter.allocated(self.label, bytes, true);
+    }
+
+    // Makes room for `additional` more values in `chunk`. When the
+    // allocator fails, the handler set with `ArenaBuilder::on_alloc_error`
+    // may free some memory and ask for another attempt.
+    fn try_grow(
+        handler: &Option<AllocErrorHandler>,
+        chunk: &mut in_alloc!(Vec<T>),
+        additional: usize,
+        exact: bool,
+    ) -> Result<(), AllocError> {
+        let bytes = chunk
+            .len()
+            .checked_add(additional)
+            .and_then(|capacity| Layout::array::<T>(capacity).ok())
+            .ok_or(AllocError)?
+            .size();
+        loop {
+            let result = if exact {
+                chunk.try_reserve_exact(additional)
+            } else {
+                chunk.try_reserve(additional)
+            };
+            if result.is_ok() {
+                return Ok(());
+            }
+            match handler {
+                Some(handler) if handler(bytes) => continue,
+                _ => return Err(AllocError),
+            }
+        }
+    }
+
+    // Like `try_grow`, but aborts like `Vec` when the handler gives up.
+    fn grow_or_abort(
+        handler: &Option<AllocErrorHandler>,
+        chunk: &mut in_alloc!(Vec<T>),
+        additional: usize,
+        exact: bool,
+    ) {
+        if Self::try_grow(handler, chunk, additional, exact).is_err() {
+            match Layout::array::<T>(chunk.len() + additional) {
+                Ok(layout) => handle_alloc_error(layout),
+                Err(_) => panic!("capacity overflow"),
+            }
+        }
+    }
+
     #[inline(never)]
     #[cold]
     fn reserve(&mut self, additional: usize) {
-        let double_cap = self
-            .current
-            .capacity()
-            .checked_mul(2)
-            .expect("capacity overflow");
-        let required_cap = additional
-            .checked_next_power_of_two()
+        let chunk = match self.take_spare(additional) {
+            Some(chunk) => chunk,
+            None => {
+                let new_capacity = self.next_capacity(additional).expect("capacity overflow");
+                self.new_chunk(new_capacity)
+            }
+        };
+        self.replace_current(chunk);
+    }
+
+    #[inline(never)]
+    #[cold]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.rest.try_reserve(1).map_err(|_| AllocError)?;
+        let chunk = match self.take_spare(additional) {
+            Some(chunk) => chunk,
+            None => {
+                let new_capacity = self.next_capacity(additional).ok_or(AllocError)?;
+                #[cfg(feature = "std")]
+                if let Some(chunk) = self.recycler.as_ref().and_then(|r| r.take(new_capacity)) {
+                    self.replace_current(chunk);
+                    return Ok(());
+                }
+                let mut chunk = self.empty_chunk();
+                Self::try_grow(&self.alloc_error_handler, &mut chunk, new_capacity, true)?;
+                #[cfg(any(feature = "metrics", feature = "tracing"))]
+                self.record_chunk(&chunk);
+                chunk
+            }
+        };
+        self.replace_current(chunk);
+        Ok(())
+    }
+
+    fn replace_current(&mut self, chunk: in_alloc!(Vec<T>)) {
+        let previous_chunk = mem::replace(&mut self.current, chunk);
+        self.advise_current();
+        // Unless it is the first chunk of `Arena::new`, which was never
+        // allocated.
+        if previous_chunk.capacity() != 0 {
+            self.rest.push(previous_chunk);
+        }
+    }
+
+    // Applies the advice configured with `ArenaBuilder` to the current
+    // chunk, which has just been allocated.
+    pub(crate) fn advise_current(&self) {
+        #[cfg(all(feature = "mmap", target_os = "linux"))]
+        self.advice.new_chunk(
+            self.current.as_ptr() as *const u8,
+            self.current.capacity() * mem::size_of::<T>(),
+        );
+    }
+
+    // Applies the advice configured with `ArenaBuilder` to the chunks that
+    // have just been emptied, which are all kept.
+    fn advise_emptied(&self) {
+        #[cfg(all(feature = "mmap", target_os = "linux"))]
+        for chunk in Some(&self.current).into_iter().chain(&self.spare) {
+            self.advice.emptied_chunk(
+                chunk.as_ptr() as *const u8,
+                chunk.capacity() * mem::size_of::<T>(),
+            );
+        }
+    }
+
+    // Takes the next spare chunk, if it has room for `additional` elements.
+    fn take_spare(&mut self, additional: usize) -> Option<in_alloc!(Vec<T>)> {
+        if self.spare.last()?.capacity() >= additional {
+            self.spare.pop()
+        } else {
+            None
+        }
+    }
+
+    // Makes room for `additional` more elements right after the tail of the
+    // current chunk that starts at `*start`. If the current chunk is too
+    // small, the tail is moved to the start of a new chunk so it stays
+    // contiguous, and `*start` is updated.
+    fn reserve_tail(&mut self, start: &mut usize, additional: usize) {
+        if additional <= self.current.capacity() - self.current.len() {
+            return;
+        }
+
+        if *start == 0 {
+            // Nothing has been handed out from the current chunk yet, so the
+            // tail can move to a spare or recycled chunk, or grow in place.
+            let len = self.current.len();
+            let required = len.checked_add(additional).expect("capacity overflow");
+            if let Some(chunk) = self.take_reusable(required) {
+                self.move_current(chunk);
+                return;
+            }
+            let new_capacity = self.next_capacity(required).expect("capacity overflow");
+            if self.current.capacity() == 0 {
+                // The first chunk of `Arena::new`, which was never allocated.
+                self.current = self.new_chunk(new_capacity);
+            } else {
+                // Not `reserve_exact`: past the maximum chunk size, huge
+                // allocations still need amortized growth.
+                let additional = new_capacity - len;
+                let previous = self.current.as_ptr() as usize;
+                #[cfg(any(feature = "metrics", feature = "tracing"))]
+                let previous_capacity = self.current.capacity();
+                Self::grow_or_abort(
+                    &self.alloc_error_handler,
+                    &mut self.current,
+                    additional,
+                    false,
+                );
+                self.track_reserved(previous);
+                #[cfg(any(feature = "metrics", feature = "tracing"))]
+                self.record_alloc(previous_capacity);
+            }
+            self.advise_current();
+        } else {
+            let tail_len = self.current.len() - *start;
+            self.reserve(tail_len.checked_add(additional).expect("capacity overflow"));
+            self.move_tail(start);
+        }
+    }
+
+    // Like `reserve_tail`, but reports allocation failure instead of
+    // aborting. On failure, the tail is left where it was.
+    fn try_reserve_tail(&mut self, start: &mut usize, additional: usize) -> Result<(), AllocError> {
+        if additional <= self.current.capacity() - self.current.len() {
+            return Ok(());
+        }
+
+        if *start == 0 {
+            let len = self.current.len();
+            let required = len.checked_add(additional).ok_or(AllocError)?;
+            self.spare.try_reserve(1).map_err(|_| AllocError)?;
+            if let Some(chunk) = self.take_reusable(required) {
+                self.move_current(chunk);
+                return Ok(());
+            }
+            let new_capacity = self.next_capacity(required).ok_or(AllocError)?;
+            let additional = new_capacity - len;
+            let previous = self.current.as_ptr() as usize;
+            #[cfg(any(feature = "metrics", feature = "tracing"))]
+            let previous_capacity = self.current.capacity();
+            Self::try_grow(
+                &self.alloc_error_handler,
+                &mut self.current,
+                additional,
+                false,
+            )?;
+            self.track_reserved(previous);
+            #[cfg(any(feature = "metrics", feature = "tracing"))]
+            self.record_alloc(previous_capacity);
+            self.advise_current();
+        } else {
+            let tail_len = self.current.len() - *start;
+            self.try_reserve(tail_len.checked_add(additional).ok_or(AllocError)?)?;
+            self.move_tail(start);
+        }
+        Ok(())
+    }
+
+    // Keeps track of the reserved chunk if it was current, at `previous`,
+    // and has just been reallocated.
+    fn track_reserved(&mut self, previous: usize) {
+        if self.reserved == Some(previous) {
+            self.reserved = Some(self.current.as_ptr() as usize);
+        }
+    }
+
+    // Takes a spare or recycled chunk with room for `required` elements,
+    // preferring the spare chunks that would be used first.
+    fn take_reusable(&mut self, required: usize) -> Option<in_alloc!(Vec<T>)> {
+        if let Some(i) = self.spare.iter().rposition(|c| c.capacity() >= required) {
+            return Some(self.spare.remove(i));
+        }
+        #[cfg(feature = "std")]
+        if let Some(chunk) = self.recycler.as_ref().and_then(|r| r.take(required)) {
+            return Some(chunk);
+        }
+        None
+    }
+
+    // Moves all the elements of the current chunk, which are a tail that
+    // starts it, to the start of `chunk`, which becomes current. The old
+    // chunk is set aside as a spare, to be used after the others.
+    fn move_current(&mut self, mut chunk: in_alloc!(Vec<T>)) {
+        let len = self.current.len();
+        unsafe {
+            ptr::copy_nonoverlapping(self.current.as_ptr(), chunk.as_mut_ptr(), len);
+            self.current.set_len(0);
+            chunk.set_len(len);
+        }
+        let previous_chunk = mem::replace(&mut self.current, chunk);
+        self.advise_current();
+        if previous_chunk.capacity() != 0 {
+            self.spare.insert(0, previous_chunk);
+        }
+    }
+
+    // Moves the tail `[*start..]` of the previous chunk to the start of the
+    // new, empty current chunk.
+    fn move_tail(&mut self, start: &mut usize) {
+        let previous_chunk = self.rest.last_mut().unwrap();
+        let tail_len = previous_chunk.len() - *start;
+        unsafe {
+            // Go through pointers, since references to the elements before
+            // the tail may already have been handed out.
+            ptr::copy_nonoverlapping(
+                previous_chunk.as_ptr().add(*start),
+                self.current.as_mut_ptr(),
+                tail_len,
+            );
+            previous_chunk.set_len(*start);
+            self.current.set_len(tail_len);
+        }
+        *start = 0;
+    }
+
+    // Moves the `len` values at `values`, which end right where the tail
+    // `[*start..]` of the current chunk begins, to the start of a new chunk
+    // along with the tail, with room for `additional` more values. Those
+    // values are only read through `values`, which is all `grow_last` may
+    // access them through, and the old chunk keeps the values before them,
+    // or is set aside as a spare if there are none.
+    fn move_slice_and_tail(
+        &mut self,
+        values: *const T,
+        len: usize,
+        start: &mut usize,
+        additional: usize,
+    ) {
+        let tail_len = self.current.len() - *start;
+        let required = len
+            .checked_add(tail_len)
+            .and_then(|n| n.checked_add(additional))
             .expect("capacity overflow");
-        let new_capacity = cmp::max(double_cap, required_cap);
-        let chunk = mem::replace(&mut self.current, Vec::with_capacity(new_capacity));
-        self.rest.push(chunk);
+        self.reserve(required);
+        let previous_chunk = self.rest.last_mut().unwrap();
+        unsafe {
+            let chunk = self.current.as_mut_ptr();
+            ptr::copy_nonoverlapping(values, chunk, len);
+            ptr::copy_nonoverlapping(
+                previous_chunk.as_ptr().add(*start),
+                chunk.add(len),
+                tail_len,
+            );
+            previous_chunk.set_len(*start - len);
+            self.current.set_len(len + tail_len);
+        }
+        if *start == len {
+            let previous_chunk = self.rest.pop().unwrap();
+            self.spare.insert(0, previous_chunk);
+        }
+        *start = len;
+    }
+
+    // The capacity of the next chunk, if it has room for `additional` elements.
+    fn next_capacity(&mut self, additional: usize) -> Option<usize> {
+        let grown_cap = match self.current.capacity() {
+            // The first chunk of `Arena::new` is allocated lazily.
+            // Zero-sized types never get here, since their capacity is
+            // unlimited.
+            0 => INITIAL_SIZE / mem::size_of::<T>(),
+            capacity => match self.growth {
+                Some(ref mut growth) => growth.next_capacity(capacity),
+                None => capacity.checked_mul(2)?,
+            },
+        };
+        let required_cap = additional.checked_next_power_of_two()?;
+        let capacity = cmp::min(cmp::max(grown_cap, required_cap), self.max_chunk_size);
+        Some(cmp::max(capacity, additional))
+    }
+}
+
+// An allocation in progress at the end of the current chunk, made of
+// everything pushed since `start`. Rolls back the partial allocation when
+// dropped, so that user code panicking or failing halfway through neither
+// leaks nor double-drops values, unless confirmed with `finish`.
+struct Tail<'a, T, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    chunks: &'a mut in_alloc!(ChunkList<T>),
+    start: usize,
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Tail<'a, T>) {
+    fn new(chunks: &'a mut in_alloc!(ChunkList<T>)) -> Self {
+        let start = chunks.current.len();
+        Tail { chunks, start }
+    }
+
+    fn is_full(&self) -> bool {
+        self.chunks.current.len() == self.chunks.current.capacity()
+    }
+
+    // Makes room for `additional` more values, keeping the tail contiguous.
+    fn reserve(&mut self, additional: usize) {
+        self.chunks.reserve_tail(&mut self.start, additional);
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.chunks.try_reserve_tail(&mut self.start, additional)
+    }
+
+    // The caller must have reserved room for `value`.
+    fn push(&mut self, value: T) {
+        debug_assert!(!self.is_full());
+        self.chunks.current.push(value);
+    }
+
+    // Pushes up to `n` values from `iter`, which the caller must have
+    // reserved room for. This lets `Vec::extend` write them in place in a
+    // single pass when the iterator's length is known.
+    fn extend_reserved<I: Iterator<Item = T>>(&mut self, iter: &mut I, n: usize) {
+        // `take` makes sure an iterator lying about its size cannot make the
+        // chunk reallocate.
+        self.chunks.current.extend(iter.take(n));
+    }
+
+    fn extend<I: Iterator<Item = T>>(&mut self, mut iter: I) {
+        let min_len = iter.size_hint().0;
+        self.reserve(min_len);
+        self.extend_reserved(&mut iter, min_len);
+        while let Some(value) = iter.next() {
+            if self.is_full() {
+                // The iterator was larger than we could fit into the current
+                // chunk, so move what we have so far to a bigger one.
+                self.reserve(iter.size_hint().0.saturating_add(1));
+            }
+            self.push(value);
+        }
+    }
+
+    // Confirms the allocation and returns it.
+    fn finish(self) -> *mut [T] {
+        let current = &mut self.chunks.current;
+        let len = current.len() - self.start;
+        // Avoid going through `Vec::deref_mut`, which overlaps
+        // other references we have already handed out!
+        let ptr = unsafe { current.as_mut_ptr().add(self.start) };
+        mem::forget(self);
+        ptr::slice_from_raw_parts_mut(ptr, len)
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> Drop for in_alloc!(Tail<'a, T>) {
+    fn drop(&mut self) {
+        self.chunks.current.truncate(self.start);
+    }
+}
+
+impl<'a, #[cfg(feature = "allocator_api")] A: Allocator + Clone> fmt::Write
+    for in_alloc!(Tail<'a, u8>)
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.reserve(s.len());
+        // There is enough capacity, so this never reallocates.
+        self.chunks.current.extend_from_slice(s.as_bytes());
+        Ok(())
     }
 }
 
@@ -575,12 +2731,12 @@ enum IterMutState<'a, T> {
 /// Mutable arena iterator.
 ///
 /// This struct is created by the [`iter_mut`](struct.Arena.html#method.iter_mut) method on [Arenas](struct.Arena.html).
-pub struct IterMut<'a, T: 'a> {
-    chunks: &'a mut ChunkList<T>,
+pub struct IterMut<'a, T: 'a, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    chunks: &'a mut in_alloc!(ChunkList<T>),
     state: IterMutState<'a, T>,
 }
 
-impl<'a, T> Iterator for IterMut<'a, T> {
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> Iterator for in_alloc!(IterMut<'a, T>) {
     type Item = &'a mut T;
     fn next(&mut self) -> Option<&'a mut T> {
         loop {
@@ -596,12 +2752,18 @@ impl<'a, T> Iterator for IterMut<'a, T> {
                             if index < self.chunks.rest.len() {
                                 let inner_iter = self.chunks.rest[index].iter_mut();
                                 // Extend the lifetime of the individual elements to that of the arena.
-                                let inner_iter = unsafe { mem::transmute(inner_iter) };
+                                let inner_iter = unsafe {
+                                    mem::transmute::<slice::IterMut<T>, slice::IterMut<T>>(
+                                        inner_iter,
+                                    )
+                                };
                                 IterMutState::ChunkListRest { index, inner_iter }
                             } else {
                                 let iter = self.chunks.current.iter_mut();
                                 // Extend the lifetime of the individual elements to that of the arena.
-                                let iter = unsafe { mem::transmute(iter) };
+                                let iter = unsafe {
+                                    mem::transmute::<slice::IterMut<T>, slice::IterMut<T>>(iter)
+                                };
                                 IterMutState::ChunkListCurrent { iter }
                             }
                         }
@@ -633,3 +2795,123 @@ impl<'a, T> Iterator for IterMut<'a, T> {
         }
     }
 }
+
+/// Immutable arena iterator.
+///
+/// This struct is created by the [`iter`](struct.Arena.html#method.iter) method on [Arenas](struct.Arena.html).
+pub struct Iter<'a, T: 'a, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    inner: in_alloc!(IterMut<'a, T>),
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> Iterator for in_alloc!(Iter<'a, T>) {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|item| &*item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Iterator over the chunks of an arena, as mutable slices.
+///
+/// This struct is created by the [`chunks_mut`][Arena::chunks_mut] method
+/// on arenas.
+pub struct ChunksMut<'a, T: 'a, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    rest: slice::IterMut<'a, in_alloc!(Vec<T>)>,
+    current: Option<&'a mut in_alloc!(Vec<T>)>,
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> Iterator
+    for in_alloc!(ChunksMut<'a, T>)
+{
+    type Item = &'a mut [T];
+
+    fn next(&mut self) -> Option<&'a mut [T]> {
+        for chunk in &mut self.rest {
+            if !chunk.is_empty() {
+                return Some(chunk);
+            }
+        }
+        self.current
+            .take()
+            .filter(|chunk| !chunk.is_empty())
+            .map(|chunk| &mut chunk[..])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.rest.len() + self.current.is_some() as usize))
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> FusedIterator
+    for in_alloc!(ChunksMut<'a, T>)
+{
+}
+
+/// Iterator over the chunks of an arena, as slices.
+///
+/// This struct is created by the [`chunks`][Arena::chunks] method on
+/// arenas.
+pub struct Chunks<'a, T: 'a, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    inner: in_alloc!(ChunksMut<'a, T>),
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> Iterator for in_alloc!(Chunks<'a, T>) {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        self.inner.next().map(|chunk| &*chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator> FusedIterator
+    for in_alloc!(Chunks<'a, T>)
+{
+}
+
+/// Owning arena iterator.
+///
+/// This struct is created by the `into_iter` method on [Arenas](struct.Arena.html)
+/// (provided by the `IntoIterator` trait).
+pub struct IntoIter<T, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    iter: iter::Flatten<vec::IntoIter<in_alloc!(Vec<T>)>>,
+    len: usize,
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator> Iterator for in_alloc!(IntoIter<T>) {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let item = self.iter.next()?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator> DoubleEndedIterator
+    for in_alloc!(IntoIter<T>)
+{
+    fn next_back(&mut self) -> Option<T> {
+        let item = self.iter.next_back()?;
+        self.len -= 1;
+        Some(item)
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator> ExactSizeIterator
+    for in_alloc!(IntoIter<T>)
+{
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator> FusedIterator for in_alloc!(IntoIter<T>) {}
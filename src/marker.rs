@@ -0,0 +1,156 @@
+#[cfg(feature = "allocator_api")]
+use crate::Allocator;
+use crate::{Arena, ChunkList};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::mem;
+
+/// A point in the allocations of an arena, returned by [`Arena::marker`].
+///
+/// Truncating the arena to a marker drops the values allocated after it,
+/// like dropping a [`SubArena`][crate::SubArena] created at the same point.
+/// Unlike a sub-arena, a marker doesn't borrow the arena, so it can be
+/// stored in a data structure, for example the undo stack of a parser that
+/// backtracks. Markers of the same arena are ordered like the points they
+/// mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Marker {
+    // The index of the current chunk, and the number of values in it.
+    chunk: usize,
+    len: usize,
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Returns a marker of the end of the arena, to truncate it back to
+    /// later.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc("kept");
+    /// let marker = arena.marker();
+    /// arena.alloc_extend(vec!["undone"; 10]);
+    /// arena.rollback_to(marker);
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(arena.marker(), marker);
+    /// ```
+    pub fn marker(&self) -> Marker {
+        let chunks = self.chunks.borrow();
+        Marker {
+            chunk: chunks.rest.len(),
+            len: chunks.lent.map_or(chunks.current.len(), |lent| lent.len),
+        }
+    }
+
+    /// Drops the values allocated after `marker`, and keeps their chunks
+    /// for reuse.
+    ///
+    /// This is the same as [`rollback_to`][Arena::rollback_to], without
+    /// the `&mut self` that proves no reference to these values is alive.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `marker` is past the end of the arena, for example because
+    /// the arena was cleared or truncated to an earlier marker since, if it
+    /// was taken before a [`SubArena::forget`][crate::SubArena::forget], or
+    /// if the arena is borrowed by a `SubArena`, a `SliceBuilder` or
+    /// `alloc_cyclic`.
+    ///
+    /// ## Safety
+    ///
+    /// No reference to a value allocated after `marker` may be used
+    /// afterwards, since the value is dropped and its memory reused.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let name = arena.alloc(String::from("x"));
+    /// let marker = arena.marker();
+    /// arena.alloc(String::from("y"));
+    /// // The reference to "y" is gone.
+    /// unsafe { arena.truncate_to(marker) };
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(name, "x");
+    /// ```
+    pub unsafe fn truncate_to(&self, marker: Marker) {
+        self.chunks.borrow_mut().truncate_to(marker);
+    }
+
+    /// Drops the values allocated after `marker`, and keeps their chunks
+    /// for reuse.
+    ///
+    /// The chunks added after the marker are set aside, and filled again
+    /// in the same order, so rolling back and allocating again in a loop
+    /// stops asking the allocator for memory.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `marker` is past the end of the arena, for example because
+    /// the arena was cleared or truncated to an earlier marker since, or if
+    /// it was taken before a [`SubArena::forget`][crate::SubArena::forget],
+    /// whose values must stay where they are.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(2);
+    /// let marker = arena.marker();
+    /// arena.alloc_extend(0..100);
+    /// let chunk_count = arena.chunk_count();
+    /// for _ in 0..10 {
+    ///     arena.rollback_to(marker);
+    ///     assert!(arena.is_empty());
+    ///     arena.alloc_extend(0..100);
+    ///     assert_eq!(arena.chunk_count(), chunk_count);
+    /// }
+    /// ```
+    pub fn rollback_to(&mut self, marker: Marker) {
+        self.chunk_list_mut().truncate_to(marker);
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(ChunkList<T>) {
+    fn truncate_to(&mut self, marker: Marker) {
+        assert!(
+            self.pending.is_empty(),
+            "cannot truncate an arena from within alloc_cyclic"
+        );
+        assert!(
+            marker.chunk >= self.forgotten,
+            "cannot roll back past a forgotten SubArena"
+        );
+        let chunk = self.rest.get(marker.chunk).unwrap_or(&self.current);
+        assert!(
+            marker.chunk <= self.rest.len() && marker.len <= chunk.len(),
+            "marker past the end of the arena"
+        );
+        self.record_peak();
+
+        // The chunk that was current at the marker is current again.
+        let mut added = Vec::new();
+        if marker.chunk < self.rest.len() {
+            added = self.rest.split_off(marker.chunk + 1);
+            let chunk = self.rest.pop().unwrap();
+            added.push(mem::replace(&mut self.current, chunk));
+        }
+        self.current.truncate(marker.len);
+
+        // Like after dropping a `SubArena`, the chunks are reused in the
+        // same order.
+        for chunk in &mut added {
+            chunk.clear();
+        }
+        added.retain(|chunk| chunk.capacity() != 0);
+        self.spare.extend(added.into_iter().rev());
+    }
+}
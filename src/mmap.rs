@@ -0,0 +1,117 @@
+#[cfg(not(feature = "std"))]
+use alloc::alloc::handle_alloc_error;
+#[cfg(feature = "std")]
+use std::alloc::handle_alloc_error;
+
+use core::alloc::Layout;
+use core::ptr::{self, NonNull};
+
+// Anonymous memory mapped directly from the OS, and returned to it with
+// `munmap` on drop, bypassing the global allocator.
+pub(crate) struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// A `Mapping` is plain memory, owned like a `Box<[u8]>`.
+unsafe impl Send for Mapping {}
+
+impl Mapping {
+    // Maps at least `min_len` bytes, rounded up to whole pages.
+    pub(crate) fn new(min_len: usize) -> Mapping {
+        let page_size = page_size();
+        let len = min_len
+            .checked_add(page_size - 1)
+            .expect("capacity overflow")
+            & !(page_size - 1);
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            let layout = Layout::from_size_align(len, page_size).expect("capacity overflow");
+            handle_alloc_error(layout);
+        }
+        Mapping {
+            ptr: NonNull::new(ptr as *mut u8).unwrap(),
+            len,
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.len);
+        }
+    }
+}
+
+// Mappings are aligned to pages, which is enough for any reasonable type.
+pub(crate) fn page_size() -> usize {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+// Transparent huge pages are 2 MiB on the common architectures, so smaller
+// chunks can't use them.
+#[cfg(target_os = "linux")]
+const HUGE_PAGE_SIZE: usize = 2 << 20;
+
+// What to tell the kernel about the memory of an arena's chunks, as set up
+// with `ArenaBuilder`.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy)]
+pub(crate) struct Advice {
+    pub(crate) huge_pages: bool,
+    pub(crate) release_emptied: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl Advice {
+    pub(crate) const NONE: Advice = Advice {
+        huge_pages: false,
+        release_emptied: false,
+    };
+
+    pub(crate) fn new_chunk(self, ptr: *const u8, len: usize) {
+        if self.huge_pages && len >= HUGE_PAGE_SIZE {
+            madvise(ptr, len, libc::MADV_HUGEPAGE);
+        }
+    }
+
+    // The chunk is empty, but kept around to be reused: its pages are
+    // handed back, and will read as zeroes if touched again.
+    pub(crate) fn emptied_chunk(self, ptr: *const u8, len: usize) {
+        if self.release_emptied {
+            madvise(ptr, len, libc::MADV_DONTNEED);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn madvise(ptr: *const u8, len: usize, advice: i32) {
+    // Only the whole pages within `ptr..ptr + len` belong to the chunk alone.
+    let page_size = page_size();
+    let start = (ptr as usize).wrapping_add(page_size - 1) & !(page_size - 1);
+    let end = (ptr as usize + len) & !(page_size - 1);
+    if start < end {
+        // This is only a hint, so failures are ignored.
+        unsafe {
+            libc::madvise(start as *mut libc::c_void, end - start, advice);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::Arena;
+
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// An arena that can be shared between threads.
+///
+/// A `MutexArena` is an `Arena` behind a mutex: it is `Sync`, so worker
+/// threads can all allocate into it, and the references it hands out stay
+/// valid for as long as the arena itself.
+///
+/// Only available with the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use typed_arena::MutexArena;
+///
+/// let arena = Arc::new(MutexArena::new());
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let arena = Arc::clone(&arena);
+///         thread::spawn(move || *arena.alloc(i) * 2)
+///     })
+///     .collect();
+/// let sum: i32 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+/// assert_eq!(sum, 12);
+/// assert_eq!(arena.len(), 4);
+/// ```
+pub struct MutexArena<T> {
+    arena: Mutex<Arena<T>>,
+}
+
+impl<T> MutexArena<T> {
+    /// Construct a new shared arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::MutexArena;
+    ///
+    /// let arena = MutexArena::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> MutexArena<T> {
+        Arena::new().into()
+    }
+
+    /// Construct a new shared arena with capacity for `n` values
+    /// pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::MutexArena;
+    ///
+    /// let arena = MutexArena::with_capacity(1337);
+    /// # arena.alloc(1);
+    /// ```
+    pub fn with_capacity(n: usize) -> MutexArena<T> {
+        Arena::with_capacity(n).into()
+    }
+
+    // The arena is left consistent by a panic in any of its methods, so a
+    // poisoned lock can be used as is.
+    fn lock(&self) -> MutexGuard<'_, Arena<T>> {
+        self.arena.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Allocates a value in the arena, and returns a mutable reference
+    /// to that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::MutexArena;
+    ///
+    /// let arena = MutexArena::new();
+    /// let x = arena.alloc(42);
+    /// assert_eq!(*x, 42);
+    /// ```
+    pub fn alloc(&self, value: T) -> &mut T {
+        let arena = self.lock();
+        let value: *mut T = arena.alloc(value);
+        // The value lives as long as the arena, not just the guard.
+        unsafe { &mut *value }
+    }
+
+    /// Uses the contents of an iterator to allocate values in the arena.
+    /// Returns a mutable slice that contains these values.
+    ///
+    /// The iterator runs before the lock is taken, so it may allocate from
+    /// this same arena, and it doesn't hold up other threads.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::MutexArena;
+    ///
+    /// let arena = MutexArena::new();
+    /// let abc = arena.alloc_extend("abcdefg".chars().take(3));
+    /// assert_eq!(abc, ['a', 'b', 'c']);
+    /// ```
+    pub fn alloc_extend<I>(&self, iterable: I) -> &mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let values: Vec<T> = iterable.into_iter().collect();
+        let arena = self.lock();
+        let values: *mut [T] = arena.alloc_extend(values);
+        unsafe { &mut *values }
+    }
+
+    /// Return the number of values allocated in the arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::MutexArena;
+    ///
+    /// let arena = MutexArena::new();
+    /// arena.alloc_extend(0..3);
+    /// assert_eq!(arena.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if no values have been allocated in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Unwrap the inner `Arena`, to use the methods that need exclusive
+    /// access, like [`Arena::into_vec`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::MutexArena;
+    ///
+    /// let arena = MutexArena::new();
+    /// arena.alloc("a");
+    /// arena.alloc("b");
+    /// assert_eq!(arena.into_inner().into_vec(), ["a", "b"]);
+    /// ```
+    pub fn into_inner(self) -> Arena<T> {
+        self.arena
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> From<Arena<T>> for MutexArena<T> {
+    fn from(arena: Arena<T>) -> MutexArena<T> {
+        MutexArena {
+            arena: Mutex::new(arena),
+        }
+    }
+}
+
+impl<T> Default for MutexArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for MutexArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.arena.try_lock() {
+            Ok(arena) => f.debug_tuple("MutexArena").field(&*arena).finish(),
+            Err(_) => f.debug_struct("MutexArena").finish_non_exhaustive(),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{Arena, Tail};
+
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelExtend,
+    ParallelIterator,
+};
+
+/// A parallel iterator over mutable references to the values of an arena.
+///
+/// Created by [`Arena::par_iter_mut`]. Only available with the `rayon`
+/// feature.
+pub struct ParIterMut<'a, T> {
+    chunks: Vec<&'a mut [T]>,
+}
+
+impl<T: Send> Arena<T> {
+    /// Returns a parallel iterator that allows modifying each value.
+    ///
+    /// The chunks of the arena are split between threads, and each of them
+    /// is split further as needed, so large chunks don't hold up the others.
+    ///
+    /// Only available with the `rayon` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # extern crate rayon;
+    /// use rayon::prelude::*;
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc_extend(0..10_000);
+    /// arena.par_iter_mut().for_each(|x| *x *= 2);
+    /// assert_eq!(arena.iter().sum::<i32>(), 99_990_000);
+    /// ```
+    pub fn par_iter_mut(&mut self) -> ParIterMut<'_, T> {
+        let chunks = self.chunk_list_mut();
+        let current = Some(&mut chunks.current);
+        ParIterMut {
+            chunks: chunks
+                .rest
+                .iter_mut()
+                .chain(current)
+                .map(|chunk| &mut chunk[..])
+                .collect(),
+        }
+    }
+
+    /// Uses the contents of an indexed parallel iterator to allocate values
+    /// in the arena, in parallel. Returns a mutable slice that contains
+    /// these values.
+    ///
+    /// The space for all the values is reserved up front, and each thread
+    /// fills its own part of it. The values keep the order of the iterator.
+    ///
+    /// The arena is borrowed while the iterator runs, so it must not
+    /// allocate from this same arena; doing so panics. If the iterator
+    /// panics, the values it has already produced are dropped, and the arena
+    /// is left as it was.
+    ///
+    /// Iterators of unknown length can be collected into a `Vec` first, and
+    /// then passed to [`alloc_extend`][Arena::alloc_extend].
+    ///
+    /// Only available with the `rayon` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # extern crate rayon;
+    /// use rayon::prelude::*;
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let squares = arena.par_alloc_extend((0..1000u32).into_par_iter().map(|i| i * i));
+    /// assert_eq!(squares[999], 998_001);
+    /// ```
+    pub fn par_alloc_extend<I>(&self, par_iter: I) -> &mut [T]
+    where
+        I: IntoParallelIterator<Item = T>,
+        I::Iter: IndexedParallelIterator,
+    {
+        let par_iter = par_iter.into_par_iter();
+        let len = par_iter.len();
+
+        let mut chunks = self.chunks.borrow_mut();
+        let mut tail = Tail::new(&mut chunks);
+        tail.reserve(len);
+        // There is room for all the values, so the chunk isn't reallocated.
+        tail.chunks.current.par_extend(par_iter);
+        unsafe { &mut *tail.finish() }
+    }
+}
+
+impl<'a, T: Send> ParallelIterator for ParIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.chunks
+            .into_par_iter()
+            .flat_map(|chunk| chunk.par_iter_mut())
+            .drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.chunks.iter().map(|chunk| chunk.len()).sum())
+    }
+}
+
+impl<'a, T: Send> IntoParallelIterator for &'a mut Arena<T> {
+    type Item = &'a mut T;
+    type Iter = ParIterMut<'a, T>;
+
+    fn into_par_iter(self) -> ParIterMut<'a, T> {
+        self.par_iter_mut()
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Arena;
+
+use core::pin::Pin;
+
+/// An arena whose values are pinned in place.
+///
+/// An [`Arena`] never moves a value while references handed out by `alloc`
+/// are in use, but its `&mut self` methods (such as `iter_mut` or
+/// `into_vec`) can move values afterwards. That is not enough for
+/// [`Pin`], which requires that a pinned value stays at the same address
+/// until it is dropped.
+///
+/// A `PinnedArena` only gives out pinned references, and never moves its
+/// values: they stay where they were allocated until they are dropped in
+/// place, all at once, when the arena is dropped. This makes it suitable
+/// for intrusive or self-referential structures, and for futures.
+///
+/// ## Example
+///
+/// ```
+/// use std::marker::PhantomPinned;
+/// use std::pin::Pin;
+/// use std::ptr;
+/// use typed_arena::PinnedArena;
+///
+/// struct SelfRef {
+///     value: u32,
+///     this: *const SelfRef,
+///     _pin: PhantomPinned,
+/// }
+///
+/// let arena = PinnedArena::new();
+/// let mut pinned = arena.alloc_pinned(SelfRef {
+///     value: 42,
+///     this: ptr::null(),
+///     _pin: PhantomPinned,
+/// });
+/// let this = &*pinned as *const SelfRef;
+/// unsafe { pinned.as_mut().get_unchecked_mut().this = this };
+///
+/// // The value won't move for as long as the arena is alive.
+/// for i in 0..1000 {
+///     arena.alloc_pinned(SelfRef { value: i, this: ptr::null(), _pin: PhantomPinned });
+/// }
+/// assert_eq!(unsafe { (*pinned.this).value }, 42);
+/// ```
+pub struct PinnedArena<T> {
+    inner: Arena<T>,
+}
+
+impl<T> PinnedArena<T> {
+    /// Construct a new pinned arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::PinnedArena;
+    ///
+    /// let arena = PinnedArena::new();
+    /// # arena.alloc_pinned(1);
+    /// ```
+    pub fn new() -> PinnedArena<T> {
+        PinnedArena {
+            inner: Arena::new(),
+        }
+    }
+
+    /// Construct a new pinned arena with capacity for `n` values
+    /// pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::PinnedArena;
+    ///
+    /// let arena = PinnedArena::with_capacity(1337);
+    /// # arena.alloc_pinned(1);
+    /// ```
+    pub fn with_capacity(n: usize) -> PinnedArena<T> {
+        PinnedArena {
+            inner: Arena::with_capacity(n),
+        }
+    }
+
+    /// Allocates a value in the arena, and returns a pinned mutable
+    /// reference to that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::PinnedArena;
+    ///
+    /// let arena = PinnedArena::new();
+    /// let x = arena.alloc_pinned(42);
+    /// assert_eq!(*x, 42);
+    /// ```
+    #[inline]
+    pub fn alloc_pinned(&self, value: T) -> Pin<&mut T> {
+        // The value is never moved: `PinnedArena` offers no way to get an
+        // unpinned `&mut T` to it, or to take it out of the inner arena,
+        // whose chunks never reallocate. It is dropped in place along with
+        // the inner arena.
+        unsafe { Pin::new_unchecked(self.inner.alloc(value)) }
+    }
+
+    /// Return the number of values allocated in the arena.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if no values have been allocated in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<T> Default for PinnedArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
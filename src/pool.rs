@@ -0,0 +1,144 @@
+use crate::Arena;
+
+use std::fmt;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, PoisonError};
+
+/// A pool of arenas to reuse, for example one for each request a server
+/// handles.
+///
+/// [`get`][ArenaPool::get] hands out an arena from the pool, and the arena
+/// goes back to it when the guard is dropped. The arena is cleared then, but
+/// keeps its chunks, so the next user doesn't have to allocate them again.
+///
+/// Only available with the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::ArenaPool;
+///
+/// let pool = ArenaPool::new();
+/// for request in 0..3 {
+///     let arena = pool.get();
+///     let ids = arena.alloc_extend(0..100);
+///     assert_eq!(ids.len(), 100);
+///     // The arena is cleared and returned to the pool here.
+/// }
+/// assert_eq!(pool.idle_count(), 1);
+/// ```
+pub struct ArenaPool<T> {
+    arenas: Mutex<Vec<Arena<T>>>,
+}
+
+/// An arena borrowed from an [`ArenaPool`], created by [`ArenaPool::get`].
+///
+/// It dereferences to the `Arena`, and returns it to the pool on drop.
+pub struct PooledArena<'p, T> {
+    pool: &'p ArenaPool<T>,
+    arena: Arena<T>,
+}
+
+impl<T> ArenaPool<T> {
+    /// Construct a new, empty pool.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ArenaPool;
+    ///
+    /// let pool = ArenaPool::new();
+    /// # pool.get().alloc(1);
+    /// ```
+    pub fn new() -> ArenaPool<T> {
+        ArenaPool {
+            arenas: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Take an arena from the pool, or create a new one if the pool is
+    /// empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ArenaPool;
+    ///
+    /// let pool = ArenaPool::new();
+    /// let arena = pool.get();
+    /// arena.alloc_extend(0..1000);
+    /// drop(arena);
+    ///
+    /// let arena = pool.get();
+    /// assert!(arena.is_empty());
+    /// assert!(arena.capacity() >= 1000);
+    /// ```
+    pub fn get(&self) -> PooledArena<'_, T> {
+        let arena = self
+            .arenas
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_default();
+        PooledArena { pool: self, arena }
+    }
+
+    /// Return the number of arenas in the pool, waiting to be reused.
+    pub fn idle_count(&self) -> usize {
+        self.arenas
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+impl<T> Default for ArenaPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ArenaPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.arenas.try_lock() {
+            Ok(arenas) => f
+                .debug_struct("ArenaPool")
+                .field("idle_count", &arenas.len())
+                .finish(),
+            Err(_) => f.debug_struct("ArenaPool").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl<'p, T> Deref for PooledArena<'p, T> {
+    type Target = Arena<T>;
+
+    fn deref(&self) -> &Arena<T> {
+        &self.arena
+    }
+}
+
+impl<'p, T> DerefMut for PooledArena<'p, T> {
+    fn deref_mut(&mut self) -> &mut Arena<T> {
+        &mut self.arena
+    }
+}
+
+impl<'p, T> Drop for PooledArena<'p, T> {
+    fn drop(&mut self) {
+        let mut arena = mem::take(&mut self.arena);
+        arena.clear();
+        self.pool
+            .arenas
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(arena);
+    }
+}
+
+impl<'p, T> fmt::Debug for PooledArena<'p, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PooledArena").field(&self.arena).finish()
+    }
+}
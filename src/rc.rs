@@ -0,0 +1,183 @@
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::{self, NonNull};
+
+/// A reference-counted pointer to a value in a [`Bump`][crate::Bump].
+///
+/// Created by [`Bump::alloc_rc`][crate::Bump::alloc_rc]. Like an `Rc`,
+/// cloning it shares the value, and the value is dropped with the last
+/// pointer. Both the count and the value live in the bump arena, whose
+/// memory is freed all at once.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::{ArenaRc, Bump};
+///
+/// enum Tree<'a> {
+///     Leaf(i32),
+///     Node(ArenaRc<'a, Tree<'a>>, ArenaRc<'a, Tree<'a>>),
+/// }
+///
+/// fn sum(tree: &Tree) -> i32 {
+///     match tree {
+///         Tree::Leaf(x) => *x,
+///         Tree::Node(l, r) => sum(l) + sum(r),
+///     }
+/// }
+///
+/// let bump = Bump::new();
+/// let shared = bump.alloc_rc(Tree::Leaf(1));
+/// let root = Tree::Node(shared.clone(), shared.clone());
+/// assert_eq!(sum(&root), 2);
+/// assert_eq!(ArenaRc::strong_count(&shared), 3);
+/// ```
+pub struct ArenaRc<'a, T> {
+    inner: NonNull<RcBox<T>>,
+    _marker: PhantomData<&'a RcBox<T>>,
+}
+
+pub(crate) struct RcBox<T> {
+    strong: Cell<usize>,
+    value: T,
+}
+
+impl<T> RcBox<T> {
+    pub(crate) fn new(value: T) -> RcBox<T> {
+        RcBox {
+            strong: Cell::new(1),
+            value,
+        }
+    }
+}
+
+impl<'a, T> ArenaRc<'a, T> {
+    // The caller must own `*inner`, which is never dropped by the arena.
+    pub(crate) unsafe fn from_box(inner: &'a mut RcBox<T>) -> Self {
+        ArenaRc {
+            inner: NonNull::from(inner),
+            _marker: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &RcBox<T> {
+        unsafe { self.inner.as_ref() }
+    }
+
+    /// Return the number of pointers to the value.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.get()
+    }
+
+    /// Returns `true` if both pointers point to the same value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaRc, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let a = bump.alloc_rc(1);
+    /// let b = bump.alloc_rc(1);
+    /// assert!(ArenaRc::ptr_eq(&a, &a.clone()));
+    /// assert!(!ArenaRc::ptr_eq(&a, &b));
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.inner == other.inner
+    }
+
+    /// Returns a mutable reference to the value if this is its only
+    /// pointer, or `None` otherwise.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaRc, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let mut x = bump.alloc_rc(1);
+    /// *ArenaRc::get_mut(&mut x).unwrap() += 1;
+    /// let y = x.clone();
+    /// assert!(ArenaRc::get_mut(&mut x).is_none());
+    /// assert_eq!(*y, 2);
+    /// ```
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if this.inner().strong.get() == 1 {
+            // The count is one, so no other pointer can read the value.
+            Some(unsafe { &mut (*this.inner.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    /// Moves the value out if this is its only pointer, or gives the
+    /// pointer back otherwise.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaRc, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let x = bump.alloc_rc(String::from("once"));
+    /// assert_eq!(ArenaRc::try_unwrap(x).unwrap(), "once");
+    /// ```
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if this.inner().strong.get() != 1 {
+            return Err(this);
+        }
+        let this = mem::ManuallyDrop::new(this);
+        this.inner().strong.set(0);
+        Ok(unsafe { ptr::read(&this.inner().value) })
+    }
+}
+
+impl<'a, T> Clone for ArenaRc<'a, T> {
+    fn clone(&self) -> Self {
+        let strong = &self.inner().strong;
+        strong.set(strong.get().checked_add(1).expect("ArenaRc count overflow"));
+        ArenaRc {
+            inner: self.inner,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Drop for ArenaRc<'a, T> {
+    fn drop(&mut self) {
+        let strong = self.inner().strong.get() - 1;
+        self.inner().strong.set(strong);
+        if strong == 0 {
+            unsafe { ptr::drop_in_place(&mut (*self.inner.as_ptr()).value) }
+        }
+    }
+}
+
+impl<'a, T> Deref for ArenaRc<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<'a, T> AsRef<T> for ArenaRc<'a, T> {
+    fn as_ref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for ArenaRc<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<'a, T: fmt::Display> fmt::Display for ArenaRc<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,184 @@
+use crate::Arena;
+
+use std::alloc::{self, Layout};
+#[cfg(feature = "allocator_api")]
+use std::alloc::{Allocator, Global};
+use std::fmt;
+use std::mem::{self, ManuallyDrop};
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A store of chunks shared between arenas.
+///
+/// Arenas built with [`ArenaBuilder::chunk_recycler`] take their new chunks
+/// from the recycler when it has a large enough one, and
+/// [`recycle`][ChunkRecycler::recycle] gives the chunks of an arena back to
+/// it. Programs that create and destroy many short-lived arenas then mostly
+/// skip the allocator.
+///
+/// Chunks are untyped: a chunk can be reused by an arena of any type with
+/// the same alignment, like all the `u8` and `MaybeUninit<u8>` byte arenas.
+///
+/// Only available with the `std` feature.
+///
+/// [`ArenaBuilder::chunk_recycler`]: crate::ArenaBuilder::chunk_recycler
+///
+/// ## Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use typed_arena::{Arena, ChunkRecycler};
+///
+/// let recycler = Arc::new(ChunkRecycler::new());
+/// for _ in 0..3 {
+///     let arena = Arena::<u32>::builder()
+///         .chunk_recycler(Arc::clone(&recycler))
+///         .build();
+///     arena.alloc_extend(0..100);
+///     recycler.recycle(arena);
+///     assert!(recycler.cached_bytes() >= 400);
+/// }
+/// ```
+pub struct ChunkRecycler {
+    chunks: Mutex<Vec<RawChunk>>,
+}
+
+// The memory of a `Vec<T>`, allocated with the global allocator.
+struct RawChunk {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+// The chunks are plain memory, owned by the recycler.
+unsafe impl Send for RawChunk {}
+
+impl Drop for RawChunk {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+impl ChunkRecycler {
+    /// Construct a new, empty recycler.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::ChunkRecycler;
+    ///
+    /// let recycler = ChunkRecycler::new();
+    /// assert_eq!(recycler.cached_bytes(), 0);
+    /// ```
+    pub fn new() -> ChunkRecycler {
+        ChunkRecycler {
+            chunks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Drop the values of an arena, and keep its chunks for other arenas to
+    /// reuse.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, ChunkRecycler};
+    ///
+    /// let recycler = ChunkRecycler::new();
+    /// let arena = Arena::with_capacity(100);
+    /// arena.alloc(1u64);
+    /// recycler.recycle(arena);
+    /// assert_eq!(recycler.cached_bytes(), 800);
+    /// ```
+    pub fn recycle<T>(&self, arena: Arena<T>) {
+        let chunks = arena.into_chunk_list();
+        let mut recycled = Vec::new();
+        let all = chunks.rest.into_iter().chain(chunks.spare);
+        for mut chunk in all.chain(Some(chunks.current)) {
+            chunk.clear();
+            if chunk.capacity() == 0 || mem::size_of::<T>() == 0 {
+                continue;
+            }
+            let chunk = ManuallyDrop::new(chunk);
+            recycled.push(RawChunk {
+                ptr: unsafe { NonNull::new_unchecked(chunk.as_ptr() as *mut u8) },
+                // The layout `Vec<T>` allocates with.
+                layout: Layout::array::<T>(chunk.capacity()).unwrap(),
+            });
+        }
+        self.lock().append(&mut recycled);
+    }
+
+    // Takes a chunk with room for at least `capacity` values of `T`.
+    pub(crate) fn take<T>(&self, capacity: usize) -> Option<Vec<T>> {
+        let size = mem::size_of::<T>();
+        if size == 0 {
+            return None;
+        }
+        let mut chunks = self.lock();
+        let index = chunks.iter().position(|chunk| {
+            chunk.layout.align() == mem::align_of::<T>()
+                && chunk.layout.size() % size == 0
+                && chunk.layout.size() / size >= capacity
+        })?;
+        let chunk = ManuallyDrop::new(chunks.swap_remove(index));
+        // Same alignment and size as the original allocation, so the
+        // `Vec<T>` frees it with the right layout.
+        Some(unsafe {
+            Vec::from_raw_parts(chunk.ptr.as_ptr() as *mut T, 0, chunk.layout.size() / size)
+        })
+    }
+
+    /// Return the total size of the chunks waiting to be reused.
+    pub fn cached_bytes(&self) -> usize {
+        self.lock().iter().map(|chunk| chunk.layout.size()).sum()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<RawChunk>> {
+        self.chunks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ChunkRecycler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ChunkRecycler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.chunks.try_lock() {
+            Ok(chunks) => f
+                .debug_struct("ChunkRecycler")
+                .field("chunk_count", &chunks.len())
+                .field(
+                    "cached_bytes",
+                    &chunks.iter().map(|c| c.layout.size()).sum::<usize>(),
+                )
+                .finish(),
+            Err(_) => f.debug_struct("ChunkRecycler").finish_non_exhaustive(),
+        }
+    }
+}
+
+// The recycler of an arena of `T`. Only arenas that allocate with the global
+// allocator are built with one, so its chunks are theirs to reuse.
+pub(crate) struct Recycler<T, #[cfg(feature = "allocator_api")] A: Allocator = Global> {
+    recycler: Arc<ChunkRecycler>,
+    take: fn(&ChunkRecycler, usize) -> Option<in_alloc!(Vec<T>)>,
+}
+
+impl<T> Recycler<T> {
+    pub(crate) fn new(recycler: Arc<ChunkRecycler>) -> Self {
+        Recycler {
+            recycler,
+            take: ChunkRecycler::take,
+        }
+    }
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator> in_alloc!(Recycler<T>) {
+    // Takes a chunk with room for at least `capacity` values.
+    pub(crate) fn take(&self, capacity: usize) -> Option<in_alloc!(Vec<T>)> {
+        (self.take)(&self.recycler, capacity)
+    }
+}
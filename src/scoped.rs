@@ -1,10 +1,22 @@
+#[cfg(feature = "tracing")]
+use crate::instrument;
 use crate::Arena;
+use crate::ArenaStats;
 use crate::ChunkList;
+use crate::Lent;
 
-use core::cell::{RefCell, RefMut};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cell::{Ref, RefCell, RefMut};
+use core::cmp;
+use core::fmt;
+use core::iter;
 use core::marker::PhantomData;
-use core::mem;
+use core::mem::{self, ManuallyDrop};
 use core::ops::Deref;
+use core::ptr::{self, NonNull};
+use core::slice;
 
 /// A scoped sub-arena
 ///
@@ -25,11 +37,28 @@ use core::ops::Deref;
 ///     // when sub_arena is dropped, the arena is partially cleared
 ///     // z and w are dropped, but x and y are still good
 /// }
+/// // the chunks the sub-arena added are kept for reuse by the arena
 /// assert_eq!(*x + *y, 123 + 456);
 /// ```
 ///
-/// While a `SubArena` is in use, the main `Arena` cannot be used -- trying to
-/// do so results in a panic:
+/// While a `SubArena` is in use, the references already handed out by the
+/// main `Arena` stay valid, and the arena can be inspected, with `len`,
+/// `stats` or `contains` for example:
+///
+/// ```
+/// use typed_arena::Arena;
+/// use typed_arena::SubArena;
+///
+/// let arena = Arena::new();
+/// let x = arena.alloc(1);
+/// let sub_arena = SubArena::new(&arena);
+/// let y = sub_arena.alloc(2);
+/// assert_eq!(arena.len(), 1);
+/// assert!(arena.contains(x) && !arena.contains(y));
+/// ```
+///
+/// But nothing can be allocated in the main `Arena` -- trying to do so
+/// results in a panic:
 /// ```should_panic
 /// use typed_arena::Arena;
 /// use typed_arena::SubArena;
@@ -40,8 +69,20 @@ use core::ops::Deref;
 /// ```
 pub struct SubArena<'a, T> {
     inner: Arena<T>,
-    old: RefMut<'a, ChunkList<T>>,
+    parent: &'a RefCell<ChunkList<T>>,
+    // Keeps the parent from allocating, but not from being inspected. It
+    // is always `Some` until the sub-arena ends.
+    lock: Option<Ref<'a, ChunkList<T>>>,
     old_len: usize,
+    // Built by `SubArenaBuilder`, so `T` may not live as long as the
+    // values of the parent arena.
+    rebound: bool,
+    // The chunks and bytes the parent lent at creation, see `stats`.
+    base_chunks: usize,
+    base_bytes: usize,
+    // Whether the chunk allocated by `with_capacity`, if any, is freed
+    // instead of given to the parent.
+    free_reserved: bool,
 }
 
 /// Hacky workaround to deal with variance issues
@@ -89,23 +130,82 @@ pub struct SubArena<'a, T> {
 /// }
 /// assert_eq!(*x + *y, 7);
 /// ```
+///
+/// [`scoped`][SubArenaBuilder::scoped] does the same with a closure, like
+/// [`Arena::scoped`].
+///
+/// The builder can only be turned into one for shorter-lived values, never
+/// longer-lived ones:
+///
+/// ```compile_fail
+/// use typed_arena::SubArenaBuilder;
+///
+/// fn lengthen<'a>(builder: SubArenaBuilder<'a, &'a u8>) -> SubArenaBuilder<'a, &'static u8> {
+///     builder
+/// }
+/// ```
+///
+/// And once built, the `SubArena` is invariant, like the `Arena`:
+///
+/// ```compile_fail
+/// use typed_arena::SubArena;
+///
+/// fn shorten<'a, 's>(sub_arena: SubArena<'s, &'static u8>) -> SubArena<'s, &'a u8> {
+///     sub_arena
+/// }
+/// ```
 pub struct SubArenaBuilder<'a, T> {
-    data: RefMut<'a, ()>,
+    lock: CovariantLock<'a, T>,
+}
+
+// Keeps the chunks of an arena borrowed, like a `RefMut`, but is covariant
+// in `T`, so that it can be turned into the lock of a subtype of the type
+// of the arena. It is the only place where the chunks change type.
+struct CovariantLock<'a, T> {
+    // The `RefCell<ChunkList<T>>` of the arena, without its type, which
+    // would make the lock invariant.
+    cell: NonNull<()>,
+    _borrow: RefMut<'a, [(); 0]>,
     _marker: PhantomData<&'a ChunkList<T>>,
 }
 
+impl<'a, T> CovariantLock<'a, T> {
+    fn new(cell: &'a RefCell<ChunkList<T>>) -> Self {
+        CovariantLock {
+            cell: NonNull::from(cell).cast(),
+            _borrow: RefMut::map(cell.borrow_mut(), |_| &mut []),
+            _marker: PhantomData,
+        }
+    }
+
+    // Releases the lock, and returns the chunks, typed for the values of
+    // a sub-arena that ends within `'a`.
+    fn into_cell(self) -> &'a RefCell<ChunkList<T>> {
+        // The lock was created for a cell of some `ChunkList<U>` that lives
+        // for `'a`, and only covariance can have turned `U` into `T`, so
+        // `T` is `U` with shorter lifetimes, and `ChunkList<T>` has the
+        // same layout as `ChunkList<U>` (see `chunk_list_is_covariant`).
+        // Values of `T` may borrow data that dies before those of the
+        // arena, so the caller must remove all of them from the chunks
+        // before `'a` ends.
+        unsafe { &*self.cell.cast::<RefCell<ChunkList<T>>>().as_ptr() }
+    }
+}
+
+// `CovariantLock` assumes that this compiles.
+#[allow(dead_code)]
+fn chunk_list_is_covariant<'a, 'b: 'a, T>(chunks: ChunkList<&'b T>) -> ChunkList<&'a T> {
+    chunks
+}
+
 impl<'a, T> SubArenaBuilder<'a, T> {
     /// Create a new `SubArenaBuilder<T>`, on which the `.build()` method will create a
     /// `SubArena<T>`.
     ///
     /// See the `SubArenaBuilder` docs for why this is useful.
     pub fn new(arena: &'a Arena<T>) -> Self {
-        unsafe {
-            let data = RefMut::map(arena.chunks.borrow_mut(), |chunks| mem::transmute(chunks));
-            Self {
-                data,
-                _marker: PhantomData,
-            }
+        Self {
+            lock: CovariantLock::new(&arena.chunks),
         }
     }
 
@@ -113,10 +213,38 @@ impl<'a, T> SubArenaBuilder<'a, T> {
     ///
     /// See the `SubArenaBuilder` docs for why this is useful.
     pub fn build(self) -> SubArena<'a, T> {
-        unsafe {
-            let data = RefMut::map(self.data, |chunks| mem::transmute(chunks));
-            SubArena::from_chunks(data)
-        }
+        // A rebound sub-arena drops or leaks its values when it ends, and
+        // never promotes them.
+        SubArena::from_chunks(self.lock.into_cell(), true)
+    }
+
+    /// Runs `f` with the `SubArena` of the builder, and rolls it back when
+    /// `f` returns or panics, like [`Arena::scoped`].
+    ///
+    /// Since the builder is covariant, the sub-arena can hold values that
+    /// borrow data shorter-lived than the values of the arena, without
+    /// naming the shorter type or keeping the built sub-arena around.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArenaBuilder};
+    ///
+    /// let arena = Arena::<&'static str>::new();
+    /// arena.alloc("static");
+    /// let line = String::from("a b");
+    /// let count = SubArenaBuilder::new(&arena).scoped(|scratch| {
+    ///     // The words borrow `line`, not `'static` data.
+    ///     scratch.alloc_extend(line.split(' ')).len()
+    /// });
+    /// assert_eq!((count, arena.len()), (2, 1));
+    /// ```
+    pub fn scoped<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&SubArena<'_, T>) -> R,
+    {
+        let sub_arena = self.build();
+        f(&sub_arena)
     }
 }
 
@@ -148,39 +276,496 @@ impl<'a, T> SubArena<'a, T> {
     /// assert_eq!(*x, 1);
     /// ```
     pub fn new(arena: &'a Arena<T>) -> Self {
-        let old = arena.chunks.borrow_mut();
-        Self::from_chunks(old)
+        Self::from_chunks(&arena.chunks, false)
+    }
+
+    /// Create a new `SubArena`, with room for at least `n` values.
+    ///
+    /// If the parent's chunks can't hold them, a chunk is allocated up
+    /// front, so that a burst of allocations in the sub-arena doesn't grow
+    /// it chunk by chunk. Like the other chunks the sub-arena adds, it goes
+    /// to the parent for reuse when the sub-arena ends, unless
+    /// [`free_reserved_on_drop`][SubArena::free_reserved_on_drop] is called.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// let arena = Arena::with_capacity(4);
+    /// arena.alloc(0);
+    /// let sub_arena = SubArena::with_capacity(&arena, 1000);
+    /// let chunk_count = sub_arena.chunk_count();
+    /// for i in 0..1000 {
+    ///     sub_arena.alloc(i);
+    /// }
+    /// assert_eq!(sub_arena.chunk_count(), chunk_count);
+    /// ```
+    pub fn with_capacity(arena: &'a Arena<T>, n: usize) -> Self {
+        let mut sub_arena = Self::new(arena);
+        let spare = sub_arena.inner.chunks.get_mut().spare.len();
+        sub_arena.inner.reserve(n);
+        // A new chunk is set aside behind the spare ones.
+        let chunks = sub_arena.inner.chunks.get_mut();
+        if chunks.spare.len() > spare {
+            chunks.reserved = Some(chunks.spare[0].as_ptr() as usize);
+        }
+        sub_arena
+    }
+
+    /// Frees the chunk allocated by [`with_capacity`][SubArena::with_capacity]
+    /// when the sub-arena ends, instead of giving it to the parent for
+    /// reuse.
+    ///
+    /// This is for a one-off burst that would otherwise keep the parent's
+    /// memory use high. The other chunks are still given to the parent.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// let arena = Arena::with_capacity(4);
+    /// arena.alloc(0);
+    /// let before = arena.allocated_bytes();
+    /// {
+    ///     let sub_arena = SubArena::with_capacity(&arena, 1000).free_reserved_on_drop();
+    ///     sub_arena.alloc_extend(0..1000);
+    /// }
+    /// assert_eq!(arena.allocated_bytes(), before);
+    /// ```
+    pub fn free_reserved_on_drop(mut self) -> Self {
+        self.free_reserved = true;
+        self
+    }
+
+    /// Return the number of values allocated in this sub-arena.
+    ///
+    /// Values allocated in the parent arena before the sub-arena was created
+    /// are not counted.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// let arena = Arena::new();
+    /// arena.alloc(1);
+    /// let sub_arena = SubArena::new(&arena);
+    /// assert!(sub_arena.is_empty());
+    /// sub_arena.alloc(2);
+    /// sub_arena.alloc(3);
+    /// assert_eq!(sub_arena.len(), 2);
+    /// drop(sub_arena);
+    /// assert_eq!(arena.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.inner.len() - self.old_len
     }
 
-    fn from_chunks(mut old: RefMut<'a, ChunkList<T>>) -> Self {
-        let inner_vec = mem::replace(&mut old.current, Vec::new());
+    /// Returns `true` if no values have been allocated in this sub-arena.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the memory use of this sub-arena alone.
+    ///
+    /// Like [`len`][SubArena::len], the values of the parent are not
+    /// counted: `chunk_count` and `allocated_bytes` are what the sub-arena
+    /// added to the parent's chunks, and the peak is the current use, since
+    /// a sub-arena never drops values before it ends.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// let arena = Arena::<u64>::with_capacity(4);
+    /// arena.alloc_extend(0..2);
+    /// let pass = SubArena::new(&arena);
+    /// pass.alloc_extend(0..10);
+    /// let stats = pass.stats();
+    /// assert_eq!((stats.len, stats.used_bytes), (10, 80));
+    /// assert_eq!((stats.chunk_count, stats.allocated_bytes), (1, 128));
+    /// ```
+    pub fn stats(&self) -> ArenaStats {
+        let chunks = self.inner.chunks.borrow();
+        let (chunk_count, allocated_bytes) = chunk_usage(&chunks);
+        let len = chunks.len() - self.old_len;
+        let used_bytes = len * mem::size_of::<T>();
+        ArenaStats {
+            label: chunks.label,
+            chunk_count: chunk_count - self.base_chunks,
+            len,
+            used_bytes,
+            allocated_bytes: allocated_bytes - self.base_bytes,
+            peak_len: len,
+            peak_used_bytes: used_bytes,
+        }
+    }
+
+    /// Keeps the values allocated in this sub-arena in the parent arena,
+    /// instead of dropping them, and returns them.
+    ///
+    /// The values are returned as one slice per chunk, in allocation order.
+    /// They now live, and drop, with the parent arena, so speculative work
+    /// that succeeded can be committed without copying it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the sub-arena was created with a [`SubArenaBuilder`],
+    /// since its values may borrow data that doesn't live as long as the
+    /// parent arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// let arena = Arena::with_capacity(2);
+    /// arena.alloc(1);
+    /// let kept = {
+    ///     let sub_arena = SubArena::new(&arena);
+    ///     sub_arena.alloc_extend(2..5);
+    ///     sub_arena.promote()
+    /// };
+    /// assert_eq!(kept.concat(), [2, 3, 4]);
+    /// arena.alloc(5);
+    /// assert_eq!(arena.len(), 5);
+    /// ```
+    pub fn promote(self) -> Vec<&'a mut [T]> {
+        assert!(
+            !self.rebound,
+            "cannot promote a SubArena created by a SubArenaBuilder"
+        );
+        let (mut old, mut chunks, old_len) = self.into_parts();
+        let mut promoted = Vec::new();
+        let mut start = old_len;
+        for chunk in &mut chunks {
+            if chunk.len() > start {
+                let len = chunk.len() - start;
+                // Moving the chunks into the parent doesn't move the values.
+                promoted
+                    .push(unsafe { slice::from_raw_parts_mut(chunk.as_mut_ptr().add(start), len) });
+            }
+            start = 0;
+        }
+
+        old.current = chunks.pop().unwrap();
+        old.rest
+            .extend(chunks.into_iter().filter(|chunk| chunk.capacity() != 0));
+        promoted
+    }
+
+    /// Ends the sub-arena without dropping the values allocated in it.
+    ///
+    /// The values are leaked, but their memory stays in the parent arena,
+    /// which never reuses it for new values, and never drops them. This is
+    /// for values whose destructors must not run, for example because they
+    /// were handed to foreign code. The memory is freed or reused once the
+    /// parent arena is dropped, cleared, reset or shrunk. Rolling the parent
+    /// back to a [marker][Arena::marker] taken before this panics instead of
+    /// reusing it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// struct Noisy<'a>(&'a Cell<u32>);
+    /// impl Drop for Noisy<'_> {
+    ///     fn drop(&mut self) {
+    ///         self.0.set(self.0.get() + 1);
+    ///     }
+    /// }
+    ///
+    /// let drops = Cell::new(0);
+    /// let arena = Arena::new();
+    /// arena.alloc(Noisy(&drops));
+    /// let sub_arena = SubArena::new(&arena);
+    /// sub_arena.alloc(Noisy(&drops));
+    /// sub_arena.forget();
+    /// arena.alloc(Noisy(&drops));
+    /// assert_eq!(arena.len(), 2);
+    /// drop(arena);
+    /// assert_eq!(drops.get(), 2);
+    /// ```
+    pub fn forget(self) {
+        let (mut old, chunks, old_len) = self.into_parts();
+        let mut start = old_len;
+        for mut chunk in chunks {
+            // Leaks the values of the sub-arena.
+            unsafe { chunk.set_len(start) };
+            start = 0;
+            if chunk.capacity() != 0 {
+                old.rest.push(chunk);
+            }
+        }
+        // New values go to a new chunk, so the leaked ones stay in place.
+        old.current = Vec::new();
+        old.forgotten = old.rest.len();
+    }
+
+    // The number of values of the parent in the first chunk of the
+    // sub-arena.
+    pub(crate) fn parent_len(&self) -> usize {
+        self.old_len
+    }
+
+    // Takes the sub-arena apart without rolling it back, and returns the
+    // parent's chunks, those of the sub-arena in allocation order, and the
+    // number of values of the parent in the first of them.
+    fn into_parts(self) -> (RefMut<'a, ChunkList<T>>, Vec<Vec<T>>, usize) {
+        let this = ManuallyDrop::new(self);
+        let (inner, lock) = unsafe { (ptr::read(&this.inner), ptr::read(&this.lock)) };
+        drop(lock);
+        let mut old = this.parent.borrow_mut();
+        old.lent = None;
+        let mut inner = inner.chunks.into_inner();
+        old.peak = cmp::max(old.peak, old.len() + inner.len());
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        old.meter.absorb(&mut inner.meter);
+        old.growth = inner.growth.take();
+        old.alloc_error_handler = inner.alloc_error_handler.take();
+        // All the spare chunks of the parent were lent.
+        old.spare = mem::take(&mut inner.spare);
+        #[cfg(feature = "std")]
+        {
+            old.recycler = inner.recycler.take();
+        }
+
+        // The first chunk is the one taken from the parent.
+        let mut chunks = inner.rest;
+        chunks.push(inner.current);
+        (old, chunks, this.old_len)
+    }
+
+    fn from_chunks(parent: &'a RefCell<ChunkList<T>>, rebound: bool) -> Self {
+        let mut old = parent.borrow_mut();
+        let mut inner_vec = mem::take(&mut old.current);
         let old_len = inner_vec.len();
+        let spare = mem::take(&mut old.spare);
+        let lent = Lent {
+            start: inner_vec.as_mut_ptr() as *mut u8,
+            len: old_len,
+            capacity: inner_vec.capacity(),
+            spare: spare.len(),
+            spare_capacity: spare.iter().map(Vec::capacity).sum(),
+        };
+        old.lent = Some(lent);
         let inner = Arena {
             chunks: RefCell::new(ChunkList {
-                current: inner_vec,
-                rest: Vec::new(),
+                max_chunk_size: old.max_chunk_size,
+                label: old.label,
+                // Given back on drop.
+                spare,
+                growth: old.growth.take(),
+                alloc_error_handler: old.alloc_error_handler.take(),
+                #[cfg(all(feature = "mmap", target_os = "linux"))]
+                advice: old.advice,
+                #[cfg(feature = "std")]
+                recycler: old.recycler.take(),
+                ..ChunkList::new(inner_vec)
             }),
         };
+        drop(old);
+        // What `chunk_usage` would count.
+        let base_chunks = lent.spare + (lent.capacity != 0) as usize;
+        let base_bytes = (lent.capacity + lent.spare_capacity) * mem::size_of::<T>();
         Self {
             inner,
-            old,
+            parent,
+            lock: Some(parent.borrow()),
             old_len,
+            rebound,
+            base_chunks,
+            base_bytes,
+            free_reserved: false,
+        }
+    }
+}
+
+// The number of chunks obtained from the allocator, and their size in
+// bytes.
+fn chunk_usage<T>(chunks: &ChunkList<T>) -> (usize, usize) {
+    let all = || {
+        chunks
+            .rest
+            .iter()
+            .chain(&chunks.spare)
+            .chain(iter::once(&chunks.current))
+    };
+    let mut count = all().filter(|chunk| chunk.capacity() != 0).count();
+    let mut bytes = all()
+        .map(|chunk| chunk.capacity() * mem::size_of::<T>())
+        .sum();
+    if let Some(lent) = chunks.lent {
+        count += lent.spare + (lent.capacity != 0) as usize;
+        bytes += (lent.capacity + lent.spare_capacity) * mem::size_of::<T>();
+    }
+    (count, bytes)
+}
+
+impl<T> Arena<T> {
+    /// Runs `f` with a [`SubArena`] of this arena, and rolls the sub-arena
+    /// back when `f` returns or panics.
+    ///
+    /// The references allocated in the sub-arena only live as long as the
+    /// closure, so they cannot escape it: this is the same as creating a
+    /// `SubArena` and dropping it, without the guard to keep track of.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the arena is already borrowed, for example by another
+    /// `SubArena`. Use `sub.scoped` on the sub-arena to nest scopes.
+    /// To allocate values with shorter lifetimes than those of the arena,
+    /// use [`SubArenaBuilder::scoped`] instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let kept = arena.alloc(String::from("kept"));
+    /// let total = arena.scoped(|sub| {
+    ///     let words = sub.alloc_extend(vec![String::from("a"), String::from("bc")]);
+    ///     let nested = sub.scoped(|inner| inner.alloc(String::from("d")).len());
+    ///     words.iter().map(|w| w.len()).sum::<usize>() + nested
+    /// });
+    /// assert_eq!(total, 4);
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(kept, "kept");
+    /// ```
+    ///
+    /// References into the sub-arena cannot be returned:
+    ///
+    /// ```compile_fail
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let escaped = arena.scoped(|sub| sub.alloc(1));
+    /// ```
+    pub fn scoped<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&SubArena<'_, T>) -> R,
+    {
+        let sub_arena = SubArena::new(self);
+        f(&sub_arena)
+    }
+}
+
+impl<'a, T: Clone> SubArena<'a, T> {
+    /// Like [`Arena::grow_last`], but a slice of the parent arena is always
+    /// cloned, even if it is the last allocation: its values belong to the
+    /// parent, and stay where they are.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Arena, SubArena};
+    ///
+    /// let arena = Arena::new();
+    /// let words = arena.alloc_extend(vec!["a", "b"]);
+    /// let sub_arena = SubArena::new(&arena);
+    /// let more = sub_arena.grow_last(words, Some("c"));
+    /// assert_eq!(more, ["a", "b", "c"]);
+    /// drop(sub_arena);
+    /// assert_eq!(arena.len(), 2);
+    /// ```
+    pub fn grow_last<'b, I>(&'b self, slice: &'b mut [T], iterable: I) -> &'b mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let of_parent = {
+            let chunks = self.inner.chunks.borrow();
+            // The values of the parent are at the start of the first chunk,
+            // which is still current if the sub-arena didn't add any.
+            let end = chunks.current.as_ptr() as usize + self.old_len * mem::size_of::<T>();
+            chunks.rest.is_empty() && (slice.as_ptr() as usize) < end
+        };
+        if of_parent {
+            self.inner
+                .alloc_extend(slice.iter().cloned().chain(iterable))
+        } else {
+            self.inner.grow_last(slice, iterable)
         }
     }
 }
 
 impl<'a, T> Drop for SubArena<'a, T> {
     fn drop(&mut self) {
-        let inner = self.inner.chunks.get_mut();
-        let mut stolen_vec = mem::replace(
-            inner.rest.get_mut(0).unwrap_or(&mut inner.current),
-            Vec::new(),
+        self.lock = None;
+        let mut old = self.parent.borrow_mut();
+        old.lent = None;
+        // The values of the parent arena are its older chunks, then those
+        // of the sub-arena.
+        let len = old.len() + self.inner.len();
+        old.peak = cmp::max(old.peak, len);
+        #[cfg(feature = "tracing")]
+        instrument::rolled_back(
+            old.label,
+            self.inner.len() - self.old_len,
+            self.inner.chunks.get_mut().rest.len(),
         );
-        while stolen_vec.len() > self.old_len {
-            stolen_vec.pop();
+
+        let inner = self.inner.chunks.get_mut();
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        old.meter.absorb(&mut inner.meter);
+        old.growth = inner.growth.take();
+        old.alloc_error_handler = inner.alloc_error_handler.take();
+        // All the spare chunks of the parent were lent.
+        old.spare = mem::take(&mut inner.spare);
+        #[cfg(feature = "std")]
+        {
+            old.recycler = inner.recycler.take();
+        }
+
+        // The first chunk is the one taken from the parent. Unless the
+        // sub-arena filled it, it is still current, and giving it back
+        // allocates nothing, which keeps short nested scopes cheap.
+        let mut added = mem::take(&mut inner.rest);
+        let mut stolen_vec = mem::take(&mut inner.current);
+        if !added.is_empty() {
+            added.push(stolen_vec);
+            stolen_vec = added.remove(0);
+        }
+        old.current = stolen_vec;
+
+        // The parent is whole again before any value is dropped. If a
+        // destructor panics, `truncate` and `clear` still drop the values
+        // after it, and unwinding drops the added chunks with the values
+        // left in them, so the parent only misses the chunks to reuse.
+        old.current.truncate(self.old_len);
+
+        // The parent can reuse the other chunks, in the same order.
+        for chunk in &mut added {
+            chunk.clear();
+        }
+        if let (Some(reserved), true) = (inner.reserved, self.free_reserved) {
+            let is_reserved = |chunk: &Vec<T>| chunk.as_ptr() as usize == reserved;
+            added.retain(|chunk| !is_reserved(chunk));
+            old.spare.retain(|chunk| !is_reserved(chunk));
         }
+        old.spare.extend(added.into_iter().rev());
+    }
+}
 
-        mem::swap(&mut stolen_vec, &mut self.old.current);
+impl<'a, T> fmt::Debug for SubArena<'a, T> {
+    /// Like the `Debug` output of `Arena`, but only counts the values
+    /// allocated in this sub-arena, and the chunks it added, like
+    /// [`stats`][SubArena::stats].
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chunk_count = match self.inner.chunks.try_borrow() {
+            Ok(chunks) => chunk_usage(&chunks).0 - self.base_chunks,
+            Err(_) => return f.debug_struct("SubArena").finish_non_exhaustive(),
+        };
+        f.debug_struct("SubArena")
+            .field("len", &self.len())
+            .field("chunk_count", &chunk_count)
+            .field("used_bytes", &(self.len() * mem::size_of::<T>()))
+            .finish()
     }
 }
 
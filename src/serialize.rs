@@ -0,0 +1,197 @@
+use crate::{Arena, FrozenArena, Id, IdArena, SyncArena};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cmp;
+use core::fmt;
+
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// Serializes the values as a sequence, in allocation order. Only available
+/// with the `serde` feature.
+///
+/// An `Arena` may have handed out mutable references to its values, so it
+/// is serialized through the view returned by
+/// [`Arena::share`][crate::Arena::share].
+///
+/// ## Example
+///
+/// ```
+/// # extern crate serde_json;
+/// use typed_arena::Arena;
+///
+/// let mut arena = Arena::new();
+/// arena.alloc_extend(1..4);
+/// let json = serde_json::to_string(&arena.share()).unwrap();
+/// assert_eq!(json, "[1,2,3]");
+/// ```
+impl<'a, T: Serialize> Serialize for SyncArena<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Serializes the values as a sequence, in allocation order. Only available
+/// with the `serde` feature.
+impl<T: Serialize> Serialize for FrozenArena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.share().serialize(serializer)
+    }
+}
+
+/// Serializes the values as a sequence, in allocation order, so that the
+/// index of each value is its id. Only available with the `serde` feature.
+impl<T: Serialize> Serialize for IdArena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Values are only modified through `&mut self`.
+        let chunks = self.values.chunks.borrow();
+        SyncArena::new(&chunks.rest, &chunks.current).serialize(serializer)
+    }
+}
+
+/// Deserializes a sequence straight into an arena, created by
+/// [`Arena::seq_seed`].
+///
+/// It is a `DeserializeSeed` whose value is the slice of the deserialized
+/// elements, so it can also deserialize a sequence nested in a larger
+/// document. Only available with the `serde` feature.
+pub struct SeqSeed<'a, T> {
+    arena: &'a Arena<T>,
+}
+
+impl<T> Arena<T> {
+    /// Returns a `DeserializeSeed` that deserializes a sequence into the
+    /// arena.
+    ///
+    /// See [`SeqSeed`].
+    pub fn seq_seed(&self) -> SeqSeed<'_, T> {
+        SeqSeed { arena: self }
+    }
+
+    /// Deserializes a sequence, allocating each element in the arena, and
+    /// returns the elements as a slice.
+    ///
+    /// The elements are written right after the last allocation as they
+    /// are deserialized, without going through a `Vec`. On error, the
+    /// elements deserialized so far are dropped, and the arena is left as
+    /// it was. Only available with the `serde` feature.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if an element allocates in the arena while it is
+    /// deserialized.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # extern crate serde_json;
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<String> = Arena::new();
+    /// let mut json = serde_json::Deserializer::from_str(r#"["a", "b"]"#);
+    /// let names = arena.deserialize_seq(&mut json).unwrap();
+    /// assert_eq!(names, ["a", "b"]);
+    /// ```
+    pub fn deserialize_seq<'de, D>(&self, deserializer: D) -> Result<&mut [T], D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        self.seq_seed().deserialize(deserializer)
+    }
+}
+
+impl<'a, 'de, T: Deserialize<'de>> DeserializeSeed<'de> for SeqSeed<'a, T> {
+    type Value = &'a mut [T];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<&'a mut [T], D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de, T: Deserialize<'de>> Visitor<'de> for SeqSeed<'a, T> {
+    type Value = &'a mut [T];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<&'a mut [T], A::Error> {
+        let mut builder = self.arena.slice_builder();
+        // Don't trust the size hint of untrusted input too much.
+        builder.reserve(cmp::min(seq.size_hint().unwrap_or(0), 4096));
+        while let Some(value) = seq.next_element()? {
+            builder.push(value);
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl<'a, T> Clone for SeqSeed<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for SeqSeed<'a, T> {}
+
+impl<'a, T> fmt::Debug for SeqSeed<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SeqSeed").finish()
+    }
+}
+
+impl<T> IdArena<T> {
+    /// Deserializes a sequence, allocating each element in the arena, and
+    /// returns their ids, in order.
+    ///
+    /// On error, the elements deserialized so far stay in the arena. Only
+    /// available with the `serde` feature.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the arena would hold more than `u32::MAX` values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # extern crate serde_json;
+    /// use typed_arena::IdArena;
+    ///
+    /// let arena: IdArena<u32> = IdArena::new();
+    /// arena.alloc(0);
+    /// let mut json = serde_json::Deserializer::from_str("[1, 2]");
+    /// let ids = arena.deserialize_seq(&mut json).unwrap();
+    /// assert_eq!(ids.iter().map(|id| id.index()).collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(arena[ids[1]], 2);
+    /// ```
+    pub fn deserialize_seq<'de, D>(&self, deserializer: D) -> Result<Vec<Id<T>>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(IdSeqVisitor { arena: self })
+    }
+}
+
+struct IdSeqVisitor<'a, T> {
+    arena: &'a IdArena<T>,
+}
+
+impl<'a, 'de, T: Deserialize<'de>> Visitor<'de> for IdSeqVisitor<'a, T> {
+    type Value = Vec<Id<T>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<Id<T>>, A::Error> {
+        let mut ids = Vec::with_capacity(cmp::min(seq.size_hint().unwrap_or(0), 4096));
+        while let Some(value) = seq.next_element()? {
+            ids.push(self.arena.alloc(value));
+        }
+        Ok(ids)
+    }
+}
@@ -0,0 +1,224 @@
+use crate::Arena;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cell::RefCell;
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+/// A typed pool, whose values can be deallocated one by one.
+///
+/// `alloc` returns a [`PoolBox`], which owns its value like a `Box`. When
+/// the `PoolBox` is dropped, or given to [`dealloc`][Pool::dealloc], the
+/// value's slot goes on a free list, and the next allocation reuses it. The
+/// slots live in the chunks of an arena, so they never move, and are freed
+/// all at once with the pool.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Pool;
+///
+/// let particles = Pool::new();
+/// let a = particles.alloc([0.0f32; 3]);
+/// let b = particles.alloc([1.0f32; 3]);
+/// assert_eq!(particles.dealloc(a), [0.0; 3]);
+///
+/// // Reuses the slot of `a`.
+/// let c = particles.alloc([2.0f32; 3]);
+/// assert_eq!(particles.capacity_used(), 2);
+/// # drop((b, c));
+/// ```
+pub struct Pool<T> {
+    slots: Arena<MaybeUninit<T>>,
+    free: RefCell<Vec<NonNull<T>>>,
+}
+
+// The pool owns its values, like a `Vec<T>`.
+unsafe impl<T: Send> Send for Pool<T> {}
+
+/// A value allocated in a [`Pool`].
+///
+/// It dereferences to the value, and gives its slot back to the pool when
+/// dropped.
+pub struct PoolBox<'p, T> {
+    pool: &'p Pool<T>,
+    value: NonNull<T>,
+}
+
+impl<T> Pool<T> {
+    /// Construct a new pool.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Pool;
+    ///
+    /// let pool = Pool::new();
+    /// # pool.alloc(1);
+    /// ```
+    pub const fn new() -> Pool<T> {
+        Pool {
+            slots: Arena::new(),
+            free: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Construct a new pool with `n` slots pre-allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Pool;
+    ///
+    /// let pool = Pool::with_capacity(1337);
+    /// # pool.alloc(1);
+    /// ```
+    pub fn with_capacity(n: usize) -> Pool<T> {
+        Pool {
+            slots: Arena::with_capacity(n),
+            free: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Allocates a value in the pool, reusing a free slot if there is one.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Pool;
+    ///
+    /// let pool = Pool::new();
+    /// let mut x = pool.alloc(41);
+    /// *x += 1;
+    /// assert_eq!(*x, 42);
+    /// ```
+    pub fn alloc(&self, value: T) -> PoolBox<'_, T> {
+        let slot = self.free.borrow_mut().pop();
+        let value = match slot {
+            Some(slot) => {
+                unsafe { ptr::write(slot.as_ptr(), value) };
+                slot
+            }
+            None => NonNull::from(self.slots.alloc(MaybeUninit::new(value))).cast(),
+        };
+        PoolBox { pool: self, value }
+    }
+
+    /// Deallocates a value, and returns it. Its slot is reused by a later
+    /// allocation.
+    ///
+    /// This is the same as [`PoolBox::into_inner`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the value is not from this pool.
+    pub fn dealloc(&self, value: PoolBox<'_, T>) -> T {
+        assert!(ptr::eq(value.pool, self), "value is not from this pool");
+        PoolBox::into_inner(value)
+    }
+
+    /// Return the number of slots in the pool, used or free.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Pool;
+    ///
+    /// let pool = Pool::new();
+    /// drop(pool.alloc(1));
+    /// drop(pool.alloc(2));
+    /// assert_eq!(pool.capacity_used(), 1);
+    /// ```
+    pub fn capacity_used(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Return the number of values in the pool.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.borrow().len()
+    }
+
+    /// Returns `true` if the pool holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn release(&self, slot: NonNull<T>) {
+        self.free.borrow_mut().push(slot);
+    }
+}
+
+impl<T> Default for Pool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("len", &self.len())
+            .field("capacity_used", &self.capacity_used())
+            .finish()
+    }
+}
+
+impl<'p, T> PoolBox<'p, T> {
+    /// Deallocates the value, and returns it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{Pool, PoolBox};
+    ///
+    /// let pool = Pool::new();
+    /// let name = pool.alloc(String::from("Ferris"));
+    /// assert_eq!(PoolBox::into_inner(name), "Ferris");
+    /// assert!(pool.is_empty());
+    /// ```
+    pub fn into_inner(this: Self) -> T {
+        let this = mem::ManuallyDrop::new(this);
+        let value = unsafe { ptr::read(this.value.as_ptr()) };
+        this.pool.release(this.value);
+        value
+    }
+}
+
+impl<'p, T> Deref for PoolBox<'p, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<'p, T> DerefMut for PoolBox<'p, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.value.as_mut() }
+    }
+}
+
+impl<'p, T> Drop for PoolBox<'p, T> {
+    fn drop(&mut self) {
+        // The slot is only given back once the value is dropped, so the
+        // pool is consistent even if that panics.
+        struct Release<'a, 'p, T>(&'a PoolBox<'p, T>);
+        impl<'a, 'p, T> Drop for Release<'a, 'p, T> {
+            fn drop(&mut self) {
+                self.0.pool.release(self.0.value);
+            }
+        }
+        let release = Release(self);
+        unsafe { ptr::drop_in_place(release.0.value.as_ptr()) };
+    }
+}
+
+impl<'p, T: fmt::Debug> fmt::Debug for PoolBox<'p, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
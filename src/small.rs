@@ -0,0 +1,147 @@
+use crate::Arena;
+
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// An arena that keeps its first `N` values inline.
+///
+/// The first `N` values are stored inside the `SmallArena` itself, and only
+/// the values after them spill to heap chunks, like in an `Arena`. Arenas
+/// that usually hold a handful of values then never allocate.
+///
+/// Since the values can live inside it, a `SmallArena` can't be moved while
+/// they are borrowed.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::SmallArena;
+///
+/// let arena = SmallArena::<u32, 4>::new();
+/// let x = arena.alloc(1);
+/// let y = arena.alloc(2);
+/// assert!(!arena.spilled());
+/// assert_eq!(*x + *y, 3);
+/// ```
+pub struct SmallArena<T, const N: usize> {
+    inline: UnsafeCell<[MaybeUninit<T>; N]>,
+    inline_len: Cell<usize>,
+    heap: Arena<T>,
+}
+
+impl<T, const N: usize> SmallArena<T, N> {
+    /// Construct a new small arena.
+    ///
+    /// This doesn't allocate until more than `N` values are allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SmallArena;
+    ///
+    /// let arena = SmallArena::<usize, 8>::new();
+    /// # arena.alloc(1);
+    /// ```
+    pub fn new() -> Self {
+        SmallArena {
+            // An array of `MaybeUninit` doesn't need to be initialized.
+            inline: UnsafeCell::new(unsafe { MaybeUninit::uninit().assume_init() }),
+            inline_len: Cell::new(0),
+            heap: Arena::new(),
+        }
+    }
+
+    /// Allocates a value in the arena, and returns a mutable reference to
+    /// that value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SmallArena;
+    ///
+    /// let arena = SmallArena::<_, 1>::new();
+    /// let x = arena.alloc(42);
+    /// let y = arena.alloc(43);
+    /// assert_eq!(*x + *y, 85);
+    /// ```
+    #[inline]
+    pub fn alloc(&self, value: T) -> &mut T {
+        let len = self.inline_len.get();
+        if len == N {
+            return self.heap.alloc(value);
+        }
+        unsafe {
+            let slot = (self.inline.get() as *mut T).add(len);
+            ptr::write(slot, value);
+            self.inline_len.set(len + 1);
+            &mut *slot
+        }
+    }
+
+    /// Return the number of values allocated in the arena, inline or not.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SmallArena;
+    ///
+    /// let arena = SmallArena::<_, 2>::new();
+    /// for i in 0..5 {
+    ///     arena.alloc(i);
+    /// }
+    /// assert_eq!(arena.len(), 5);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.inline_len.get() + self.heap.len()
+    }
+
+    /// Returns `true` if no values have been allocated in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the arena has outgrown its inline storage, and
+    /// allocated values on the heap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::SmallArena;
+    ///
+    /// let arena = SmallArena::<_, 1>::new();
+    /// arena.alloc('a');
+    /// assert!(!arena.spilled());
+    /// arena.alloc('b');
+    /// assert!(arena.spilled());
+    /// ```
+    pub fn spilled(&self) -> bool {
+        !self.heap.is_empty()
+    }
+}
+
+impl<T, const N: usize> Default for SmallArena<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for SmallArena<T, N> {
+    fn drop(&mut self) {
+        let len = self.inline_len.replace(0);
+        unsafe {
+            let start = self.inline.get() as *mut T;
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(start, len));
+        }
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for SmallArena<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SmallArena")
+            .field("inline_len", &self.inline_len.get())
+            .field("heap", &self.heap)
+            .finish()
+    }
+}
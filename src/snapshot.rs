@@ -0,0 +1,118 @@
+use crate::Arena;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::iter;
+use core::marker::PhantomData;
+use core::mem;
+use core::slice;
+
+/// Maps the values of an arena to their allocation index, returned by
+/// [`Arena::clone_into_new_arena`].
+///
+/// The clone keeps the allocation order, so the index of a value is the
+/// same in both arenas: a reference into the old arena becomes an index,
+/// and the index finds its copy in the new one. The map only holds the
+/// address ranges of the old chunks, and doesn't borrow the arena.
+pub struct CloneMap<T> {
+    // The start address and length of each chunk, in allocation order.
+    chunks: Vec<(usize, usize)>,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T: Clone> Arena<T> {
+    /// Clones every value into a new, independent arena, in allocation
+    /// order, and returns it with a [`CloneMap`] from the values of this
+    /// arena to their index.
+    ///
+    /// The references the arena already handed out stay valid, so they can
+    /// be looked up in the map afterwards. The new arena holds all the
+    /// copies in a single chunk.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if called while a `SubArena` or `SliceBuilder` of the arena is
+    /// alive, since they hold some of its chunks, or from within
+    /// [`alloc_cyclic`][Arena::alloc_cyclic].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let state = Arena::new();
+    /// let counter = &*state.alloc_extend(0..3).last().unwrap();
+    ///
+    /// let (mut snapshot, map) = state.clone_into_new_arena();
+    /// *snapshot.get_mut(2).unwrap() += 10;
+    ///
+    /// let index = map.index_of(counter).unwrap();
+    /// assert_eq!(index, 2);
+    /// assert_eq!(snapshot.get(index), Some(&12));
+    /// assert_eq!(*counter, 2);
+    /// ```
+    pub fn clone_into_new_arena(&self) -> (Arena<T>, CloneMap<T>) {
+        let chunks = self.chunks.borrow();
+        assert!(chunks.lent.is_none(), "the arena is in use by a SubArena");
+        assert!(
+            chunks.pending.is_empty(),
+            "cannot clone an arena from within alloc_cyclic"
+        );
+        let mut ranges = Vec::with_capacity(chunks.rest.len() + 1);
+        let mut values = Vec::with_capacity(chunks.len());
+        for chunk in chunks.rest.iter().chain(iter::once(&chunks.current)) {
+            ranges.push((chunk.as_ptr() as usize, chunk.len()));
+            // Through the pointer of the chunk, like `get_shared`, so as not
+            // to reborrow the values handed out.
+            let chunk = unsafe { slice::from_raw_parts(chunk.as_ptr(), chunk.len()) };
+            values.extend_from_slice(chunk);
+        }
+        let map = CloneMap {
+            chunks: ranges,
+            _marker: PhantomData,
+        };
+        (Arena::from_vec(values), map)
+    }
+}
+
+impl<T> CloneMap<T> {
+    /// Returns the allocation index of `value`, or `None` if it is not a
+    /// value of the arena that was cloned.
+    ///
+    /// The addresses are those of the old arena at the time of the clone,
+    /// so `value` must not be from a chunk the arena allocated since. It
+    /// always returns `None` for zero-sized types, since they don't occupy
+    /// any memory.
+    pub fn index_of(&self, value: &T) -> Option<usize> {
+        let size = mem::size_of::<T>();
+        let address = value as *const T as usize;
+        let mut index = 0;
+        for &(start, len) in &self.chunks {
+            if address >= start && address < start + len * size {
+                return Some(index + (address - start) / size);
+            }
+            index += len;
+        }
+        None
+    }
+
+    /// Return the number of values that were cloned.
+    pub fn len(&self) -> usize {
+        self.chunks.iter().map(|&(_, len)| len).sum()
+    }
+
+    /// Returns `true` if the cloned arena was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> fmt::Debug for CloneMap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CloneMap")
+            .field("len", &self.len())
+            .finish()
+    }
+}
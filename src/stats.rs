@@ -0,0 +1,209 @@
+#[cfg(feature = "allocator_api")]
+use crate::Allocator;
+use crate::Arena;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::cmp;
+use core::fmt;
+use core::iter;
+use core::mem;
+use core::ops::Range;
+
+/// A snapshot of the memory use of an arena, returned by [`Arena::stats`].
+///
+/// It gathers in one call what [`Arena::len`], [`Arena::chunk_count`],
+/// [`Arena::used_bytes`] and [`Arena::allocated_bytes`] report, along with
+/// the peak use and the label given with
+/// [`ArenaBuilder::label`][crate::ArenaBuilder::label], so that a memory
+/// dashboard can tell arenas apart.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Arena;
+///
+/// let mut arena = Arena::<u32>::builder()
+///     .label("scratch")
+///     .initial_capacity(16)
+///     .build();
+/// arena.alloc_extend(0..10);
+/// arena.clear();
+/// arena.alloc_extend(0..4);
+///
+/// let stats = arena.stats();
+/// assert_eq!(stats.label, Some("scratch"));
+/// assert_eq!((stats.len, stats.peak_len), (4, 10));
+/// assert_eq!((stats.used_bytes, stats.peak_used_bytes), (16, 40));
+/// assert_eq!((stats.chunk_count, stats.allocated_bytes), (1, 64));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ArenaStats {
+    /// The label of the arena, if it has one.
+    pub label: Option<&'static str>,
+    /// The number of chunks obtained from the allocator.
+    pub chunk_count: usize,
+    /// The number of values in the arena.
+    pub len: usize,
+    /// The number of bytes taken up by the values.
+    pub used_bytes: usize,
+    /// The number of bytes of chunk memory obtained from the allocator.
+    pub allocated_bytes: usize,
+    /// The most values the arena ever held at once.
+    pub peak_len: usize,
+    /// The most bytes the values of the arena ever took up at once.
+    pub peak_used_bytes: usize,
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Returns a snapshot of the memory use of the arena.
+    ///
+    /// See [`ArenaStats`]. While a [`SubArena`][crate::SubArena] of the
+    /// arena is alive, only the values and chunks of the arena itself are
+    /// counted, as they were when the sub-arena was created.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if called while a `SliceBuilder` of the arena is alive.
+    pub fn stats(&self) -> ArenaStats {
+        let len = self.len();
+        let peak_len = cmp::max(self.chunks.borrow().peak, len);
+        ArenaStats {
+            label: self.chunks.borrow().label,
+            chunk_count: self.chunk_count(),
+            len,
+            used_bytes: len * mem::size_of::<T>(),
+            allocated_bytes: self.allocated_bytes(),
+            peak_len,
+            peak_used_bytes: peak_len * mem::size_of::<T>(),
+        }
+    }
+
+    /// Returns the address range of each chunk of the arena.
+    ///
+    /// The ranges cover the whole capacity of each chunk, used or not, in
+    /// the same order as [`debug_dump`][Arena::debug_dump], so tools like
+    /// crash handlers or conservative scanners can tell whether a pointer
+    /// points into the arena. To check whether a reference points to an
+    /// allocated value, see [`contains`][Arena::contains]. The ranges stay
+    /// valid until the arena allocates a chunk or frees one.
+    ///
+    /// For zero-sized types, the ranges are empty.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if called while a `SubArena` or `SliceBuilder` of the arena is
+    /// alive, since they hold some of its chunks.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u64>::with_capacity(4);
+    /// let x: *const u64 = arena.alloc(1);
+    /// let ranges = arena.chunk_ranges();
+    /// assert_eq!(ranges.len(), 1);
+    /// assert!(ranges[0].contains(&x));
+    /// assert_eq!(ranges[0].end as usize - ranges[0].start as usize, 32);
+    /// ```
+    pub fn chunk_ranges(&self) -> Vec<Range<*const T>> {
+        let chunks = self.chunks.borrow();
+        assert!(chunks.lent.is_none(), "the arena is in use by a SubArena");
+        chunks
+            .rest
+            .iter()
+            .chain(iter::once(&chunks.current))
+            .chain(chunks.spare.iter().rev())
+            .filter(|chunk| chunk.capacity() != 0)
+            .map(|chunk| {
+                let start = chunk.as_ptr();
+                // Stays within the allocation of the chunk.
+                let end = unsafe { start.add(chunk.capacity()) };
+                start..end
+            })
+            .collect()
+    }
+
+    /// Writes the layout of the arena to `w`, one line per chunk, with its
+    /// length, capacity and address range.
+    ///
+    /// The chunks are listed in allocation order, the current one last but
+    /// for the empty chunks set aside for reuse. Addresses are in hex, and
+    /// ranges cover the whole capacity of the chunk. While a `SubArena` of
+    /// the arena is alive, the chunks are not listed, and while a
+    /// `SliceBuilder` is, only a line saying so is written.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::<u32>::with_capacity(2);
+    /// for i in 0..3 {
+    ///     arena.alloc(i);
+    /// }
+    /// let mut dump = String::new();
+    /// arena.debug_dump(&mut dump).unwrap();
+    /// let lines: Vec<&str> = dump.lines().collect();
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0], "Arena: 3 values in 2 chunks, 24 bytes allocated");
+    /// assert!(lines[1].starts_with("chunk 0: 2/2 values, 0x"));
+    /// assert!(lines[2].starts_with("chunk 1 (current): 1/4 values, 0x"));
+    /// ```
+    pub fn debug_dump<W: fmt::Write>(&self, mut w: W) -> fmt::Result {
+        let chunks = match self.chunks.try_borrow() {
+            Ok(chunks) => chunks,
+            Err(_) => return writeln!(w, "Arena: borrowed by a SliceBuilder"),
+        };
+        let size = mem::size_of::<T>();
+        if let Some(label) = chunks.label {
+            write!(w, "{}: ", label)?;
+        } else {
+            w.write_str("Arena: ")?;
+        }
+        drop(chunks);
+        writeln!(
+            w,
+            "{} values in {} chunks, {} bytes allocated",
+            self.len(),
+            self.chunk_count(),
+            self.allocated_bytes(),
+        )?;
+
+        let chunks = self.chunks.borrow();
+        if chunks.lent.is_some() {
+            return writeln!(w, "chunks lent to a SubArena");
+        }
+        let current = chunks.rest.len();
+        let all = chunks
+            .rest
+            .iter()
+            .chain(iter::once(&chunks.current))
+            .chain(chunks.spare.iter().rev())
+            .enumerate()
+            .map(|(i, chunk)| match i.cmp(&current) {
+                cmp::Ordering::Less => ("", chunk),
+                cmp::Ordering::Equal => (" (current)", chunk),
+                cmp::Ordering::Greater => (" (spare)", chunk),
+            })
+            // Like `chunk_count`, skip the chunks that were never allocated.
+            .filter(|(_, chunk)| chunk.capacity() != 0);
+        for (i, (kind, chunk)) in all.enumerate() {
+            let start = chunk.as_ptr() as usize;
+            writeln!(
+                w,
+                "chunk {}{}: {}/{} values, {:#x}..{:#x}",
+                i,
+                kind,
+                chunk.len(),
+                chunk.capacity(),
+                start,
+                start + chunk.capacity() * size,
+            )?;
+        }
+        Ok(())
+    }
+}
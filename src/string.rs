@@ -0,0 +1,146 @@
+use crate::{ArenaVec, Bump};
+
+use core::fmt;
+use core::ops::Deref;
+use core::str;
+
+/// A growable string whose buffer is allocated in a [`Bump`].
+///
+/// It is to `String` what [`ArenaVec`] is to `Vec`: formatted names and
+/// paths are built in the bump arena with `push_str` or `write!`, and
+/// [`into_str`][ArenaString::into_str] turns the result into a `&str` that
+/// lives as long as the bump.
+///
+/// ## Example
+///
+/// ```
+/// use std::fmt::Write;
+/// use typed_arena::{ArenaString, Bump};
+///
+/// let bump = Bump::new();
+/// let mut path = ArenaString::new_in(&bump);
+/// for (i, part) in ["usr", "lib"].iter().enumerate() {
+///     write!(path, "/{}{}", part, i).unwrap();
+/// }
+/// let path: &str = path.into_str();
+/// assert_eq!(path, "/usr0/lib1");
+/// ```
+pub struct ArenaString<'a> {
+    bytes: ArenaVec<'a, u8>,
+}
+
+impl<'a> ArenaString<'a> {
+    /// Construct a new, empty string in `bump`.
+    pub fn new_in(bump: &'a Bump) -> ArenaString<'a> {
+        ArenaString {
+            bytes: ArenaVec::new_in(bump),
+        }
+    }
+
+    /// Construct a new string in `bump`, with room for `capacity` bytes.
+    pub fn with_capacity_in(capacity: usize, bump: &'a Bump) -> ArenaString<'a> {
+        ArenaString {
+            bytes: ArenaVec::with_capacity_in(capacity, bump),
+        }
+    }
+
+    /// Appends a string slice.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaString, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let mut s = ArenaString::new_in(&bump);
+    /// s.push_str("abc");
+    /// s.push('d');
+    /// assert_eq!(s.as_str(), "abcd");
+    /// ```
+    pub fn push_str(&mut self, s: &str) {
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+
+    /// Appends a character.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Removes the last character, and returns it, or `None` if the string
+    /// is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.bytes.truncate(self.bytes.len() - c.len_utf8());
+        Some(c)
+    }
+
+    /// Drops the content of the string, and keeps its buffer.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    /// Return the length of the string, in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Return the number of bytes the string can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Returns the content of the string.
+    pub fn as_str(&self) -> &str {
+        // Only ever appended to with whole strings.
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Converts the string into a `&str` that lives as long as the bump.
+    pub fn into_str(self) -> &'a mut str {
+        unsafe { str::from_utf8_unchecked_mut(self.bytes.into_slice()) }
+    }
+}
+
+impl<'a> Deref for ArenaString<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> fmt::Write for ArenaString<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for ArenaString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a> fmt::Display for ArenaString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a> PartialEq<str> for ArenaString<'a> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for ArenaString<'a> {
+    fn eq(&self, other: &&'b str) -> bool {
+        self.as_str() == *other
+    }
+}
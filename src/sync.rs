@@ -0,0 +1,288 @@
+use core::fmt;
+use core::iter::FusedIterator;
+use core::slice;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A read-only view of an arena's values, which can be shared between
+/// threads.
+///
+/// Created by [`Arena::share`][crate::Arena::share]. The arena stays
+/// mutably borrowed as long as the view is alive, so there is no way to
+/// allocate into it meanwhile: a `SyncArena` is `Sync` and `Send` whenever
+/// `T: Sync`, and it is `Copy`, for each thread to take its own.
+///
+/// Values are found again by their index, as returned by
+/// [`Arena::alloc_indexed`][crate::Arena::alloc_indexed].
+///
+/// ## Example
+///
+/// ```
+/// use std::thread;
+/// use typed_arena::Arena;
+///
+/// // Freed at the end, once the threads are done with it.
+/// let raw = Box::into_raw(Box::new(Arena::new()));
+/// let arena: &'static mut Arena<_> = unsafe { &mut *raw };
+/// let (root, _) = arena.alloc_indexed(String::from("root"));
+/// arena.alloc_extend((0..10).map(|i| i.to_string()));
+///
+/// let shared = arena.share();
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| thread::spawn(move || shared.get(root).unwrap().len()))
+///     .collect();
+/// for worker in workers {
+///     assert_eq!(worker.join().unwrap(), 4);
+/// }
+/// drop(unsafe { Box::from_raw(raw) });
+/// ```
+pub struct SyncArena<'a, T> {
+    rest: &'a [Vec<T>],
+    current: &'a [T],
+    len: usize,
+}
+
+impl<'a, T> SyncArena<'a, T> {
+    pub(crate) fn new(rest: &'a [Vec<T>], current: &'a [T]) -> Self {
+        let len = rest.iter().fold(current.len(), |a, v| a + v.len());
+        SyncArena { rest, current, len }
+    }
+
+    /// Return the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the value at `index` in allocation order, or
+    /// `None` if the arena holds fewer values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc_extend(10..20);
+    /// let shared = arena.share();
+    /// assert_eq!(shared.get(3), Some(&13));
+    /// assert_eq!(shared.get(10), None);
+    /// ```
+    pub fn get(&self, mut index: usize) -> Option<&'a T> {
+        for chunk in self.chunks() {
+            if index < chunk.len() {
+                return chunk.get(index);
+            }
+            index -= chunk.len();
+        }
+        None
+    }
+
+    /// Returns an iterator over the values, in the order that they were
+    /// allocated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc_extend(1..=3);
+    /// assert_eq!(arena.share().iter().sum::<i32>(), 6);
+    /// ```
+    pub fn iter(&self) -> SyncIter<'a, T> {
+        SyncIter {
+            chunks: self.rest.iter(),
+            current: Some(self.current),
+            inner: [].iter(),
+            len: self.len,
+        }
+    }
+
+    fn chunks(&self) -> impl Iterator<Item = &'a [T]> {
+        let current = Some(self.current);
+        self.rest.iter().map(|chunk| &chunk[..]).chain(current)
+    }
+}
+
+impl<'a, T> Clone for SyncArena<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for SyncArena<'a, T> {}
+
+impl<'a, T> IntoIterator for SyncArena<'a, T> {
+    type Item = &'a T;
+    type IntoIter = SyncIter<'a, T>;
+
+    fn into_iter(self) -> SyncIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for SyncArena<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the values of a [`SyncArena`].
+pub struct SyncIter<'a, T> {
+    chunks: slice::Iter<'a, Vec<T>>,
+    current: Option<&'a [T]>,
+    inner: slice::Iter<'a, T>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for SyncIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(value) = self.inner.next() {
+                self.len -= 1;
+                return Some(value);
+            }
+            let chunk = match self.chunks.next() {
+                Some(chunk) => &chunk[..],
+                None => self.current.take()?,
+            };
+            self.inner = chunk.iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SyncIter<'a, T> {}
+
+impl<'a, T> FusedIterator for SyncIter<'a, T> {}
+
+impl<'a, T> Clone for SyncIter<'a, T> {
+    fn clone(&self) -> Self {
+        SyncIter {
+            chunks: self.chunks.clone(),
+            current: self.current,
+            inner: self.inner.clone(),
+            len: self.len,
+        }
+    }
+}
+
+/// An arena that no longer allocates, created by
+/// [`Arena::freeze`][crate::Arena::freeze].
+///
+/// It owns the values of the arena, and gives shared access to them. A
+/// `FrozenArena` is `Send` and `Sync` whenever `T` is, so once it is built
+/// it can be put in an `Arc` and read from any thread.
+///
+/// ## Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use typed_arena::Arena;
+///
+/// let arena = Arena::new();
+/// arena.alloc_extend(0..100);
+/// let frozen = Arc::new(arena.freeze());
+///
+/// let reader = Arc::clone(&frozen);
+/// let sum = thread::spawn(move || reader.iter().sum::<i32>());
+/// assert_eq!(sum.join().unwrap(), 4950);
+/// assert_eq!(frozen.get(42), Some(&42));
+/// ```
+pub struct FrozenArena<T> {
+    rest: Vec<Vec<T>>,
+    current: Vec<T>,
+}
+
+impl<T> FrozenArena<T> {
+    pub(crate) fn new(rest: Vec<Vec<T>>, current: Vec<T>) -> Self {
+        FrozenArena { rest, current }
+    }
+
+    /// Return the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.share().len()
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a reference to the value at `index` in allocation order, or
+    /// `None` if the arena holds fewer values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let (index, _) = arena.alloc_indexed('x');
+    /// let frozen = arena.freeze();
+    /// assert_eq!(frozen.get(index), Some(&'x'));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.share().get(index)
+    }
+
+    /// Returns an iterator over the values, in the order that they were
+    /// allocated.
+    pub fn iter(&self) -> SyncIter<'_, T> {
+        self.share().iter()
+    }
+
+    /// Returns a `Copy` view of the values, like
+    /// [`Arena::share`][crate::Arena::share].
+    pub fn share(&self) -> SyncArena<'_, T> {
+        SyncArena::new(&self.rest, &self.current)
+    }
+
+    /// Convert this `FrozenArena` into a `Vec<T>`, in allocation order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// arena.alloc("a");
+    /// arena.alloc("b");
+    /// assert_eq!(arena.freeze().into_vec(), ["a", "b"]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len());
+        for mut chunk in self.rest {
+            result.append(&mut chunk);
+        }
+        result.extend(self.current);
+        result
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FrozenArena<T> {
+    type Item = &'a T;
+    type IntoIter = SyncIter<'a, T>;
+
+    fn into_iter(self) -> SyncIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FrozenArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
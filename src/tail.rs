@@ -0,0 +1,374 @@
+use crate::{Arena, ChunkList};
+
+use core::cell::RefMut;
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ptr;
+use core::slice;
+use core::str;
+
+/// A slice being built at the end of an arena, created by
+/// [`Arena::slice_builder`].
+///
+/// Values are pushed one at a time right after the last allocation, and
+/// [`finish`][SliceBuilder::finish] turns them into a slice, so a sequence
+/// of unknown length is collected in a single pass without a temporary
+/// `Vec`. If the chunk fills up, the values built so far move to a new,
+/// larger chunk, which is fine since none of them has been handed out.
+///
+/// The builder keeps the arena borrowed: allocating anything else in the
+/// arena before `finish` panics. Dropping the builder without finishing
+/// drops the values.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Arena;
+///
+/// let arena = Arena::new();
+/// let mut digits = arena.slice_builder();
+/// for c in "a1b22c333".chars() {
+///     if let Some(d) = c.to_digit(10) {
+///         digits.push(d);
+///     }
+/// }
+/// let digits = digits.finish();
+/// assert_eq!(digits, [1, 2, 2, 3, 3, 3]);
+/// ```
+pub struct SliceBuilder<'a, T> {
+    chunks: RefMut<'a, ChunkList<T>>,
+    start: usize,
+}
+
+impl<T> Arena<T> {
+    /// Starts building a slice at the end of the arena.
+    ///
+    /// See [`SliceBuilder`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if another `SliceBuilder` of the arena is alive.
+    pub fn slice_builder(&self) -> SliceBuilder<'_, T> {
+        let chunks = self.chunks.borrow_mut();
+        let start = chunks.current.len();
+        SliceBuilder { chunks, start }
+    }
+}
+
+impl<'a, T> SliceBuilder<'a, T> {
+    /// Appends a value to the slice.
+    pub fn push(&mut self, value: T) {
+        let chunks = &mut *self.chunks;
+        if chunks.current.len() == chunks.current.capacity() {
+            chunks.reserve_tail(&mut self.start, 1);
+        }
+        chunks.current.push(value);
+    }
+
+    /// Makes room for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.chunks.reserve_tail(&mut self.start, additional);
+    }
+
+    /// Return the number of values in the slice so far.
+    pub fn len(&self) -> usize {
+        self.chunks.current.len() - self.start
+    }
+
+    /// Returns `true` if no value was pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the values pushed so far.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let mut fib = arena.slice_builder();
+    /// fib.extend([1, 1]);
+    /// while fib.len() < 10 {
+    ///     let next = fib.as_slice()[fib.len() - 2..].iter().sum();
+    ///     fib.push(next);
+    /// }
+    /// assert_eq!(fib.finish()[9], 55);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        let current = &self.chunks.current;
+        unsafe { slice::from_raw_parts(current.as_ptr().add(self.start), self.len()) }
+    }
+
+    /// Returns the values pushed so far, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let start = self.start;
+        let current = &mut self.chunks.current;
+        // Avoid going through `Vec::deref_mut`, which overlaps
+        // other references we have already handed out!
+        unsafe { slice::from_raw_parts_mut(current.as_mut_ptr().add(start), current.len() - start) }
+    }
+
+    /// Confirms the allocation of the values pushed so far, and returns
+    /// them, while the builder carries on with a new, empty slice.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// let mut lines = arena.slice_builder();
+    /// lines.extend("ab".chars());
+    /// let first = lines.take_slice();
+    /// lines.extend("cde".chars());
+    /// assert_eq!(lines.finish(), ['c', 'd', 'e']);
+    /// assert_eq!(first, ['a', 'b']);
+    /// ```
+    pub fn take_slice(&mut self) -> &'a mut [T] {
+        let slice = self.as_mut_slice() as *mut [T];
+        // The slice is left out of the builder, which never moves it again.
+        self.start = self.chunks.current.len();
+        unsafe { &mut *slice }
+    }
+
+    /// Confirms the allocation of the slice, and returns it.
+    pub fn finish(self) -> &'a mut [T] {
+        let mut this = ManuallyDrop::new(self);
+        let slice = this.as_mut_slice() as *mut [T];
+        // Releases the borrow of the arena.
+        drop(unsafe { ptr::read(&this.chunks) });
+        unsafe { &mut *slice }
+    }
+}
+
+impl<'a, T: Copy> SliceBuilder<'a, T> {
+    /// Appends a copy of each value of `values`.
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        self.reserve(values.len());
+        // There is enough capacity, so this never reallocates.
+        self.chunks.current.extend_from_slice(values);
+    }
+}
+
+/// Appends the bytes to the slice, so that anything that writes to an
+/// `io::Write` can write into the arena. Only available with the `std`
+/// feature.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Write;
+/// use typed_arena::Arena;
+///
+/// let arena: Arena<u8> = Arena::new();
+/// let mut out = arena.slice_builder();
+/// write!(out, "{}-{}", 1, 2).unwrap();
+/// out.write_all(b"!").unwrap();
+/// assert_eq!(out.finish(), b"1-2!");
+/// ```
+#[cfg(feature = "std")]
+impl<'a> std::io::Write for SliceBuilder<'a, u8> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> SliceBuilder<'a, u8> {
+    /// Reads `reader` to its end, and appends the bytes to the slice.
+    ///
+    /// Returns the number of bytes read. The bytes are read straight into
+    /// the chunk, and on error, the bytes read so far stay in the slice.
+    /// Only available with the `std` feature.
+    pub fn read_from<R: std::io::Read>(&mut self, mut reader: R) -> std::io::Result<usize> {
+        let start = self.len();
+        loop {
+            if self.chunks.current.len() == self.chunks.current.capacity() {
+                let additional = self.len().max(64);
+                self.reserve(additional);
+            }
+            let current = &mut self.chunks.current;
+            let len = current.len();
+            let capacity = current.capacity();
+            // `Read` wants initialized memory.
+            current.resize(capacity, 0);
+            // Avoid going through `Vec::deref_mut`, which overlaps
+            // other references we have already handed out!
+            let buf =
+                unsafe { slice::from_raw_parts_mut(current.as_mut_ptr().add(len), capacity - len) };
+            let result = reader.read(buf);
+            match result {
+                Ok(0) => {
+                    current.truncate(len);
+                    return Ok(self.len() - start);
+                }
+                Ok(n) => current.truncate(len + n),
+                Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => current.truncate(len),
+                Err(e) => {
+                    current.truncate(len);
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> Extend<T> for SliceBuilder<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T> Drop for SliceBuilder<'a, T> {
+    fn drop(&mut self) {
+        self.chunks.current.truncate(self.start);
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for SliceBuilder<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SliceBuilder")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
+
+/// A string being built at the end of an `Arena<u8>`, created by
+/// [`Arena::str_builder`].
+///
+/// It is a [`SliceBuilder`] that only takes whole strings, and implements
+/// `fmt::Write`, so `write!` formats straight into the arena, even without
+/// the `std` feature.
+///
+/// ## Example
+///
+/// ```
+/// use std::fmt::Write;
+/// use typed_arena::Arena;
+///
+/// let arena: Arena<u8> = Arena::new();
+/// let mut name = arena.str_builder();
+/// for i in 0..3 {
+///     write!(name, "x{}", i).unwrap();
+/// }
+/// assert_eq!(name.finish(), "x0x1x2");
+/// ```
+pub struct StrBuilder<'a> {
+    bytes: SliceBuilder<'a, u8>,
+}
+
+impl Arena<u8> {
+    /// Starts building a string at the end of the arena.
+    ///
+    /// See [`StrBuilder`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a `SliceBuilder` or `StrBuilder` of the arena is alive.
+    pub fn str_builder(&self) -> StrBuilder<'_> {
+        StrBuilder {
+            bytes: self.slice_builder(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Arena<u8> {
+    /// Reads `reader` to its end into the arena, and returns the bytes.
+    ///
+    /// The bytes are read straight into the chunks of the arena, without a
+    /// temporary buffer, so a parser can borrow slices of them for as long
+    /// as the arena lives. To read at most `n` bytes, pass
+    /// `reader.take(n)`. On error, the arena is left as it was. Only
+    /// available with the `std` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// use typed_arena::Arena;
+    ///
+    /// let arena: Arena<u8> = Arena::new();
+    /// let input: &[u8] = b"key=value";
+    /// let text = arena.read_to_arena(input).unwrap();
+    /// assert_eq!(text, b"key=value");
+    ///
+    /// let head = arena.read_to_arena(input.take(3)).unwrap();
+    /// assert_eq!(head, b"key");
+    /// ```
+    pub fn read_to_arena<R: std::io::Read>(&self, reader: R) -> std::io::Result<&mut [u8]> {
+        let mut builder = self.slice_builder();
+        builder.read_from(reader)?;
+        Ok(builder.finish())
+    }
+}
+
+impl<'a> StrBuilder<'a> {
+    /// Appends a string slice.
+    pub fn push_str(&mut self, s: &str) {
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+
+    /// Appends a character.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Return the length of the string so far, in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the string is empty so far.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the string built so far.
+    pub fn as_str(&self) -> &str {
+        // Only ever appended to with whole strings.
+        unsafe { str::from_utf8_unchecked(self.bytes.as_slice()) }
+    }
+
+    /// Confirms the allocation of the string built so far, and returns it,
+    /// while the builder carries on with a new, empty string.
+    ///
+    /// See [`SliceBuilder::take_slice`].
+    pub fn take_str(&mut self) -> &'a mut str {
+        unsafe { str::from_utf8_unchecked_mut(self.bytes.take_slice()) }
+    }
+
+    /// Confirms the allocation of the string, and returns it.
+    pub fn finish(self) -> &'a mut str {
+        unsafe { str::from_utf8_unchecked_mut(self.bytes.finish()) }
+    }
+}
+
+impl<'a> fmt::Write for StrBuilder<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for StrBuilder<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("StrBuilder").field(&self.as_str()).finish()
+    }
+}
@@ -36,9 +36,12 @@ fn arena_as_intended() {
         assert_eq!(node.0.unwrap().0.unwrap().1, 2);
         assert_eq!(node.0.unwrap().0.unwrap().0.unwrap().1, 1);
         assert!(node.0.unwrap().0.unwrap().0.unwrap().0.is_none());
+        // Every node tracks its drop with the same counter.
+        assert!(ptr::eq((node.2).0, &drop_counter));
 
         assert_eq!(arena.len(), 4);
 
+        #[allow(dropping_references)]
         mem::drop(node);
         assert_eq!(drop_counter.get(), 0);
 
@@ -95,6 +98,7 @@ fn test_alloc_extend() {
 }
 
 #[test]
+#[allow(clippy::unnecessary_fold)]
 fn test_alloc_uninitialized() {
     const LIMIT: usize = 15;
     let drop_counter = Cell::new(0);
@@ -114,6 +118,26 @@ fn test_alloc_uninitialized() {
     assert_eq!(drop_counter.get(), (0..LIMIT).fold(0, |a, e| a + e) as u32);
 }
 
+#[test]
+fn test_alloc_uninit_slice() {
+    const LIMIT: usize = 15;
+    let drop_counter = Cell::new(0);
+    unsafe {
+        let arena: Arena<Node> = Arena::with_capacity(4);
+        for i in 0..LIMIT {
+            let slice = arena.alloc_uninit_slice(i);
+            for (j, elem) in slice.iter_mut().enumerate() {
+                ptr::write(
+                    elem.as_mut_ptr(),
+                    Node(None, j as u32, DropTracker(&drop_counter)),
+                );
+            }
+            assert_eq!(drop_counter.get(), 0);
+        }
+    }
+    assert_eq!(drop_counter.get(), (0..LIMIT).sum::<usize>() as u32);
+}
+
 #[test]
 fn test_alloc_extend_with_drop_counter() {
     let drop_counter = Cell::new(0);
@@ -129,6 +153,43 @@ fn test_alloc_extend_with_drop_counter() {
     assert_eq!(drop_counter.get(), 200);
 }
 
+#[test]
+fn alloc_extend_exact_size_reserves_once() {
+    let arena = Arena::with_capacity(2);
+    arena.alloc(0);
+    let slice = arena.alloc_extend(vec![7; 1000]);
+    assert_eq!(slice.len(), 1000);
+    assert_eq!(arena.chunk_count(), 2);
+    assert_eq!(arena.capacity(), 2 + 1024);
+}
+
+#[test]
+fn alloc_extend_with_panicking_iterator() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(4);
+        arena.alloc(DropTracker(&drop_counter));
+        // Panic within the current chunk, and after spilling into a new one.
+        for &panic_at in &[2, 10] {
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                arena.alloc_extend((0..20).map(|i| {
+                    if i == panic_at {
+                        panic!("oops");
+                    }
+                    DropTracker(&drop_counter)
+                }));
+            }))
+            .unwrap_err();
+            assert_eq!(arena.len(), 1);
+        }
+        assert_eq!(drop_counter.get(), 12);
+        let slice = arena.alloc_extend((0..3).map(|_| DropTracker(&drop_counter)));
+        assert_eq!(slice.len(), 3);
+        assert_eq!(arena.len(), 4);
+    }
+    assert_eq!(drop_counter.get(), 16);
+}
+
 /// Test with bools.
 ///
 /// Bools, unlike integers, have invalid bit patterns. Therefore, ever having an uninitialized bool
@@ -148,8 +209,29 @@ fn test_alloc_uninitialized_bools() {
     }
 }
 
+/// Test with bools.
+///
+/// Bools, unlike integers, have invalid bit patterns. Therefore, ever having an uninitialized bool
+/// is insta-UB. Make sure miri doesn't find any such thing.
+#[test]
+fn test_alloc_uninit_slice_bools() {
+    const LEN: usize = 20;
+    unsafe {
+        let arena: Arena<bool> = Arena::with_capacity(2);
+        let slice = arena.alloc_uninit_slice(LEN);
+        for elem in slice.iter_mut() {
+            ptr::write(elem.as_mut_ptr(), true);
+        }
+        // Now it is fully initialized, we can safely transmute the slice.
+        let slice: &mut [bool] = mem::transmute(slice);
+        assert_eq!(&[true; LEN], slice);
+    }
+}
+
 /// Check nothing bad happens by panicking during initialization of borrowed slice.
 #[test]
+// The test predates the lint, which newer compilers deny.
+#[allow(unknown_lints, dangerous_implicit_autorefs)]
 fn alloc_uninitialized_with_panic() {
     struct Dropper(bool);
 
@@ -178,6 +260,36 @@ fn alloc_uninitialized_with_panic() {
     assert!(reached_first_init);
 }
 
+/// Check nothing bad happens by panicking during initialization of borrowed slice.
+#[test]
+fn alloc_uninit_slice_with_panic() {
+    struct Dropper(bool);
+
+    impl Drop for Dropper {
+        fn drop(&mut self) {
+            // Just make sure we touch the value, to make sure miri would bite if it was
+            // unitialized
+            if self.0 {
+                panic!();
+            }
+        }
+    }
+    let mut reached_first_init = false;
+    panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+        let arena: Arena<Dropper> = Arena::new();
+        arena.reserve_extend(2);
+        let uninitialized = arena.uninitialized_array();
+        assert!((&*uninitialized).len() >= 2);
+        ptr::write((&mut *uninitialized)[0].as_mut_ptr(), Dropper(false));
+        reached_first_init = true;
+        panic!("To drop the arena");
+        // If it didn't panic, we would continue by initializing the second one and confirming by
+        // .alloc_uninit_slice();
+    }))
+    .unwrap_err();
+    assert!(reached_first_init);
+}
+
 #[test]
 fn test_uninitialized_array() {
     let arena = Arena::with_capacity(2);
@@ -218,6 +330,7 @@ fn dont_trust_the_iterator_size() {
 
     let arena = Arena::with_capacity(2);
     arena.alloc(0);
+    #[allow(clippy::manual_repeat_n)]
     let slice = arena.alloc_extend(WrongSizeIter(repeat(1).take(1_000)));
     // Allocation of 1000 elements should have created a new chunk
     assert_eq!(arena.chunks.borrow().rest.len(), 1);
@@ -389,3 +502,2900 @@ fn covariant_sub_arena() {
     }
     assert_eq!(*x + *y, 7);
 }
+
+#[test]
+fn alloc_with_large_value() {
+    struct Big([u64; 8192]);
+
+    let arena = Arena::with_capacity(1);
+    let a = arena.alloc_with(|| Big([1; 8192]));
+    let b = arena.alloc_with(|| Big([2; 8192]));
+    assert!(a.0.iter().all(|&x| x == 1));
+    assert!(b.0.iter().all(|&x| x == 2));
+    assert_eq!(arena.len(), 2);
+
+    let sub_arena = SubArena::new(&arena);
+    let c = sub_arena.alloc_with(|| Big([3; 8192]));
+    assert!(c.0.iter().all(|&x| x == 3));
+}
+
+#[test]
+fn alloc_with_panic() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(2);
+        arena.alloc(DropTracker(&drop_counter));
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_with(|| -> DropTracker { panic!("oops") });
+        }))
+        .unwrap_err();
+        assert_eq!(arena.len(), 1);
+        arena.alloc_with(|| DropTracker(&drop_counter));
+        assert_eq!(arena.len(), 2);
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 2);
+}
+
+#[test]
+fn alloc_with_reentrant() {
+    let arena = Arena::with_capacity(2);
+    let mut inner = None;
+    let outer = arena.alloc_with(|| {
+        inner = Some(&*arena.alloc_extend(1..10));
+        0
+    });
+    assert_eq!(*outer, 0);
+    assert_eq!(inner.unwrap(), &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert_eq!(arena.into_vec(), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn try_alloc() {
+    let arena = Arena::with_capacity(1);
+    for i in 0..100 {
+        assert_eq!(*arena.try_alloc(i).unwrap(), i);
+    }
+    assert_eq!(arena.len(), 100);
+}
+
+#[test]
+fn try_reserve_failure_leaves_arena_usable() {
+    let arena = Arena::with_capacity(2);
+    arena.alloc(1_u64);
+    let err = arena.chunks.borrow_mut().try_reserve(usize::MAX / 4);
+    assert_eq!(err, Err(AllocError));
+    assert_eq!(arena.chunks.borrow().rest.len(), 0);
+    assert_eq!(*arena.try_alloc(2).unwrap(), 2);
+    assert_eq!(arena.into_vec(), vec![1, 2]);
+}
+
+#[test]
+fn try_alloc_extend() {
+    let arena = Arena::with_capacity(2);
+    arena.alloc(0);
+    let slice = arena
+        .try_alloc_extend((1..50).filter(|n| n % 7 != 0))
+        .unwrap();
+    assert_eq!(slice.len(), 42);
+    assert_eq!(slice[..7], [1, 2, 3, 4, 5, 6, 8]);
+    assert_eq!(arena.len(), 43);
+}
+
+#[test]
+fn try_alloc_extend_failure_drops_consumed_items() {
+    // Once started, claims to have far too many items left to fit.
+    struct Greedy<'a> {
+        started: bool,
+        drop_counter: &'a Cell<u32>,
+    }
+    impl<'a> Iterator for Greedy<'a> {
+        type Item = DropTracker<'a>;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.started = true;
+            Some(DropTracker(self.drop_counter))
+        }
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            if self.started {
+                (usize::MAX / 4, None)
+            } else {
+                (0, None)
+            }
+        }
+    }
+
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(4);
+        arena.alloc(DropTracker(&drop_counter));
+        let result = arena.try_alloc_extend(Greedy {
+            started: false,
+            drop_counter: &drop_counter,
+        });
+        assert!(result.is_err());
+        // Three items fit in the first chunk, and the fourth did not.
+        assert_eq!(drop_counter.get(), 4);
+        assert_eq!(arena.len(), 1);
+        arena.alloc(DropTracker(&drop_counter));
+        assert_eq!(arena.len(), 2);
+    }
+    assert_eq!(drop_counter.get(), 6);
+}
+
+#[test]
+fn alloc_slice_copy() {
+    let arena = Arena::with_capacity(2);
+    let src: Vec<u32> = (0..100).collect();
+    for i in 0..src.len() {
+        let copy = arena.alloc_slice_copy(&src[..i]);
+        assert_eq!(copy, &src[..i]);
+    }
+    assert_eq!(arena.len(), (0..100).sum::<usize>());
+}
+
+#[test]
+fn alloc_slice_clone_with_panic() {
+    struct PanicOnClone<'a>(bool, DropTracker<'a>);
+    impl<'a> Clone for PanicOnClone<'a> {
+        fn clone(&self) -> Self {
+            if self.0 {
+                panic!("oops");
+            }
+            PanicOnClone(false, DropTracker(self.1 .0))
+        }
+    }
+
+    let drop_counter = Cell::new(0);
+    let src = [
+        PanicOnClone(false, DropTracker(&drop_counter)),
+        PanicOnClone(false, DropTracker(&drop_counter)),
+        PanicOnClone(true, DropTracker(&drop_counter)),
+    ];
+    {
+        let arena = Arena::with_capacity(4);
+        let ok = arena.alloc_slice_clone(&src[..2]);
+        assert_eq!(ok.len(), 2);
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_slice_clone(&src);
+        }))
+        .unwrap_err();
+        // The two clones made before the panic were dropped right away.
+        assert_eq!(drop_counter.get(), 2);
+        assert_eq!(arena.len(), 2);
+        arena.alloc_slice_clone(&src[..1]);
+        assert_eq!(arena.len(), 3);
+    }
+    assert_eq!(drop_counter.get(), 5);
+}
+
+#[test]
+fn alloc_many() {
+    let arena = Arena::with_capacity(3);
+    arena.alloc(1_u8);
+    let bytes = arena.alloc_many(100, 0xAA);
+    assert!(bytes.iter().all(|&b| b == 0xAA));
+    assert_eq!(arena.alloc_many(0, 0), [0_u8; 0]);
+    assert_eq!(arena.len(), 101);
+
+    let arena = Arena::new();
+    let strings = arena.alloc_many(3, "ab".to_string());
+    strings[0].push('c');
+    assert_eq!(strings, ["abc", "ab", "ab"]);
+}
+
+#[test]
+fn alloc_slice_fill_with() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(2);
+        let nodes =
+            arena.alloc_slice_fill_with(10, |i| Node(None, i as u32, DropTracker(&drop_counter)));
+        assert!(nodes.iter().enumerate().all(|(i, node)| node.1 == i as u32));
+
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_slice_fill_with(10, |i| {
+                if i == 5 {
+                    panic!("oops");
+                }
+                Node(None, i as u32, DropTracker(&drop_counter))
+            });
+        }))
+        .unwrap_err();
+        assert_eq!(drop_counter.get(), 5);
+        assert_eq!(arena.len(), 10);
+    }
+    assert_eq!(drop_counter.get(), 15);
+}
+
+#[test]
+fn alloc_uninit() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(1);
+        arena.alloc(Node(None, 0, DropTracker(&drop_counter)));
+        let mut slot = arena.alloc_uninit();
+        let address = slot.as_mut_ptr();
+        slot.as_mut_uninit()
+            .write(Node(None, 1, DropTracker(&drop_counter)));
+        let node = unsafe { slot.assume_init() };
+        assert_eq!((node.1, node as *mut _), (1, address));
+        assert_eq!(arena.len(), 2);
+
+        // Uninitialized slots are never dropped, even when leaked.
+        drop(arena.alloc_uninit());
+        mem::forget(arena.alloc_uninit());
+        assert!(arena
+            .try_alloc_unlocked(Node(None, 2, DropTracker(&drop_counter)))
+            .is_err());
+        assert_eq!(drop_counter.get(), 1);
+        let mut arena = arena;
+        assert_eq!(arena.iter().count(), 2);
+        arena
+            .alloc_uninit()
+            .write(Node(None, 3, DropTracker(&drop_counter)));
+        assert_eq!(
+            arena.iter().map(|node| node.1).collect::<Vec<_>>(),
+            [0, 1, 3]
+        );
+    }
+    assert_eq!(drop_counter.get(), 4);
+}
+
+#[test]
+fn alloc_uninit_slice_tails_are_not_dropped() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(4);
+        for i in 0..10_u32 {
+            // Every other request leaves an uninitialized tail in the chunk.
+            let len = 3 + i as usize % 2;
+            unsafe {
+                let slice = arena.alloc_uninit_slice(len);
+                for elem in slice.iter_mut() {
+                    elem.write(Node(None, i, DropTracker(&drop_counter)));
+                }
+                let slice = Arena::assume_init_slice(slice);
+                assert!(slice.iter().all(|node| node.1 == i));
+            }
+        }
+        assert_eq!(arena.len(), 35);
+    }
+    assert_eq!(drop_counter.get(), 35);
+}
+
+#[test]
+fn alloc_str() {
+    let arena: Arena<u8> = Arena::with_capacity(4);
+    let hello = arena.alloc_str("Hello");
+    let world = arena.alloc_str(" world, this spills into a new chunk");
+    hello.make_ascii_uppercase();
+    assert_eq!(hello, "HELLO");
+    assert_eq!(world, " world, this spills into a new chunk");
+    assert_eq!(arena.alloc_str(""), "");
+}
+
+#[test]
+fn alloc_fmt() {
+    use std::fmt;
+
+    let arena: Arena<u8> = Arena::with_capacity(4);
+    let before = arena.alloc_str("ab");
+    let long = arena.alloc_fmt(format_args!("{}-{:?}-{}", "x".repeat(10), [1, 2], 3.5));
+    assert_eq!(long, "xxxxxxxxxx-[1, 2]-3.5");
+    assert_eq!(before, "ab");
+    assert_eq!(arena.alloc_fmt(format_args!("literal")), "literal");
+    assert_eq!(arena.len(), 2 + long.len() + "literal".len());
+
+    struct Fails;
+    impl fmt::Display for Fails {
+        fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+    let len = arena.len();
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        arena.alloc_fmt(format_args!("partial output {}", Fails));
+    }))
+    .unwrap_err();
+    assert_eq!(arena.len(), len);
+}
+
+#[test]
+fn alloc_bytes_aligned() {
+    let arena = Arena::with_capacity(100);
+    arena.alloc(1_u8);
+    for &align in &[1, 2, 32, 64, 4096] {
+        let buffer = arena.alloc_bytes_aligned(10, align);
+        assert_eq!(buffer.as_ptr() as usize % align, 0);
+        assert_eq!(buffer, [0; 10]);
+        buffer[9] = 1;
+    }
+
+    let bump = Bump::new();
+    bump.alloc(1_u8);
+    assert_eq!(bump.alloc_bytes_aligned(3, 64).as_ptr() as usize % 64, 0);
+}
+
+#[test]
+#[should_panic(expected = "alignment must be a power of two")]
+fn alloc_bytes_aligned_rejects_bad_alignment() {
+    Arena::new().alloc_bytes_aligned(1, 3);
+}
+
+#[test]
+fn alloc_array() {
+    let arena = Arena::with_capacity(3);
+    let a: &mut [usize; 4] = arena.alloc_array(|i| i);
+    let b: &mut [usize; 0] = arena.alloc_array(|i| i);
+    let c = arena.alloc_array::<2, _>(|i| 10 * i);
+    assert_eq!(*a, [0, 1, 2, 3]);
+    assert_eq!(*b, [0_usize; 0]);
+    assert_eq!(*c, [0, 10]);
+    assert_eq!(arena.into_vec(), vec![0, 1, 2, 3, 0, 10]);
+}
+
+#[test]
+fn from_vec_adopts_elements_in_place() {
+    let mut vec = Vec::with_capacity(4);
+    vec.extend_from_slice(&[1, 2]);
+    let ptr = vec.as_ptr();
+    let arena = Arena::from_vec(vec);
+    let three = arena.alloc(3);
+    assert_eq!(three as *const i32, unsafe { ptr.add(2) });
+    arena.alloc_extend(4..10);
+    assert_eq!(arena.into_vec(), (1..10).collect::<Vec<_>>());
+
+    // Allocation still works when the vector has no spare capacity.
+    let arena = Arena::from_vec(Vec::new());
+    arena.alloc(1);
+    assert_eq!(arena.into_vec(), [1]);
+}
+
+#[test]
+fn collect_and_extend() {
+    let drop_counter = Cell::new(0);
+    {
+        let mut arena: Arena<_> = (0..3).map(|_| DropTracker(&drop_counter)).collect();
+        assert_eq!(arena.len(), 3);
+        arena.extend((0..3).map(|_| DropTracker(&drop_counter)));
+        let mut shared = &arena;
+        shared.extend((0..3).map(|_| DropTracker(&drop_counter)));
+        assert_eq!(arena.len(), 9);
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 9);
+}
+
+#[test]
+fn debug_output() {
+    #[derive(Debug, Default)]
+    struct Holder {
+        arena: Arena<u64>,
+    }
+
+    let holder = Holder::default();
+    assert_eq!(
+        format!("{:?}", holder),
+        "Holder { arena: Arena { len: 0, chunk_count: 0, used_bytes: 0 } }"
+    );
+
+    let arena = &holder.arena;
+    arena.alloc(1);
+    {
+        let sub_arena = SubArena::new(arena);
+        sub_arena.alloc(2);
+        assert_eq!(
+            format!("{:?}", sub_arena),
+            "SubArena { len: 1, chunk_count: 0, used_bytes: 8 }"
+        );
+        sub_arena.alloc_extend(0..1000);
+        assert_eq!(
+            format!("{:?}", sub_arena),
+            "SubArena { len: 1001, chunk_count: 1, used_bytes: 8008 }"
+        );
+        assert_eq!(
+            format!("{:?}", arena),
+            "Arena { len: 1, chunk_count: 1, used_bytes: 8 }"
+        );
+    }
+    arena.alloc_slice_fill_with(1, |_| {
+        assert_eq!(format!("{:?}", arena), "Arena { .. }");
+        3
+    });
+    // The chunk added by the sub-arena is kept for reuse.
+    assert_eq!(
+        format!("{:?}", arena),
+        "Arena { len: 2, chunk_count: 2, used_bytes: 16 }"
+    );
+}
+
+#[test]
+fn new_allocates_lazily() {
+    const fn empty() -> Arena<u64> {
+        Arena::new()
+    }
+
+    let arena = empty();
+    assert_eq!(arena.allocated_bytes(), 0);
+    assert_eq!(arena.chunk_count(), 0);
+    assert_eq!(arena.stats().chunk_count, 0);
+    assert!(arena.chunk_ranges().is_empty());
+    arena.alloc(1);
+    assert_eq!(arena.capacity(), 1024 / 8);
+    assert_eq!(arena.chunk_count(), 1);
+
+    let arena = Arena::new();
+    assert_eq!(arena.alloc_extend(0..2000).len(), 2000);
+    assert_eq!(arena.chunk_count(), 1);
+
+    let arena = Arena::new();
+    assert_eq!(*arena.try_alloc(1_u8).unwrap(), 1);
+    assert_eq!(arena.chunk_count(), 1);
+    assert_eq!(arena.alloc_str("after").len(), 5);
+}
+
+#[test]
+fn max_chunk_size() {
+    let arena = Arena::<u32>::builder()
+        .initial_capacity(2)
+        .max_chunk_size(8)
+        .build();
+    for i in 0..30 {
+        arena.alloc(i);
+    }
+    let capacities: Vec<usize> = {
+        let chunks = arena.chunks.borrow();
+        chunks
+            .rest
+            .iter()
+            .chain(Some(&chunks.current))
+            .map(Vec::capacity)
+            .collect()
+    };
+    assert_eq!(capacities, [2, 4, 8, 8, 8]);
+
+    // Larger allocations still fit.
+    arena.alloc(0);
+    assert_eq!(arena.alloc_extend(0..20).len(), 20);
+
+    // Sub-arenas follow the same policy.
+    let sub_arena = SubArena::new(&arena);
+    for i in 0..20 {
+        sub_arena.alloc(i);
+    }
+    assert!(sub_arena.chunks.borrow().current.capacity() <= 8);
+}
+
+#[test]
+fn max_chunk_bytes() {
+    let arena = Arena::<u64>::builder()
+        .initial_capacity(1)
+        .max_chunk_bytes(100)
+        .build();
+    for i in 0..100 {
+        arena.alloc(i);
+    }
+    assert!(arena
+        .chunks
+        .borrow()
+        .rest
+        .iter()
+        .all(|chunk| chunk.capacity() <= 12));
+
+    // Huge iterators of unknown length still grow their chunk geometrically.
+    let len = 100_000;
+    let slice = arena.alloc_extend((0..len).filter(|_| true));
+    assert_eq!(slice.len(), len as usize);
+    assert!(arena.chunks.borrow().current.capacity() < 2 * len as usize);
+    assert!(arena.chunk_count() < 30);
+}
+
+#[test]
+fn growth_strategies() {
+    fn capacities<T>(arena: &Arena<T>) -> Vec<usize> {
+        let chunks = arena.chunks.borrow();
+        chunks
+            .rest
+            .iter()
+            .chain(Some(&chunks.current))
+            .map(Vec::capacity)
+            .collect()
+    }
+
+    fn assert_send_and_unwind_safe<T: Send + panic::UnwindSafe>(_: &T) {}
+
+    let arena = Arena::builder()
+        .initial_capacity(1)
+        .growth_strategy(Fibonacci::new())
+        .build();
+    assert_send_and_unwind_safe(&arena);
+    for i in 0..20 {
+        arena.alloc(i);
+    }
+    assert_eq!(capacities(&arena), [1, 2, 3, 5, 8, 13]);
+
+    // The ceiling applies on top of the strategy, and sub-arenas share it.
+    let arena = Arena::builder()
+        .initial_capacity(2)
+        .max_chunk_size(10)
+        .growth_strategy(|capacity: usize| capacity * 3)
+        .build();
+    {
+        let sub_arena = SubArena::new(&arena);
+        for i in 0..8 {
+            sub_arena.alloc(i);
+        }
+        assert_eq!(capacities(&sub_arena), [2, 6]);
+    }
+    for i in 0..20 {
+        arena.alloc(i);
+    }
+    assert_eq!(capacities(&arena), [2, 6, 10, 10]);
+}
+
+#[cfg(feature = "allocator_api")]
+#[test]
+fn arena_as_allocator() {
+    use std::alloc::{AllocError, Allocator, Layout};
+
+    let arena = Arena::<MaybeUninit<u8>>::with_capacity(3);
+    arena.alloc(MaybeUninit::new(1));
+    let mut words: Vec<u64, &Arena<MaybeUninit<u8>>> = Vec::new_in(&arena);
+    for i in 0..100 {
+        words.push(i);
+        assert_eq!(words.as_ptr() as usize % mem::align_of::<u64>(), 0);
+    }
+    let empty: Vec<u128, &Arena<MaybeUninit<u8>>> = Vec::with_capacity_in(0, &arena);
+    assert_eq!(empty.as_ptr() as usize % mem::align_of::<u128>(), 0);
+    let boxed = Box::new_in([7_u16; 3], &arena);
+    assert_eq!(*boxed, [7; 3]);
+    assert_eq!(words.iter().sum::<u64>(), 4950);
+
+    // A borrowed arena fails to allocate instead of panicking.
+    let layout = Layout::new::<u64>();
+    let sub_arena = SubArena::new(&arena);
+    assert_eq!(arena.allocate(layout), Err(AllocError));
+    assert!(sub_arena.allocate(layout).is_ok());
+    drop(sub_arena);
+    let builder = arena.slice_builder();
+    assert_eq!(arena.allocate(layout), Err(AllocError));
+    drop(builder);
+    assert!(arena.allocate(layout).is_ok());
+}
+
+#[cfg(feature = "allocator_api")]
+#[test]
+fn sub_arena_as_allocator() {
+    let arena = Arena::<MaybeUninit<u8>>::with_capacity(16);
+    arena.alloc(MaybeUninit::new(1));
+    let allocated = {
+        let sub_arena = SubArena::new(&arena);
+        let mut words: Vec<u64, &SubArena<MaybeUninit<u8>>> = Vec::new_in(&sub_arena);
+        words.extend(0..100);
+        assert_eq!(words.as_ptr() as usize % mem::align_of::<u64>(), 0);
+        let nested = SubArena::new(&*sub_arena);
+        let boxed = Box::new_in(7_u32, &nested);
+        assert_eq!((*boxed, words.iter().sum::<u64>()), (7, 4950));
+        drop(boxed);
+        drop(nested);
+        sub_arena.stats().allocated_bytes
+    };
+    assert!(allocated > 800);
+    assert_eq!(arena.len(), 1);
+    // The chunks of the sub-arena are kept for reuse.
+    assert!(arena.allocated_bytes() > 800);
+}
+
+#[cfg(feature = "allocator_api")]
+#[test]
+fn bump_as_allocator() {
+    let bump = Bump::new();
+    let flag = bump.alloc(true);
+    let mut words: Vec<u64, &Bump> = Vec::new_in(&bump);
+    words.extend(0..100);
+    assert_eq!(words.as_ptr() as usize % mem::align_of::<u64>(), 0);
+    let boxed = Box::new_in(7_u8, &bump);
+    assert_eq!((*flag, *boxed, words.iter().sum::<u64>()), (true, 7, 4950));
+    assert!(bump.used_bytes() >= 800);
+}
+
+#[cfg(feature = "allocator_api")]
+#[test]
+fn chunks_from_allocator() {
+    use std::alloc::{AllocError, Allocator, Global, Layout};
+    use std::ptr::NonNull;
+
+    // Keeps count of the bytes allocated and not freed yet.
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a Cell<usize>);
+
+    unsafe impl<'a> Allocator for Counting<'a> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + layout.size());
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.set(self.0.get() - layout.size());
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let live = Cell::new(0);
+    {
+        let arena = Arena::new_in(Counting(&live));
+        assert_eq!(live.get(), 0);
+        for i in 0..500_u64 {
+            arena.alloc(i);
+        }
+        arena.try_alloc_extend(500..1000).unwrap();
+        assert!(arena.chunks.borrow().rest.len() > 1);
+        assert_eq!(live.get(), arena.allocated_bytes());
+
+        let values = arena.into_vec();
+        assert_eq!(values, (0..1000).collect::<Vec<_>>());
+        assert_eq!(live.get(), 8000);
+    }
+    assert_eq!(live.get(), 0);
+
+    let mut arena = Arena::with_capacity_in(10, Counting(&live));
+    arena.alloc(1_u32);
+    assert_eq!(live.get(), 40);
+
+    // The methods that don't need the global allocator work too.
+    arena.alloc_with(|| 2);
+    arena.alloc_slice_copy(&[3, 4]);
+    arena.alloc_uninit().write(5);
+    let marker = arena.marker();
+    arena.alloc(6);
+    arena.rollback_to(marker);
+    assert_eq!(arena.iter_mut().map(|x| *x).sum::<u32>(), 15);
+    assert_eq!(arena.stats().len, 5);
+    arena.clear();
+    assert!(arena.is_empty());
+    assert_eq!(live.get(), 40);
+}
+
+#[test]
+fn bump_mixes_types_with_alignment() {
+    #[repr(align(64))]
+    struct Aligned(u8);
+
+    let bump = Bump::with_capacity(8);
+    let byte = bump.alloc(1_u8);
+    let aligned = bump.alloc(Aligned(2));
+    assert_eq!(aligned as *mut Aligned as usize % 64, 0);
+    let words = bump.alloc_slice_copy(&[3_u64; 100]);
+    assert_eq!(words.as_ptr() as usize % mem::align_of::<u64>(), 0);
+    let unit = bump.alloc(());
+    let name = bump.alloc_str("four");
+    assert_eq!(
+        (*byte, aligned.0, words[99], *unit, &*name),
+        (1, 2, 3, (), "four")
+    );
+    assert!(bump.used_bytes() >= 1 + 64 + 800 + 4);
+
+    let layout = core::alloc::Layout::from_size_align(10, 32).unwrap();
+    let block = bump.alloc_layout(layout);
+    assert_eq!(block.as_ptr() as usize % 32, 0);
+    let empty = core::alloc::Layout::from_size_align(0, 16).unwrap();
+    assert_eq!(bump.alloc_layout(empty).as_ptr() as usize % 16, 0);
+
+    // Values are not dropped.
+    let drop_counter = Cell::new(0);
+    Bump::new().alloc(DropTracker(&drop_counter));
+    assert_eq!(drop_counter.get(), 0);
+}
+
+#[test]
+fn dropless_arena_across_chunks() {
+    let arena = DroplessArena::with_capacity(3);
+    let first = arena.alloc(0_u32);
+    let mut refs = Vec::new();
+    for i in 1..100 {
+        refs.push(arena.alloc(i));
+    }
+    let big = arena.alloc_slice_copy(&[7; 1000]);
+    *first = 100;
+    assert_eq!(*first, 100);
+    assert!(refs.iter().enumerate().all(|(i, r)| **r == i as u32 + 1));
+    assert_eq!(big.len(), 1000);
+    assert!(arena.allocated_bytes() >= 1100 * 4);
+
+    let units = DroplessArena::new();
+    assert_eq!(units.alloc_slice_copy(&[(); 10]).len(), 10);
+    assert_eq!(units.allocated_bytes(), 0);
+}
+
+#[cfg(all(feature = "mmap", unix))]
+#[test]
+fn dropless_arena_with_mapped_chunks() {
+    let arena = DroplessArena::new_mapped();
+    let first = arena.alloc(1_u64);
+    let big = arena.alloc_slice_copy(&[2_u64; 100_000]);
+    assert_eq!(*first + big[99_999], 3);
+    assert_eq!(arena.allocated_bytes() % crate::mmap::page_size(), 0);
+    assert!(arena.allocated_bytes() >= 100_001 * 8);
+}
+
+#[cfg(all(feature = "mmap", target_os = "linux"))]
+#[test]
+fn released_chunks_are_reusable() {
+    let mut arena = Arena::<u64>::builder()
+        .initial_capacity(1 << 18)
+        .huge_pages(true)
+        .release_cleared_chunks(true)
+        .build();
+    for round in 0..3 {
+        let values = arena.alloc_extend(0..1 << 20);
+        assert!(values.iter().enumerate().all(|(i, &v)| v == i as u64));
+        assert_eq!(arena.len(), 1 << 20);
+        if round == 0 {
+            arena.clear();
+        } else {
+            arena.reset();
+        }
+    }
+}
+
+#[test]
+fn fixed_arena_scopes() {
+    let drop_counter = Cell::new(0);
+    let mut buffer: [MaybeUninit<DropTracker>; 3] = [
+        MaybeUninit::uninit(),
+        MaybeUninit::uninit(),
+        MaybeUninit::uninit(),
+    ];
+    {
+        let arena = FixedArena::new(&mut buffer);
+        arena.try_alloc(DropTracker(&drop_counter)).ok().unwrap();
+        {
+            let sub_arena = arena.sub_arena();
+            sub_arena
+                .try_alloc(DropTracker(&drop_counter))
+                .ok()
+                .unwrap();
+            {
+                let inner = sub_arena.sub_arena();
+                inner.try_alloc(DropTracker(&drop_counter)).ok().unwrap();
+                assert!(inner.try_alloc(DropTracker(&drop_counter)).is_err());
+                assert_eq!(drop_counter.get(), 1);
+            }
+            assert_eq!(drop_counter.get(), 2);
+            assert_eq!(sub_arena.remaining(), 1);
+        }
+        assert_eq!(drop_counter.get(), 3);
+        assert_eq!(arena.len(), 1);
+        assert_eq!(arena.remaining(), 2);
+    }
+    assert_eq!(drop_counter.get(), 4);
+}
+
+#[test]
+fn small_arena_spills_to_heap() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = SmallArena::<_, 3>::new();
+        let inline: Vec<_> = (0..3)
+            .map(|i| arena.alloc((i, DropTracker(&drop_counter))))
+            .collect();
+        assert!(!arena.spilled());
+        let spilled: Vec<_> = (3..10)
+            .map(|i| arena.alloc((i, DropTracker(&drop_counter))))
+            .collect();
+        assert!(arena.spilled());
+        assert_eq!(arena.len(), 10);
+        assert!(inline
+            .iter()
+            .chain(&spilled)
+            .enumerate()
+            .all(|(i, v)| v.0 == i));
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 10);
+}
+
+#[test]
+fn mutex_arena_across_threads() {
+    let arena = std::sync::Arc::new(MutexArena::new());
+    let workers: Vec<_> = (0..8)
+        .map(|t| {
+            let arena = std::sync::Arc::clone(&arena);
+            std::thread::spawn(move || {
+                for i in 0..1000 {
+                    let value = arena.alloc(t * 1000 + i);
+                    assert_eq!(*value, t * 1000 + i);
+                }
+                let slice = arena.alloc_extend((0..3).map(|_| *arena.alloc(t)));
+                assert_eq!(slice, [t; 3]);
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    let arena = std::sync::Arc::try_unwrap(arena).ok().unwrap();
+    let mut values = arena.into_inner().into_vec();
+    assert_eq!(values.len(), 8 * 1006);
+    values.sort_unstable();
+    values.dedup();
+    assert_eq!(values.len(), 8000);
+}
+
+#[test]
+fn concurrent_arena_across_threads() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counted<'a>(usize, &'a AtomicUsize);
+    impl<'a> Drop for Counted<'a> {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let arena = std::sync::Arc::new(ConcurrentArena::new());
+    let workers: Vec<_> = (0..8)
+        .map(|t| {
+            let arena = std::sync::Arc::clone(&arena);
+            std::thread::spawn(move || {
+                let mut sum = 0;
+                for i in 0..1000 {
+                    let value = arena.alloc(Counted(t * 1000 + i, &DROPS));
+                    assert_eq!(value.0, t * 1000 + i);
+                    let slice = arena.alloc_extend((0..i % 5).map(|_| Counted(t, &DROPS)));
+                    assert!(slice.iter().all(|v| v.0 == t));
+                    sum += value.0 + slice.len();
+                }
+                sum
+            })
+        })
+        .collect();
+    let sum: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+    assert_eq!(sum, (0..8000).sum::<usize>() + 8 * 2000);
+    assert_eq!(arena.len(), 8 * 3000);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+    drop(arena);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 8 * 3000);
+}
+
+#[test]
+fn herd_values_outlive_members() {
+    let drop_counter = Cell::new(0);
+    {
+        let herd = Herd::new();
+        let first = herd.get();
+        let x = first.alloc(DropTracker(&drop_counter));
+        let second = herd.get();
+        let ys = second.alloc_extend((0..10).map(|_| DropTracker(&drop_counter)));
+        drop(first);
+        drop(second);
+        // Reuses the arena of a dropped member.
+        let z = herd.get().alloc(DropTracker(&drop_counter));
+        assert_eq!(format!("{:?}", herd), "Herd { idle_arenas: 2 }");
+        assert_eq!((x.0.get(), ys.len(), z.0.get()), (0, 10, 0));
+    }
+    assert_eq!(drop_counter.get(), 12);
+
+    let herd = std::sync::Arc::new(Herd::new());
+    let workers: Vec<_> = (0..4)
+        .map(|t| {
+            let herd = std::sync::Arc::clone(&herd);
+            std::thread::spawn(move || {
+                let member = herd.get();
+                let values: Vec<&mut usize> = (0..1000).map(|i| member.alloc(t + i)).collect();
+                assert!(values.iter().enumerate().all(|(i, v)| **v == t + i));
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn arena_pool_reuses_chunks() {
+    let drop_counter = Cell::new(0);
+    let pool = ArenaPool::new();
+    let capacity = {
+        let arena = pool.get();
+        arena.alloc_extend((0..100).map(|_| DropTracker(&drop_counter)));
+        arena.capacity()
+    };
+    assert_eq!(drop_counter.get(), 100);
+    {
+        let first = pool.get();
+        let second = pool.get();
+        assert_eq!(first.capacity(), capacity);
+        assert_eq!(second.capacity(), 0);
+        assert_eq!(pool.idle_count(), 0);
+    }
+    assert_eq!(pool.idle_count(), 2);
+}
+
+#[test]
+fn chunk_recycler_shares_chunks() {
+    let drop_counter = Cell::new(0);
+    let recycler = std::sync::Arc::new(ChunkRecycler::new());
+    let arena = Arena::with_capacity(200);
+    arena.alloc_extend((0..25).map(|_| DropTracker(&drop_counter)));
+    let bytes = arena.allocated_bytes();
+    recycler.recycle(arena);
+    assert_eq!(drop_counter.get(), 25);
+    assert_eq!(recycler.cached_bytes(), bytes);
+
+    // Same size and alignment as `DropTracker`.
+    let arena = Arena::<&u8>::builder()
+        .chunk_recycler(recycler.clone())
+        .build();
+    let byte = 0;
+    for _ in 0..25 {
+        arena.alloc(&byte);
+    }
+    assert_eq!(arena.allocated_bytes(), bytes);
+    assert_eq!(recycler.cached_bytes(), 0);
+
+    // Nothing to reuse for a different alignment.
+    recycler.recycle(arena);
+    let arena = Arena::<u8>::builder()
+        .chunk_recycler(recycler.clone())
+        .build();
+    arena.alloc(1);
+    assert_eq!(recycler.cached_bytes(), bytes);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_iter_mut_visits_every_chunk() {
+    use rayon::prelude::*;
+
+    let mut arena = Arena::with_capacity(3);
+    for i in 0..1000 {
+        arena.alloc(i);
+    }
+    assert!(arena.chunk_count() > 1);
+    let iter = arena.par_iter_mut();
+    assert_eq!(iter.opt_len(), Some(1000));
+    iter.for_each(|x| *x += 1);
+    (&mut arena).into_par_iter().for_each(|x| *x *= 2);
+    assert!(arena.iter().enumerate().all(|(i, &x)| x == (i + 1) * 2));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_alloc_extend_fills_in_parallel() {
+    use rayon::prelude::*;
+
+    let arena = Arena::with_capacity(10);
+    let first = arena.alloc(usize::MAX);
+    let values = arena.par_alloc_extend((0..100_000).into_par_iter().map(|i| i * 3));
+    assert!(values.iter().enumerate().all(|(i, &v)| v == i * 3));
+    assert_eq!(*first, usize::MAX);
+    assert_eq!(arena.len(), 100_001);
+
+    let drop_counter = std::sync::atomic::AtomicUsize::new(0);
+    struct Counted<'a>(&'a std::sync::atomic::AtomicUsize);
+    impl<'a> Drop for Counted<'a> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+    let arena = Arena::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        arena.par_alloc_extend((0..1000).into_par_iter().map(|i| {
+            if i == 500 {
+                panic!("oops");
+            }
+            Counted(&drop_counter)
+        }));
+    }));
+    assert!(result.is_err());
+    assert!(arena.is_empty());
+    let produced = drop_counter.load(std::sync::atomic::Ordering::Relaxed);
+    assert!(produced < 1000);
+    drop(arena);
+    assert_eq!(
+        drop_counter.load(std::sync::atomic::Ordering::Relaxed),
+        produced
+    );
+}
+
+#[test]
+fn share_across_threads() {
+    // Freed at the end, once the threads are done with it.
+    let raw = Box::into_raw(Box::new(Arena::with_capacity(2)));
+    let arena: &'static mut Arena<Vec<usize>> = unsafe { &mut *raw };
+    for i in 0..100 {
+        arena.alloc(vec![i; i]);
+    }
+    let shared = arena.share();
+    assert_eq!(shared.iter().len(), 100);
+    let workers: Vec<_> = (0..4)
+        .map(|t| {
+            std::thread::spawn(move || {
+                assert_eq!(shared.get(t * 25).map(Vec::len), Some(t * 25));
+                shared
+                    .iter()
+                    .skip(t * 25)
+                    .take(25)
+                    .map(Vec::len)
+                    .sum::<usize>()
+            })
+        })
+        .collect();
+    let total: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+    assert_eq!(total, (0..100).sum::<usize>());
+    assert_eq!(shared.get(100), None);
+    drop(unsafe { Box::from_raw(raw) });
+}
+
+#[test]
+fn freeze_keeps_values_in_place() {
+    let arena = Arena::with_capacity(4);
+    let addresses: Vec<*const u64> = (0..50).map(|i| arena.alloc(i) as *const u64).collect();
+    let frozen = std::sync::Arc::new(arena.freeze());
+    assert_eq!(frozen.len(), 50);
+    for (i, &address) in addresses.iter().enumerate() {
+        assert!(ptr::eq(frozen.get(i).unwrap(), address));
+    }
+    let reader = std::sync::Arc::clone(&frozen);
+    let sum = std::thread::spawn(move || reader.iter().sum::<u64>());
+    assert_eq!(sum.join().unwrap(), (0..50).sum::<u64>());
+    let frozen = std::sync::Arc::try_unwrap(frozen).ok().unwrap();
+    assert_eq!(frozen.into_vec(), (0..50).collect::<Vec<_>>());
+}
+
+#[test]
+fn gen_arena_detects_stale_handles() {
+    let drop_counter = Cell::new(0);
+    {
+        let mut arena = GenArena::with_capacity(2);
+        let handles: Vec<_> = (0..10)
+            .map(|_| arena.alloc(DropTracker(&drop_counter)))
+            .collect();
+        for &handle in handles.iter().step_by(2) {
+            assert!(arena.remove(handle).is_some());
+        }
+        assert_eq!(drop_counter.get(), 5);
+        assert_eq!(arena.len(), 5);
+
+        let reused: Vec<_> = (0..5)
+            .map(|_| arena.alloc(DropTracker(&drop_counter)))
+            .collect();
+        let fresh = arena.alloc(DropTracker(&drop_counter));
+        assert_eq!(fresh.index(), 10);
+        for (old, new) in handles.iter().step_by(2).zip(&reused) {
+            assert!(!arena.contains(*old));
+            assert!(arena.contains(*new));
+            assert_eq!(new.generation(), 1);
+        }
+        assert!(handles
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .all(|&h| arena.contains(h)));
+        assert_eq!(arena.len(), 11);
+    }
+    assert_eq!(drop_counter.get(), 16);
+}
+
+#[test]
+fn id_arena_links_nodes_by_id() {
+    struct Node {
+        value: u32,
+        next: Option<Id<Node>>,
+    }
+
+    let mut arena = IdArena::with_capacity(3);
+    let mut next = None;
+    for value in 0..100 {
+        next = Some(arena.alloc(Node { value, next }));
+    }
+    let head = next.unwrap();
+    assert_eq!(head.index(), 99);
+
+    let first = &arena[head];
+    let mut sum = 0;
+    let mut cursor = Some(head);
+    while let Some(id) = cursor {
+        // Shared lookups can overlap with allocation.
+        arena.alloc(Node {
+            value: 0,
+            next: None,
+        });
+        sum += arena[id].value;
+        cursor = arena[id].next;
+    }
+    assert_eq!(sum, (0..100).sum::<u32>());
+    assert_eq!(first.value, 99);
+
+    arena[Id::from_raw(0)].value = 1000;
+    assert_eq!(arena.get(Id::from_raw(0)).map(|n| n.value), Some(1000));
+    assert!(arena.get(Id::from_raw(200)).is_none());
+}
+
+#[test]
+fn pool_reuses_freed_slots() {
+    let drop_counter = Cell::new(0);
+    let pool = Pool::with_capacity(4);
+    let mut live: Vec<_> = (0..8)
+        .map(|_| pool.alloc(DropTracker(&drop_counter)))
+        .collect();
+    let addresses: Vec<*const DropTracker> = live.iter().map(|b| &**b as *const _).collect();
+
+    // Free every other value, some by drop and some explicitly.
+    for (i, value) in mem::take(&mut live).into_iter().enumerate() {
+        match i % 4 {
+            0 => drop(value),
+            2 => mem::forget(pool.dealloc(value)),
+            _ => live.push(value),
+        }
+    }
+    assert_eq!(drop_counter.get(), 2);
+    assert_eq!(pool.len(), 4);
+
+    for _ in 0..4 {
+        let value = pool.alloc(DropTracker(&drop_counter));
+        assert!(addresses.contains(&(&*value as *const _)));
+        live.push(value);
+    }
+    assert_eq!(pool.capacity_used(), 8);
+    drop(live);
+    assert_eq!(drop_counter.get(), 10);
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn arena_box_drops_eagerly() {
+    let drop_counter = Cell::new(0);
+    let bump = Bump::new();
+    let boxes: Vec<_> = (0..10)
+        .map(|_| bump.alloc_box(DropTracker(&drop_counter)))
+        .collect();
+    let mut boxes = boxes.into_iter();
+    drop(boxes.next());
+    assert_eq!(drop_counter.get(), 1);
+    ArenaBox::leak(boxes.next().unwrap());
+    let taken = ArenaBox::into_inner(boxes.next().unwrap());
+    assert_eq!(drop_counter.get(), 1);
+    drop(taken);
+    drop(boxes);
+    // The leaked value is never dropped.
+    assert_eq!(drop_counter.get(), 9);
+}
+
+#[test]
+fn arena_rc_drops_with_last_pointer() {
+    let drop_counter = Cell::new(0);
+    let bump = Bump::new();
+    let a = bump.alloc_rc(DropTracker(&drop_counter));
+    let b = a.clone();
+    let c = match ArenaRc::try_unwrap(b) {
+        Ok(_) => panic!("the value is still shared"),
+        Err(c) => c,
+    };
+    assert_eq!(ArenaRc::strong_count(&a), 2);
+    drop(a);
+    assert_eq!(drop_counter.get(), 0);
+    drop(ArenaRc::try_unwrap(c).ok().unwrap());
+    assert_eq!(drop_counter.get(), 1);
+
+    let d = bump.alloc_rc(DropTracker(&drop_counter));
+    let e = d.clone();
+    drop((d, e));
+    assert_eq!(drop_counter.get(), 2);
+}
+
+#[test]
+fn dyn_arena_drops_trait_objects() {
+    trait Named {
+        fn name(&self) -> &str;
+    }
+    impl<'a> Named for DropTracker<'a> {
+        fn name(&self) -> &str {
+            "tracker"
+        }
+    }
+    impl Named for u8 {
+        fn name(&self) -> &str {
+            "byte"
+        }
+    }
+
+    let drop_counter = Cell::new(0);
+    {
+        let arena = DynArena::new();
+        let mut names = Vec::new();
+        for i in 0..100 {
+            let value: &mut dyn Named = if i % 2 == 0 {
+                arena.alloc(DropTracker(&drop_counter))
+            } else {
+                arena.alloc(i as u8)
+            };
+            names.push(value.name().len());
+        }
+        assert_eq!(names.iter().sum::<usize>(), 50 * 7 + 50 * 4);
+        assert_eq!(arena.drop_count(), 50);
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 50);
+}
+
+#[test]
+fn bump_header_slice_layout() {
+    let bump = Bump::new();
+    bump.alloc(1_u8);
+    let node = bump.alloc_header_slice(7_u8, 3, |i| i as u64);
+    assert_eq!(node.header, 7);
+    assert_eq!(node.slice, [0, 1, 2]);
+    assert_eq!(mem::size_of_val(node), 32);
+    assert_eq!(
+        &node.header as *const u8 as usize % mem::align_of::<u64>(),
+        0
+    );
+    node.slice[2] = 5;
+
+    let empty: &HeaderSlice<u64, u8> = bump.alloc_header_slice(9, 0, |_| 0);
+    assert_eq!((empty.header, empty.slice.len()), (9, 0));
+    assert_eq!(mem::size_of_val(empty), 8);
+    assert_eq!(node.slice, [0, 1, 5]);
+
+    let strings = bump.alloc_slice_clone(&[String::from("a"), String::from("b")]);
+    assert_eq!(strings.concat(), "ab");
+    // The bump never drops them.
+    unsafe { ptr::drop_in_place(strings) };
+}
+
+#[test]
+fn alloc_cyclic_builds_and_survives_panics() {
+    struct Node<'a, 'c> {
+        parent: Option<Cyclic<'a, Node<'a, 'c>>>,
+        _tracker: DropTracker<'c>,
+    }
+
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(2);
+        let mut child = None;
+        let root = arena.alloc_cyclic(|root| {
+            // Fills the first chunk, so the root's slot is in `rest` by now.
+            for _ in 0..10 {
+                child = Some(&*arena.alloc(Node {
+                    parent: Some(root),
+                    _tracker: DropTracker(&drop_counter),
+                }));
+            }
+            assert!(root.get().is_none());
+            Node {
+                parent: None,
+                _tracker: DropTracker(&drop_counter),
+            }
+        });
+        let parent = child.unwrap().parent.unwrap().get().unwrap();
+        assert!(ptr::eq(parent, root));
+        assert_eq!(arena.len(), 11);
+    }
+    assert_eq!(drop_counter.get(), 11);
+
+    {
+        let arena = Arena::new();
+        arena.alloc(Node {
+            parent: None,
+            _tracker: DropTracker(&drop_counter),
+        });
+        let mut orphan = None;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_cyclic(|this| {
+                orphan = Some(this);
+                arena.alloc(Node {
+                    parent: Some(this),
+                    _tracker: DropTracker(&drop_counter),
+                });
+                panic!("construction failed");
+            });
+        }));
+        assert!(result.is_err());
+        assert!(orphan.unwrap().get().is_none());
+        // The value before the failed slot is kept, the child is leaked.
+        assert_eq!(arena.len(), 1);
+        arena.alloc(Node {
+            parent: None,
+            _tracker: DropTracker(&drop_counter),
+        });
+        assert_eq!(arena.len(), 2);
+    }
+    assert_eq!(drop_counter.get(), 13);
+
+    {
+        let arena = Arena::with_capacity(4);
+        let mut orphan = None;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            arena.alloc_cyclic(|this| {
+                orphan = Some(this);
+                panic!("construction failed");
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(arena.len(), 0);
+        // Nothing came after the failed slot, so it is reused.
+        let node = arena.alloc(Node {
+            parent: None,
+            _tracker: DropTracker(&drop_counter),
+        });
+        assert!(ptr::eq(node, arena.chunks.borrow().current.as_ptr()));
+        assert!(orphan.unwrap().get().is_none());
+        let root = arena.alloc_cyclic(|root| Node {
+            parent: Some(root),
+            _tracker: DropTracker(&drop_counter),
+        });
+        assert!(ptr::eq(root.parent.unwrap().get().unwrap(), root));
+    }
+    assert_eq!(drop_counter.get(), 15);
+
+    // The chunk retired for the leaked child is reused after `clear`.
+    let mut arena = Arena::with_capacity(4);
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        arena.alloc_cyclic(|_| {
+            arena.alloc(1);
+            panic!("construction failed")
+        });
+    }))
+    .unwrap_err();
+    arena.clear();
+    arena.reserve(100);
+    let bytes = arena.allocated_bytes();
+    for i in 0..100 {
+        arena.alloc(i);
+    }
+    assert_eq!(arena.allocated_bytes(), bytes);
+}
+
+#[test]
+fn any_arena_keeps_one_arena_per_type() {
+    use std::rc::Rc;
+
+    let tracker = Rc::new(());
+    {
+        let arena = AnyArena::new();
+        let mut refs = Vec::new();
+        for i in 0..100_u32 {
+            refs.push(&*arena.alloc(i));
+            arena.alloc(Rc::clone(&tracker));
+            arena.alloc(i as u8);
+        }
+        assert_eq!(arena.type_count(), 3);
+        assert_eq!(arena.arena::<u32>().len(), 100);
+        assert_eq!(arena.arena::<u8>().len(), 100);
+        assert_eq!(refs.iter().copied().sum::<u32>(), 4950);
+        assert_eq!(Rc::strong_count(&tracker), 101);
+    }
+    assert_eq!(Rc::strong_count(&tracker), 1);
+}
+
+#[test]
+fn interner_deduplicates() {
+    let interner = Interner::with_capacity(8);
+    let mut first = Vec::new();
+    for i in 0..100 {
+        first.push(interner.intern(&i.to_string()));
+    }
+    for i in (0..100).rev() {
+        let again = interner.intern(&i.to_string());
+        assert!(ptr::eq(again, first[i]));
+    }
+    assert_eq!(interner.len(), 100);
+    assert_eq!(interner.get("42"), Some("42"));
+    assert!(interner.get("100").is_none());
+    assert_eq!(interner.intern(""), "");
+    assert!(ptr::eq(interner.intern(""), interner.intern("")));
+}
+
+#[test]
+fn symbol_table_round_trips() {
+    let symbols = SymbolTable::with_capacity(8);
+    let words: Vec<String> = (0..100).map(|i| (i % 10).to_string()).collect();
+    let ids: Vec<Symbol> = words.iter().map(|w| symbols.intern(w)).collect();
+    assert_eq!(symbols.len(), 10);
+    for (word, &id) in words.iter().zip(&ids) {
+        assert_eq!(symbols.resolve(id), word);
+        assert_eq!(symbols.get(word), Some(id));
+        assert_eq!(Symbol::from_raw(id.to_raw()), id);
+    }
+    assert_eq!(ids[13].index(), 3);
+    assert!(symbols.try_resolve(Symbol::from_raw(10)).is_none());
+}
+
+#[test]
+fn dedup_arena_hash_conses() {
+    let arena = DedupArena::new();
+    let mut firsts = Vec::new();
+    for i in 0..50 {
+        firsts.push(arena.alloc(i.to_string()));
+    }
+    for i in (0..50).rev() {
+        assert!(ptr::eq(arena.alloc(i.to_string()), firsts[i]));
+    }
+    assert_eq!(arena.len(), 50);
+    assert!(arena.get(&String::from("50")).is_none());
+
+    let words = [String::from("a"), String::from("b")];
+    let a = arena.alloc_slice(&words);
+    assert!(ptr::eq(a, arena.alloc_slice(&words.clone())));
+    assert!(!ptr::eq(a, arena.alloc_slice(&words[..1])));
+    assert!(ptr::eq(arena.alloc_slice(&[]), arena.alloc_slice(&[])));
+}
+
+#[test]
+fn arena_vec_grows_and_drops() {
+    let drop_counter = Cell::new(0);
+    let bump = Bump::with_capacity(16);
+    let mut vec = ArenaVec::new_in(&bump);
+    for _ in 0..100 {
+        vec.push(DropTracker(&drop_counter));
+    }
+    assert_eq!(vec.len(), 100);
+    drop(vec.pop());
+    vec.truncate(50);
+    assert_eq!(drop_counter.get(), 50);
+    drop(vec);
+    assert_eq!(drop_counter.get(), 100);
+
+    let mut vec = ArenaVec::new_in(&bump);
+    vec.extend((0..10).map(|_| DropTracker(&drop_counter)));
+    let slice = vec.into_slice();
+    assert_eq!(slice.len(), 10);
+    drop(bump);
+    assert_eq!(drop_counter.get(), 100);
+
+    let bump = Bump::new();
+    let mut units = ArenaVec::new_in(&bump);
+    units.extend((0..1000).map(|_| ()));
+    assert_eq!((units.len(), bump.used_bytes()), (1000, 0));
+}
+
+#[test]
+fn arena_string_formats() {
+    use std::fmt::Write;
+
+    let bump = Bump::with_capacity(4);
+    let mut s = ArenaString::new_in(&bump);
+    for i in 0..100 {
+        write!(s, "{},", i).unwrap();
+    }
+    s.push('é');
+    assert_eq!(s.pop(), Some('é'));
+    assert_eq!(s.pop(), Some(','));
+    let expected: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+    let s: &str = s.into_str();
+    assert_eq!(s, expected.join(","));
+}
+
+#[test]
+fn slice_builder_moves_to_new_chunks() {
+    let drop_counter = Cell::new(0);
+    let arena = Arena::with_capacity(2);
+    let first = arena.alloc(DropTracker(&drop_counter));
+    let mut builder = arena.slice_builder();
+    for _ in 0..100 {
+        builder.push(DropTracker(&drop_counter));
+    }
+    assert_eq!(builder.len(), 100);
+    let slice = builder.finish();
+    assert_eq!(slice.len(), 100);
+    assert_eq!(arena.len(), 101);
+    let _ = first;
+
+    let mut dropped = arena.slice_builder();
+    dropped.extend((0..10).map(|_| DropTracker(&drop_counter)));
+    drop(dropped);
+    assert_eq!(drop_counter.get(), 10);
+    assert_eq!(arena.len(), 101);
+    assert!(arena.slice_builder().finish().is_empty());
+    drop(arena);
+    assert_eq!(drop_counter.get(), 111);
+}
+
+#[test]
+fn grow_last_in_place_and_by_copy() {
+    let arena = Arena::with_capacity(4);
+    let mut slice = arena.alloc_extend(vec![String::from("0")]);
+    for i in 1..50 {
+        slice = arena.grow_last(slice, Some(i.to_string()));
+    }
+    assert_eq!(arena.len(), 50);
+    let expected: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+    assert_eq!(slice, &expected[..]);
+
+    // Not the last allocation anymore: cloned.
+    let other = arena.alloc(String::from("other"));
+    let slice = arena.grow_last(slice, vec![String::from("50")]);
+    assert_eq!(slice.len(), 51);
+    assert_eq!(arena.len(), 50 + 1 + 51);
+    assert_eq!(other, "other");
+
+    let empty = arena.alloc_extend(None);
+    assert_eq!(arena.grow_last(empty, Some(String::from("x"))), ["x"]);
+
+    // A slice of zero-sized values from another arena is not taken for the
+    // last allocation of this one.
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    #[derive(Clone)]
+    struct Zst;
+    impl Drop for Zst {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+    {
+        let arena = Arena::new();
+        let other = Arena::new();
+        arena.alloc_extend(vec![Zst, Zst, Zst]);
+        let foreign = other.alloc_extend(vec![Zst, Zst]);
+        let grown = arena.grow_last(foreign, Some(Zst));
+        assert_eq!(grown.len(), 3);
+        assert_eq!((arena.len(), other.len()), (6, 2));
+    }
+    assert_eq!(DROPS.load(Ordering::SeqCst), 8);
+}
+
+#[test]
+fn grow_last_moves_full_chunks_without_reallocating_them() {
+    // The slice starts the full chunk, which would otherwise grow in place.
+    let arena = Arena::with_capacity(4);
+    let slice = arena.alloc_extend(0..4);
+    let start = slice.as_ptr();
+    let slice = arena.grow_last(slice, Some(4));
+    assert_eq!(slice, [0, 1, 2, 3, 4]);
+    assert_ne!(slice.as_ptr(), start);
+    assert_eq!(arena.len(), 5);
+    // The emptied chunk is kept for reuse.
+    assert_eq!(arena.chunks.borrow().spare.len(), 1);
+
+    // The slice follows other values, which stay where they are, and moves
+    // again along with the new values.
+    let arena = Arena::with_capacity(4);
+    let first = arena.alloc(0);
+    let slice = arena.alloc_extend(1..4);
+    let slice = arena.grow_last(slice, 4..40);
+    assert_eq!(slice, &(1..40).collect::<Vec<_>>()[..]);
+    assert_eq!((*first, arena.len()), (0, 40));
+    assert_eq!(arena.into_vec(), (0..40).collect::<Vec<_>>());
+
+    // If the iterator panics, the values of the slice stay in the arena,
+    // whether they moved or not.
+    use std::rc::Rc;
+    for &fail_at in &[1, 3] {
+        let live = Rc::new(());
+        let arena = Arena::with_capacity(2);
+        let slice = arena.alloc_extend(vec![live.clone()]);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let values = (0..).map(|i| {
+                assert!(i < fail_at);
+                live.clone()
+            });
+            arena.grow_last(slice, values);
+        }));
+        assert!(result.is_err());
+        assert_eq!((Rc::strong_count(&live), arena.len()), (2, 1));
+        drop(arena);
+        assert_eq!(Rc::strong_count(&live), 1);
+    }
+}
+
+#[test]
+fn slice_builder_io_write_parts() {
+    use std::io::Write;
+
+    let arena: Arena<u8> = Arena::with_capacity(4);
+    let mut out = arena.slice_builder();
+    let mut parts = Vec::new();
+    for i in 0..20 {
+        write!(out, "line {}", i).unwrap();
+        parts.push(out.take_slice());
+    }
+    out.write_all(&[0; 100]).unwrap();
+    let rest = out.finish();
+    for (i, part) in parts.iter().enumerate() {
+        assert_eq!(**part, *format!("line {}", i).as_bytes());
+    }
+    assert_eq!(rest.len(), 100);
+}
+
+#[test]
+fn str_builder_formats_into_arena() {
+    use std::fmt::Write;
+
+    let arena: Arena<u8> = Arena::with_capacity(2);
+    let mut builder = arena.str_builder();
+    let mut names = Vec::new();
+    for i in 0..30 {
+        write!(builder, "name{}", i).unwrap();
+        builder.push('ß');
+        names.push(builder.take_str());
+    }
+    builder.push_str("tail");
+    assert_eq!(builder.as_str(), "tail");
+    assert_eq!(builder.finish(), "tail");
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(**name, format!("name{}ß", i));
+    }
+}
+
+#[test]
+fn read_to_arena_reads_in_chunks() {
+    use std::io::{self, Read};
+
+    // Returns a few bytes at a time, then an error.
+    struct Trickle(usize, bool);
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return if self.1 {
+                    Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+                } else {
+                    Ok(0)
+                };
+            }
+            let n = buf.len().min(self.0).min(7);
+            for b in &mut buf[..n] {
+                *b = b'x';
+            }
+            self.0 -= n;
+            Ok(n)
+        }
+    }
+
+    let arena: Arena<u8> = Arena::with_capacity(4);
+    let first = arena.read_to_arena(Trickle(1000, false)).unwrap();
+    assert_eq!(first.len(), 1000);
+    assert!(first.iter().all(|&b| b == b'x'));
+    assert!(arena.read_to_arena(Trickle(100, true)).is_err());
+    assert_eq!(arena.len(), 1000);
+    assert_eq!(
+        arena
+            .read_to_arena(Trickle(100, false).take(10))
+            .unwrap()
+            .len(),
+        10
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_in_allocation_order() {
+    let mut arena = Arena::with_capacity(2);
+    arena.alloc_extend(vec![String::from("a"), String::from("b")]);
+    arena.alloc(String::from("c"));
+    assert!(arena.chunk_count() > 1);
+    let json = serde_json::to_string(&arena.share()).unwrap();
+    assert_eq!(json, r#"["a","b","c"]"#);
+    assert_eq!(serde_json::to_string(&arena.freeze()).unwrap(), json);
+
+    let ids = IdArena::new();
+    ids.alloc(1);
+    ids.alloc(2);
+    assert_eq!(serde_json::to_string(&ids).unwrap(), "[1,2]");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialize_seq_into_arena() {
+    use serde::de::DeserializeSeed;
+
+    let arena: Arena<String> = Arena::with_capacity(1);
+    let first = arena.alloc(String::from("first"));
+    let mut json = serde_json::Deserializer::from_str(r#"["a", "b", "c"]"#);
+    let slice = arena.seq_seed().deserialize(&mut json).unwrap();
+    assert_eq!(slice, ["a", "b", "c"]);
+    assert_eq!(first, "first");
+
+    let mut json = serde_json::Deserializer::from_str(r#"["d", 1]"#);
+    assert!(arena.deserialize_seq(&mut json).is_err());
+    assert_eq!(arena.len(), 4);
+
+    let ids: IdArena<u8> = IdArena::new();
+    let mut json = serde_json::Deserializer::from_str("[1, 2, 300]");
+    assert!(ids.deserialize_seq(&mut json).is_err());
+    assert_eq!(ids.len(), 2);
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn archive_in_allocation_order() {
+    let mut arena = Arena::with_capacity(2);
+    arena.alloc_extend(vec![String::from("a"), String::from("b")]);
+    arena.alloc(String::from("c"));
+    assert!(arena.chunk_count() > 1);
+    let bytes = rkyv::to_bytes::<_, 256>(&arena.share()).unwrap();
+    let archived = unsafe { rkyv::archived_root::<FrozenArena<String>>(&bytes) };
+    assert_eq!(archived.len(), 3);
+    assert!(archived.iter().zip(["a", "b", "c"]).all(|(a, b)| a == b));
+
+    let copy: Arena<String> = Arena::with_capacity(1);
+    let first = copy.alloc(String::from("first"));
+    let slice = copy
+        .deserialize_archived(archived, &mut rkyv::Infallible)
+        .unwrap();
+    assert_eq!(slice, ["a", "b", "c"]);
+    assert_eq!(first, "first");
+    assert_eq!(copy.len(), 4);
+
+    let ids = IdArena::new();
+    let one = ids.alloc(1_u64);
+    let two = ids.alloc(2);
+    let bytes = rkyv::to_bytes::<_, 256>(&ids).unwrap();
+    let archived = unsafe { rkyv::archived_root::<IdArena<u64>>(&bytes) };
+    assert_eq!((archived[one.index()], archived[two.index()]), (1, 2));
+}
+
+#[test]
+fn clone_into_new_arena_keeps_order() {
+    let arena = Arena::with_capacity(2);
+    let values: Vec<&String> = (0..10).map(|i| &*arena.alloc(i.to_string())).collect();
+    assert!(arena.chunk_count() > 1);
+    let (mut clone, map) = arena.clone_into_new_arena();
+    assert_eq!(map.len(), 10);
+    assert_eq!(clone.chunk_count(), 1);
+    for (i, &value) in values.iter().enumerate() {
+        let index = map.index_of(value).unwrap();
+        assert_eq!(index, i);
+        assert_eq!(clone.get(index), Some(value));
+    }
+    assert_eq!(map.index_of(&String::new()), None);
+    clone.get_mut(0).unwrap().push('!');
+    assert_eq!(values[0], "0");
+}
+
+#[test]
+fn absorb_keeps_values_in_place() {
+    let drop_counter = Cell::new(0);
+    {
+        let mut arena = Arena::with_capacity(2);
+        let other = Arena::with_capacity(2);
+        arena.alloc(Node(None, 1, DropTracker(&drop_counter)));
+        let mut moved: Vec<*const Node> = Vec::new();
+        for i in 2..6 {
+            moved.push(other.alloc(Node(None, i, DropTracker(&drop_counter))));
+        }
+        assert_eq!(other.chunk_count(), 2);
+
+        arena.absorb(other);
+        arena.absorb(Arena::new());
+        assert_eq!(arena.len(), 5);
+        assert_eq!(arena.chunk_count(), 3);
+        assert!(moved.iter().all(|&node| arena.contains(unsafe { &*node })));
+        arena.alloc(Node(None, 6, DropTracker(&drop_counter)));
+        let order: Vec<u32> = arena.iter_mut().map(|node| node.1).collect();
+        assert_eq!(order, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 6);
+}
+
+#[test]
+fn transfer_last_moves_without_dropping() {
+    let drop_counter = Cell::new(0);
+    {
+        let long_lived = Arena::new();
+        let mut scratch = Arena::with_capacity(1);
+        scratch.alloc(Node(None, 1, DropTracker(&drop_counter)));
+        scratch.reserve_extend(4);
+        assert_eq!(scratch.chunk_count(), 2);
+        let moved = scratch.transfer_last(&long_lived).unwrap();
+        assert_eq!(moved.1, 1);
+        assert!(scratch.is_empty());
+        assert!(scratch.transfer_last(&long_lived).is_none());
+        drop(scratch);
+        assert_eq!(drop_counter.get(), 0);
+        assert_eq!(long_lived.len(), 1);
+    }
+    assert_eq!(drop_counter.get(), 1);
+}
+
+#[test]
+fn stats_track_peak_use() {
+    let mut arena = Arena::<u64>::builder().label("nodes").build();
+    assert_eq!(arena.stats().peak_len, 0);
+    arena.alloc_extend(0..100);
+    {
+        let sub_arena = SubArena::new(&arena);
+        sub_arena.alloc_extend(0..50);
+    }
+    assert_eq!(arena.stats().len, 100);
+    assert_eq!(arena.stats().peak_len, 150);
+    arena.reset();
+    arena.alloc(1);
+    let stats = arena.stats();
+    assert_eq!((stats.len, stats.peak_len), (1, 150));
+    assert_eq!(stats.peak_used_bytes, 150 * 8);
+    assert_eq!(stats.chunk_count, arena.chunk_count());
+    assert!(format!("{:?}", arena).starts_with("Arena { label: \"nodes\", len: 1,"));
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_events() {
+    use std::fmt::{self, Write};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::{span, Event, Metadata, Subscriber};
+
+    // Records the fields of each event, as a line of `name=value` pairs.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            let _ = write!(self.0, "{}={:?} ", field.name(), value);
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+        fn new_span(&self, _: &span::Attributes) -> span::Id {
+            span::Id::from_u64(1)
+        }
+        fn record(&self, _: &span::Id, _: &span::Record) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, event: &Event) {
+            assert_eq!(event.metadata().target(), "typed_arena");
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0.trim_end().to_string());
+        }
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut arena = Arena::<u64>::builder()
+            .initial_capacity(2)
+            .label("nodes")
+            .build();
+        arena.alloc_extend(0..2);
+        arena.alloc(2);
+        {
+            let sub_arena = SubArena::new(&arena);
+            sub_arena.alloc_extend(3..10);
+        }
+        arena.clear();
+        arena.alloc_extend(0..3);
+        drop(arena);
+
+        let arena = Arena::<u8>::with_capacity(2);
+        arena.alloc_extend(0..10);
+
+        // Nothing to report about arenas that don't allocate.
+        let zsts = Arena::new();
+        zsts.alloc_extend(vec![(); 10]);
+    });
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            r#"message=allocated a chunk label="nodes" bytes=16"#,
+            r#"message=allocated a chunk label="nodes" bytes=32"#,
+            r#"message=allocated a chunk label="nodes" bytes=64"#,
+            r#"message=rolled back a sub-arena label="nodes" values=7 chunks=1"#,
+            r#"message=cleared an arena label="nodes" values=3"#,
+            r#"message=dropped an arena label="nodes" chunks_allocated=3 bytes_allocated=112"#,
+            "message=allocated a chunk bytes=2",
+            "message=grew a chunk bytes=14",
+            "message=dropped an arena chunks_allocated=1 bytes_allocated=16",
+        ]
+    );
+}
+
+#[test]
+// Miri aborts instead of failing such a huge allocation.
+#[cfg_attr(miri, ignore)]
+fn alloc_error_handler_retries() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Huge;
+    impl Iterator for Huge {
+        type Item = u8;
+        fn next(&mut self) -> Option<u8> {
+            None
+        }
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (1 << 62, None)
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let arena = Arena::<u8>::builder()
+        .on_alloc_error(move |bytes| {
+            assert_eq!(bytes, 1 << 62);
+            counter.fetch_add(1, Ordering::Relaxed) < 2
+        })
+        .build();
+    arena.alloc(1);
+    assert_eq!(arena.try_alloc_extend(Huge), Err(AllocError));
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+    // Sizes that overflow fail right away.
+    let err = arena.chunks.borrow_mut().try_reserve(usize::MAX);
+    assert_eq!(err, Err(AllocError));
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+    assert_eq!(arena.into_vec(), [1]);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics_counters() {
+    use metrics::{
+        Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    // Keeps the value of each metric, by name and labels.
+    type Values = Arc<Mutex<BTreeMap<String, f64>>>;
+
+    struct Handle(Values, String);
+
+    impl Handle {
+        fn add(&self, value: f64) {
+            *self.0.lock().unwrap().entry(self.1.clone()).or_default() += value;
+        }
+    }
+
+    impl CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            self.add(value as f64);
+        }
+        fn absolute(&self, _: u64) {
+            unreachable!();
+        }
+    }
+
+    impl GaugeFn for Handle {
+        fn increment(&self, value: f64) {
+            self.add(value);
+        }
+        fn decrement(&self, value: f64) {
+            self.add(-value);
+        }
+        fn set(&self, _: f64) {
+            unreachable!();
+        }
+    }
+
+    struct Registry(Values);
+
+    impl Registry {
+        fn handle(&self, key: &Key) -> Arc<Handle> {
+            let mut name = key.name().to_string();
+            for label in key.labels() {
+                name += &format!("{{{}={}}}", label.key(), label.value());
+            }
+            Arc::new(Handle(self.0.clone(), name))
+        }
+    }
+
+    impl Recorder for Registry {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn register_counter(&self, key: &Key, _: &Metadata) -> Counter {
+            Counter::from_arc(self.handle(key))
+        }
+        fn register_gauge(&self, key: &Key, _: &Metadata) -> Gauge {
+            Gauge::from_arc(self.handle(key))
+        }
+        fn register_histogram(&self, _: &Key, _: &Metadata) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    let values = Values::default();
+    let value = |name: &str| values.lock().unwrap().get(name).copied().unwrap_or(0.0);
+    metrics::with_local_recorder(&Registry(values.clone()), || {
+        let mut arena = Arena::<u64>::builder()
+            .initial_capacity(2)
+            .label("nodes")
+            .build();
+        assert_eq!(value("typed_arena.arenas_live{arena=nodes}"), 1.0);
+        arena.alloc_extend(0..2);
+        arena.alloc(2);
+        {
+            let sub_arena = SubArena::new(&arena);
+            sub_arena.alloc_extend(3..10);
+        }
+        assert_eq!(value("typed_arena.chunks_allocated{arena=nodes}"), 3.0);
+        assert_eq!(value("typed_arena.bytes_allocated{arena=nodes}"), 112.0);
+
+        let other = Arena::with_capacity(1);
+        other.alloc(10);
+        assert_eq!(value("typed_arena.arenas_live"), 1.0);
+        arena.absorb(other);
+        assert_eq!(value("typed_arena.arenas_live"), 0.0);
+        assert_eq!(value("typed_arena.arenas_live{arena=nodes}"), 1.0);
+        assert_eq!(value("typed_arena.chunks_allocated"), 1.0);
+        assert_eq!(value("typed_arena.bytes_allocated"), 8.0);
+
+        // Reused chunks don't count.
+        arena.clear();
+        arena.alloc_extend(0..10);
+        assert_eq!(value("typed_arena.chunks_allocated{arena=nodes}"), 3.0);
+        drop(arena);
+        assert_eq!(value("typed_arena.arenas_live{arena=nodes}"), 0.0);
+
+        let zsts = Arena::new();
+        zsts.alloc_extend(vec![(); 10]);
+        assert_eq!(value("typed_arena.arenas_live"), 0.0);
+    });
+}
+
+#[test]
+fn debug_dump_lists_every_chunk() {
+    let mut arena = Arena::<u64>::builder()
+        .label("dump")
+        .initial_capacity(1)
+        .build();
+    for i in 0..3 {
+        arena.alloc(i);
+    }
+    arena.clear();
+    let mut dump = String::new();
+    arena.debug_dump(&mut dump).unwrap();
+    let lines: Vec<&str> = dump.lines().collect();
+    assert_eq!(lines[0], "dump: 0 values in 2 chunks, 24 bytes allocated");
+    assert!(lines[1].starts_with("chunk 0 (current): 0/1 values, 0x"));
+    assert!(lines[2].starts_with("chunk 1 (spare): 0/2 values, 0x"));
+    assert_eq!(lines.len(), 3);
+
+    let start = arena.chunks.borrow().current.as_ptr() as usize;
+    assert!(lines[1].ends_with(&format!("{:#x}..{:#x}", start, start + 8)));
+
+    let sub_arena = SubArena::new(&arena);
+    dump.clear();
+    arena.debug_dump(&mut dump).unwrap();
+    assert_eq!(
+        dump,
+        "dump: 0 values in 2 chunks, 24 bytes allocated\nchunks lent to a SubArena\n"
+    );
+    drop(sub_arena);
+
+    let builder = arena.slice_builder();
+    dump.clear();
+    arena.debug_dump(&mut dump).unwrap();
+    assert_eq!(dump, "Arena: borrowed by a SliceBuilder\n");
+    drop(builder);
+
+    dump.clear();
+    Arena::<u64>::new().debug_dump(&mut dump).unwrap();
+    assert_eq!(dump, "Arena: 0 values in 0 chunks, 0 bytes allocated\n");
+}
+
+#[test]
+fn chunks_skip_empty_chunks() {
+    let mut arena = Arena::with_capacity(2);
+    arena.alloc(0);
+    arena.reserve_extend(10);
+    arena.alloc_extend(1..4);
+    arena.reserve_extend(100);
+    assert_eq!(arena.chunk_count(), 3);
+    let chunks: Vec<&[i32]> = arena.chunks().collect();
+    assert_eq!(chunks, [&[0][..], &[1, 2, 3]]);
+
+    for chunk in arena.chunks_mut() {
+        for x in chunk {
+            *x *= 10;
+        }
+    }
+    assert_eq!(arena.into_vec(), [0, 10, 20, 30]);
+    assert_eq!(Arena::<u8>::new().chunks().next(), None);
+}
+
+#[test]
+fn chunk_ranges_cover_every_value() {
+    let mut arena = Arena::with_capacity(1);
+    let values: Vec<*const u32> = (0..20).map(|i| arena.alloc(i) as *const u32).collect();
+    let ranges = arena.chunk_ranges();
+    assert_eq!(ranges.len(), arena.chunk_count());
+    assert!(values
+        .iter()
+        .all(|value| ranges.iter().filter(|r| r.contains(value)).count() == 1));
+    let outside = 0_u32;
+    assert!(!ranges.iter().any(|r| r.contains(&(&outside as *const u32))));
+
+    arena.clear();
+    assert_eq!(arena.chunk_ranges(), ranges);
+    let zsts = Arena::<()>::with_capacity(4);
+    zsts.alloc(());
+    assert!(zsts.chunk_ranges()[0].is_empty());
+}
+
+#[test]
+fn scoped_rolls_back_on_panic() {
+    let drop_counter = Cell::new(0);
+    let arena = Arena::new();
+    arena.alloc(Node(None, 1, DropTracker(&drop_counter)));
+    let len = arena.scoped(|sub| {
+        sub.alloc(Node(None, 2, DropTracker(&drop_counter)));
+        sub.len()
+    });
+    assert_eq!(len, 1);
+    assert_eq!(drop_counter.get(), 1);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        arena.scoped(|sub| {
+            sub.alloc(Node(None, 3, DropTracker(&drop_counter)));
+            panic!("oops");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(drop_counter.get(), 2);
+    assert_eq!(arena.len(), 1);
+}
+
+#[test]
+fn sub_arena_promote_keeps_values() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(2);
+        arena.alloc(Node(None, 0, DropTracker(&drop_counter)));
+        let promoted = {
+            let sub_arena = SubArena::new(&arena);
+            sub_arena.reserve(100);
+            for i in 1..6 {
+                sub_arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+            }
+            sub_arena.promote()
+        };
+        assert_eq!(drop_counter.get(), 0);
+        let values: Vec<u32> = promoted
+            .iter()
+            .flat_map(|c| c.iter().map(|n| n.1))
+            .collect();
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+        assert_eq!(arena.len(), 6);
+        arena.alloc(Node(None, 6, DropTracker(&drop_counter)));
+        assert_eq!(arena.stats().peak_len, 7);
+    }
+    assert_eq!(drop_counter.get(), 7);
+
+    let arena = Arena::<&'static u32>::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        SubArenaBuilder::new(&arena).build().promote();
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn sub_arena_forget_leaks_values_in_place() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(4);
+        arena.alloc(Node(None, 0, DropTracker(&drop_counter)));
+        let sub_arena = SubArena::new(&arena);
+        let leaked: Vec<*const Node> = (1..10)
+            .map(|i| sub_arena.alloc(Node(None, i, DropTracker(&drop_counter))) as *const Node)
+            .collect();
+        sub_arena.forget();
+        assert_eq!(arena.len(), 1);
+        for i in 10..20 {
+            arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+        }
+        // Neither moved nor overwritten.
+        for (i, &node) in leaked.iter().enumerate() {
+            assert_eq!(unsafe { (*node).1 }, i as u32 + 1);
+        }
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 11);
+
+    // Markers taken before the sub-arena can't roll back over its values,
+    // but those taken after it can.
+    let mut arena = Arena::with_capacity(4);
+    arena.alloc(0);
+    let before = arena.marker();
+    let sub_arena = SubArena::new(&arena);
+    sub_arena.alloc(1);
+    sub_arena.forget();
+    let after = arena.marker();
+    arena.alloc(2);
+    arena.rollback_to(after);
+    assert_eq!(arena.len(), 1);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| arena.rollback_to(before)));
+    assert!(result.is_err());
+    arena.clear();
+    arena.rollback_to(arena.marker());
+}
+
+#[test]
+fn arena_recovers_from_leaked_sub_arena() {
+    let drop_counter = Cell::new(0);
+    {
+        // The sub-arena stays within the first chunk, so that only its
+        // values leak.
+        let mut arena = Arena::with_capacity(16);
+        arena.alloc(Node(None, 0, DropTracker(&drop_counter)));
+        let sub_arena = SubArena::new(&arena);
+        sub_arena.alloc_extend((1..10).map(|i| Node(None, i, DropTracker(&drop_counter))));
+        mem::forget(sub_arena);
+        // The values of the sub-arena are leaked, but not those before it.
+        assert_eq!(arena.get_mut(0).unwrap().1, 0);
+        assert!(arena.get_mut(1).is_none());
+        arena.alloc(Node(None, 10, DropTracker(&drop_counter)));
+        assert_eq!(arena.len(), 2);
+        arena.clear();
+        assert_eq!(drop_counter.get(), 2);
+        arena.alloc(Node(None, 11, DropTracker(&drop_counter)));
+    }
+    assert_eq!(drop_counter.get(), 3);
+
+    let arena = Arena::new();
+    arena.alloc_extend(0..3);
+    mem::forget(SubArena::new(&arena));
+    assert_eq!(arena.into_vec(), vec![0, 1, 2]);
+
+    let mut arena = Arena::new();
+    mem::forget(arena.slice_builder());
+    arena.reset();
+    arena.alloc(1);
+}
+
+#[test]
+// Leaks the chunk the sub-arena added, so Miri runs it apart, see
+// `ci/miri.sh`.
+#[cfg_attr(miri, ignore)]
+fn leaked_sub_arena_leaves_grown_parent_slices_in_place() {
+    // The slice of the parent fills the chunk lent to the sub-arena, which
+    // would otherwise grow in place, or move the values of the parent.
+    let mut arena = Arena::with_capacity(4);
+    let values = arena.alloc_extend((0..4).map(|i| i.to_string()));
+    let sub_arena = SubArena::new(&arena);
+    let grown = sub_arena.grow_last(values, Some(4.to_string()));
+    grown[0].push_str(" and then some");
+    assert_eq!(grown.len(), 5);
+    assert_eq!(sub_arena.len(), 5);
+    mem::forget(sub_arena);
+    let expected: Vec<String> = (0..4).map(|i| i.to_string()).collect();
+    assert_eq!(arena.iter().cloned().collect::<Vec<_>>(), expected);
+    arena.clear();
+    arena.alloc(String::from("x"));
+}
+
+#[test]
+fn sub_arena_drop_hands_back_chunks() {
+    let drop_counter = Cell::new(0);
+    let arena = Arena::with_capacity(4);
+    arena.alloc(Node(None, 0, DropTracker(&drop_counter)));
+    let mut bytes = None;
+    for round in 0..3 {
+        {
+            let sub_arena = SubArena::new(&arena);
+            for i in 0..1000 {
+                sub_arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+            }
+        }
+        assert_eq!(drop_counter.get(), 1000 * (round + 1));
+        assert_eq!(arena.len(), 1);
+        // Later rounds reuse the chunks of the first one.
+        let allocated = arena.allocated_bytes();
+        assert_eq!(*bytes.get_or_insert(allocated), allocated);
+    }
+    assert!(arena.chunk_count() > 1);
+}
+
+#[test]
+fn sub_arena_stats_only_count_the_sub_arena() {
+    let arena = Arena::<u32>::builder()
+        .label("ast")
+        .initial_capacity(2)
+        .build();
+    arena.alloc(1);
+    {
+        let pass = SubArena::new(&arena);
+        assert_eq!(pass.stats().chunk_count, 0);
+        pass.alloc(2);
+        let stats = pass.stats();
+        assert_eq!(
+            (stats.len, stats.chunk_count, stats.allocated_bytes),
+            (1, 0, 0)
+        );
+        pass.alloc_extend(0..10);
+        let stats = pass.stats();
+        assert_eq!(stats.label, Some("ast"));
+        assert_eq!((stats.len, stats.used_bytes), (11, 44));
+        assert_eq!((stats.chunk_count, stats.allocated_bytes), (1, 64));
+    }
+    // The second pass reuses the chunk of the first.
+    let pass = SubArena::new(&arena);
+    pass.alloc_extend(0..10);
+    assert_eq!(pass.stats().allocated_bytes, 0);
+
+    let empty = Arena::<u32>::new();
+    let pass = SubArena::new(&empty);
+    assert_eq!(pass.stats().chunk_count, 0);
+    pass.alloc(1);
+    assert_eq!(pass.stats().chunk_count, 1);
+}
+
+#[test]
+fn try_alloc_unlocked_never_panics() {
+    let arena = Arena::new();
+    assert!(!arena.is_locked());
+    {
+        let builder = arena.slice_builder();
+        assert!(arena.is_locked());
+        let err = arena.try_alloc_unlocked(String::from("x")).unwrap_err();
+        assert_eq!(err.into_inner(), "x");
+        drop(builder);
+    }
+    let values = arena.alloc_extend((0..3).map(|i| {
+        assert!(arena.try_alloc_unlocked(String::new()).is_err());
+        i.to_string()
+    }));
+    assert_eq!(values, ["0", "1", "2"]);
+    assert_eq!(arena.try_alloc_unlocked(String::from("y")).unwrap(), "y");
+    assert_eq!(arena.len(), 4);
+}
+
+#[test]
+fn parent_can_be_inspected_during_sub_arena() {
+    let arena = Arena::<u64>::with_capacity(4);
+    let x = &*arena.alloc(1);
+    let before = arena.stats();
+    let capacity = arena.capacity();
+    {
+        let sub_arena = SubArena::new(&arena);
+        let y = &sub_arena.alloc_extend(0..10)[0];
+        assert_eq!(arena.len(), 1);
+        assert!(!arena.is_empty());
+        assert!(arena.contains(x) && !arena.contains(y));
+        assert_eq!(arena.stats(), before);
+        assert_eq!(arena.capacity(), capacity);
+        assert!(arena.is_locked());
+
+        let nested = SubArena::new(&*sub_arena);
+        nested.alloc(2);
+        assert_eq!((sub_arena.len(), sub_arena.stats().len), (10, 10));
+        assert!(sub_arena.contains(x) && sub_arena.contains(y));
+        assert_eq!(*x, 1);
+    }
+    assert!(!arena.is_locked());
+    assert_eq!(arena.len(), 1);
+    arena.alloc(3);
+    assert_eq!(arena.len(), 2);
+}
+
+#[test]
+fn rollback_to_markers_in_a_stack() {
+    let drops = Cell::new(0);
+    let mut arena = Arena::with_capacity(2);
+    let mut undo = Vec::new();
+    for round in 0..4 {
+        undo.push(arena.marker());
+        for _ in 0..round * 3 {
+            arena.alloc(DropTracker(&drops));
+        }
+    }
+    assert_eq!(arena.len(), 18);
+    let chunk_count = arena.chunk_count();
+
+    arena.rollback_to(undo.pop().unwrap());
+    assert_eq!((arena.len(), drops.get()), (9, 9));
+    let marker = undo.pop().unwrap();
+    unsafe { arena.truncate_to(marker) };
+    assert_eq!((arena.len(), drops.get()), (3, 15));
+    assert_eq!(arena.marker(), marker);
+    assert_eq!(arena.chunk_count(), chunk_count);
+
+    for _ in 0..15 {
+        arena.alloc(DropTracker(&drops));
+    }
+    assert_eq!(arena.chunk_count(), chunk_count);
+
+    let first = undo[0];
+    arena.rollback_to(first);
+    assert!(arena.is_empty());
+    let past_end = panic::catch_unwind(AssertUnwindSafe(|| arena.rollback_to(marker)));
+    assert!(past_end.is_err());
+    assert_eq!(drops.get(), 33);
+}
+
+#[test]
+fn empty_nested_sub_arenas_leave_the_arena_unchanged() {
+    let recycler = std::sync::Arc::new(ChunkRecycler::new());
+    let arena = Arena::builder().chunk_recycler(recycler.clone()).build();
+    arena.alloc_extend(0..3);
+    let start = arena.chunks.borrow().current.as_ptr();
+    {
+        let sub_arena = SubArena::new(&arena);
+        sub_arena.alloc(3);
+        for _ in 0..100 {
+            drop(SubArena::new(&*sub_arena));
+        }
+        assert_eq!(sub_arena.len(), 1);
+        assert_eq!(std::sync::Arc::strong_count(&recycler), 2);
+    }
+    let chunks = arena.chunks.borrow();
+    assert_eq!(chunks.current.as_ptr(), start);
+    assert_eq!((chunks.current.len(), chunks.rest.len()), (3, 0));
+    assert!(chunks.spare.is_empty());
+    assert!(chunks.recycler.is_some());
+}
+
+#[test]
+fn bump_scopes_drop_their_values_and_reuse_memory() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static UNIT_DROPS: AtomicUsize = AtomicUsize::new(0);
+    struct Unit;
+    impl Drop for Unit {
+        fn drop(&mut self) {
+            UNIT_DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let bump = Bump::with_capacity(64);
+    let kept = bump.alloc(7_u8);
+    let used = bump.used_bytes();
+    for _ in 0..2 {
+        let total = bump.scoped(|scope| {
+            for i in 0..100_u64 {
+                scope.alloc((i, DropTracker(&drops)));
+            }
+            assert_eq!(scope.len(), 100);
+            scope.len()
+        });
+        assert_eq!(total, 100);
+        assert_eq!(bump.used_bytes(), used);
+        assert!(bump.allocated_bytes() > 64);
+    }
+    assert_eq!(drops.get(), 200);
+    let allocated = bump.allocated_bytes();
+    bump.scoped(|scope| {
+        for _ in 0..100_u64 {
+            scope.alloc((0_u64, DropTracker(&drops)));
+        }
+    });
+    assert_eq!(bump.allocated_bytes(), allocated);
+
+    bump.scoped(|scope| {
+        scope.alloc(Unit);
+        scope.alloc(Unit);
+    });
+    assert_eq!(UNIT_DROPS.load(Ordering::Relaxed), 2);
+    assert_eq!(drops.get(), 300);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        bump.scoped(|scope| {
+            scope.alloc(DropTracker(&drops));
+            panic!("oops");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 301);
+    assert_eq!(*kept, 7);
+}
+
+#[test]
+fn sub_arena_builder_scoped_shortens_lifetimes() {
+    let drops = Cell::new(0);
+    let arena: Arena<Option<&'static DropTracker>> = Arena::new();
+    arena.alloc(None);
+    let local = DropTracker(&drops);
+    let found = SubArenaBuilder::new(&arena).scoped(|sub| {
+        let slot = sub.alloc(Some(&local));
+        assert_eq!(sub.len(), 1);
+        slot.is_some()
+    });
+    assert!(found);
+    assert_eq!(arena.len(), 1);
+    drop(local);
+    assert_eq!(drops.get(), 1);
+}
+
+// Compiles only while `SubArenaBuilder` is covariant.
+fn shorten_builder<'a, 's>(
+    builder: SubArenaBuilder<'s, &'static u8>,
+) -> SubArenaBuilder<'s, &'a u8> {
+    builder
+}
+
+#[test]
+fn sub_arena_builder_is_covariant() {
+    let arena: Arena<&'static u8> = Arena::new();
+    arena.alloc(&1);
+    let local = 2;
+    {
+        let sub_arena = shorten_builder(SubArenaBuilder::new(&arena)).build();
+        assert_eq!(**sub_arena.alloc(&local), 2);
+        assert!(arena.is_locked());
+    }
+    assert!(!arena.is_locked());
+    assert_eq!(arena.len(), 1);
+}
+
+#[test]
+fn sub_arena_with_capacity() {
+    let arena = Arena::with_capacity(4);
+    arena.alloc(0);
+    let before = arena.allocated_bytes();
+
+    // There is room already, so nothing is allocated.
+    drop(SubArena::with_capacity(&arena, 3).free_reserved_on_drop());
+    assert_eq!(arena.allocated_bytes(), before);
+
+    // By default, the parent keeps the chunk for reuse.
+    {
+        let sub_arena = SubArena::with_capacity(&arena, 100);
+        let chunk_count = sub_arena.chunk_count();
+        sub_arena.alloc_extend(0..100);
+        assert_eq!(sub_arena.chunk_count(), chunk_count);
+    }
+    let retained = arena.allocated_bytes();
+    assert!(retained > before);
+    drop(SubArena::with_capacity(&arena, 100));
+    assert_eq!(arena.allocated_bytes(), retained);
+
+    // Freed whether or not it was filled, while other chunks are kept.
+    let arena = Arena::with_capacity(4);
+    arena.alloc(0);
+    {
+        let sub_arena = SubArena::with_capacity(&arena, 10).free_reserved_on_drop();
+        sub_arena.alloc_extend(0..20);
+        assert_eq!(sub_arena.len(), 20);
+    }
+    assert_eq!(arena.len(), 1);
+    let kept = arena.allocated_bytes();
+    drop(SubArena::with_capacity(&arena, 10).free_reserved_on_drop());
+    assert_eq!(arena.allocated_bytes(), kept);
+
+    // Still freed after a slice filling it grew it in place.
+    let arena = Arena::with_capacity(4);
+    arena.alloc(0);
+    let before = arena.allocated_bytes();
+    {
+        let sub_arena = SubArena::with_capacity(&arena, 100).free_reserved_on_drop();
+        let slice = sub_arena.alloc_extend((0..1000).filter(|_| true));
+        assert_eq!(slice.len(), 1000);
+    }
+    assert_eq!(arena.allocated_bytes(), before);
+}
+
+#[test]
+fn sub_arena_drop_survives_panicking_destructor() {
+    struct PanicOnDrop<'a>(&'a Cell<u32>, bool);
+    impl<'a> Drop for PanicOnDrop<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+            if self.1 {
+                panic!("PanicOnDrop");
+            }
+        }
+    }
+
+    let drops = Cell::new(0);
+    let arena = Arena::with_capacity(4);
+    let kept = arena.alloc(PanicOnDrop(&drops, false));
+    // The panicking value is in the chunk shared with the parent, then in a
+    // chunk added by the sub-arena.
+    for panicking in [1, 8] {
+        drops.set(0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let sub_arena = SubArena::new(&arena);
+            for i in 0..20 {
+                sub_arena.alloc(PanicOnDrop(&drops, i == panicking));
+            }
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 20);
+        assert_eq!(arena.len(), 1);
+        assert!(!arena.is_locked());
+    }
+    assert!(!kept.1);
+    arena.alloc_extend((0..10).map(|_| PanicOnDrop(&drops, false)));
+    assert_eq!(arena.len(), 11);
+}
+
+#[test]
+fn alloc_extend_reuses_spare_chunks() {
+    let mut arena = Arena::with_capacity(4);
+    for i in 0..100 {
+        arena.alloc(i);
+    }
+    arena.clear();
+    let bytes = arena.allocated_bytes();
+    assert_eq!(arena.alloc_extend(0..50).len(), 50);
+    assert_eq!(arena.allocated_bytes(), bytes);
+    // The chunk it replaced is still used afterwards.
+    for i in 0..74 {
+        arena.alloc(i);
+    }
+    assert_eq!(arena.allocated_bytes(), bytes);
+
+    let arena = Arena::with_capacity(4);
+    arena.reserve(100);
+    let bytes = arena.allocated_bytes();
+    assert_eq!(arena.alloc_extend(0..100).len(), 100);
+    assert_eq!(arena.allocated_bytes(), bytes);
+}
+
+#[test]
+fn len_and_is_empty() {
+    let arena = Arena::with_capacity(2);
+    assert!(arena.is_empty());
+    arena.alloc_extend(0..5);
+    assert_eq!(arena.len(), 5);
+    assert!(!arena.is_empty());
+    {
+        let sub_one = SubArena::new(&arena);
+        assert!(sub_one.is_empty());
+        sub_one.alloc_extend(0..3);
+        assert_eq!(sub_one.len(), 3);
+        {
+            let sub_two = SubArena::new(&*sub_one);
+            sub_two.alloc(7);
+            assert_eq!(sub_two.len(), 1);
+        }
+        assert_eq!(sub_one.len(), 3);
+    }
+    assert_eq!(arena.len(), 5);
+}
+
+#[test]
+fn memory_footprint() {
+    let arena: Arena<u64> = Arena::with_capacity(4);
+    assert_eq!(arena.chunk_count(), 1);
+    assert_eq!(arena.capacity(), 4);
+    assert_eq!(arena.allocated_bytes(), 32);
+    assert_eq!(arena.used_bytes(), 0);
+
+    arena.alloc(0);
+    arena.alloc_extend(1..5);
+    assert_eq!(arena.chunk_count(), 2);
+    assert_eq!(arena.capacity(), 4 + 8);
+    assert_eq!(arena.allocated_bytes(), 96);
+    assert_eq!(arena.used_bytes(), 40);
+
+    // Zero-sized types never need any memory.
+    let arena = Arena::new();
+    arena.alloc_extend((0..100).map(|_| ()));
+    assert_eq!(arena.allocated_bytes(), 0);
+    assert_eq!(arena.used_bytes(), 0);
+    assert_eq!(arena.capacity(), usize::MAX);
+}
+
+#[test]
+fn reserve() {
+    let arena = Arena::with_capacity(4);
+    arena.alloc(0);
+    // Fits in the current chunk.
+    arena.reserve(3);
+    assert_eq!(arena.chunk_count(), 1);
+
+    arena.reserve(50);
+    assert_eq!(arena.chunk_count(), 2);
+    let capacity = arena.capacity();
+    assert!(capacity >= 51);
+    for i in 1..51 {
+        arena.alloc(i);
+    }
+    assert_eq!(arena.chunk_count(), 2);
+    assert_eq!(arena.capacity(), capacity);
+    assert_eq!(arena.into_vec(), (0..51).collect::<Vec<_>>());
+}
+
+#[test]
+fn shrink_to_fit() {
+    let drop_counter = Cell::new(0);
+    {
+        let mut arena = Arena::with_capacity(4);
+        arena.alloc_extend((0..3).map(|i| Node(None, i, DropTracker(&drop_counter))));
+        // Leaves unused space in the first chunk, and a spare chunk.
+        arena.alloc_extend((3..10).map(|i| Node(None, i, DropTracker(&drop_counter))));
+        arena.reserve(100);
+        assert_eq!(arena.chunk_count(), 3);
+
+        arena.shrink_to_fit();
+        assert_eq!(arena.chunk_count(), 2);
+        assert_eq!(arena.capacity(), 10);
+        assert_eq!(drop_counter.get(), 0);
+        let ids: Vec<u32> = arena.iter_mut().map(|node| node.1).collect();
+        assert_eq!(ids, (0..10).collect::<Vec<_>>());
+
+        arena.alloc(Node(None, 10, DropTracker(&drop_counter)));
+        assert_eq!(arena.len(), 11);
+    }
+    assert_eq!(drop_counter.get(), 11);
+
+    let mut arena = Arena::<u8>::with_capacity(16);
+    arena.shrink_to_fit();
+    assert_eq!(arena.capacity(), 1);
+    arena.alloc_str("still works");
+
+    // An empty chunk before a marker is freed, but the marker still works.
+    let mut arena = Arena::with_capacity(2);
+    arena.reserve_extend(10);
+    arena.alloc(0);
+    let marker = arena.marker();
+    arena.alloc_extend(1..100);
+    arena.shrink_to_fit();
+    assert_eq!(arena.chunk_count(), 2);
+    arena.rollback_to(marker);
+    assert_eq!(arena.into_vec(), vec![0]);
+
+    let mut arena = Arena::with_capacity(2);
+    arena.reserve_extend(10);
+    arena.alloc(0);
+    arena.shrink_to_fit();
+    arena.clear();
+    assert_eq!(arena.chunk_count(), 1);
+    arena.alloc_extend(0..10);
+}
+
+#[test]
+fn clear_keeps_chunks() {
+    let drop_counter = Cell::new(0);
+    let mut arena = Arena::with_capacity(2);
+    for i in 0..20 {
+        arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+    }
+    let chunk_count = arena.chunk_count();
+    let capacity = arena.capacity();
+
+    arena.clear();
+    assert_eq!(drop_counter.get(), 20);
+    assert!(arena.is_empty());
+    assert_eq!(arena.chunk_count(), chunk_count);
+    assert_eq!(arena.capacity(), capacity);
+
+    for i in 0..20 {
+        arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+    }
+    assert_eq!(arena.chunk_count(), chunk_count);
+    assert_eq!(arena.capacity(), capacity);
+    let ids: Vec<u32> = arena.iter_mut().map(|node| node.1).collect();
+    assert_eq!(ids, (0..20).collect::<Vec<_>>());
+
+    drop(arena);
+    assert_eq!(drop_counter.get(), 40);
+}
+
+#[test]
+fn reset_keeps_largest_chunk() {
+    let drop_counter = Cell::new(0);
+    let mut arena = Arena::with_capacity(2);
+    for i in 0..20 {
+        arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+    }
+    arena.reserve(1000);
+    let largest = arena.chunks.borrow().spare[0].capacity();
+
+    arena.reset();
+    assert_eq!(drop_counter.get(), 20);
+    assert!(arena.is_empty());
+    assert_eq!(arena.chunk_count(), 1);
+    assert_eq!(arena.capacity(), largest);
+
+    for i in 0..1000 {
+        arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+    }
+    assert_eq!(arena.chunk_count(), 1);
+
+    drop(arena);
+    assert_eq!(drop_counter.get(), 1020);
+}
+
+#[test]
+fn into_iter_maintains_order_of_allocation() {
+    let drop_counter = Cell::new(0);
+    {
+        let arena = Arena::with_capacity(1);
+        for i in 0..10 {
+            arena.alloc(Node(None, i, DropTracker(&drop_counter)));
+        }
+        let mut iter = arena.into_iter();
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next().unwrap().1, 0);
+        assert_eq!(iter.next_back().unwrap().1, 9);
+        assert_eq!(iter.len(), 8);
+        assert_eq!(drop_counter.get(), 2);
+        let ids: Vec<u32> = iter.by_ref().take(3).map(|node| node.1).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(drop_counter.get(), 5);
+        // The rest are dropped along with the iterator.
+    }
+    assert_eq!(drop_counter.get(), 10);
+}
+
+#[test]
+fn iter_low_capacity() {
+    let mut arena = Arena::with_capacity(1);
+    for i in 0..100 {
+        arena.alloc(i);
+    }
+    let items: Vec<&i32> = arena.iter().collect();
+    assert_eq!(items.len(), 100);
+    assert!(items.iter().enumerate().all(|(i, &&x)| x == i as i32));
+}
+
+#[test]
+fn first_and_last_across_chunks() {
+    let mut arena = Arena::with_capacity(2);
+    arena.alloc_extend(0..2);
+    // Starts a new, still empty current chunk.
+    arena.reserve_extend(10);
+    assert_eq!(arena.first(), Some(&0));
+    assert_eq!(arena.last(), Some(&1));
+
+    arena.alloc(2);
+    *arena.last_mut().unwrap() *= 10;
+    assert_eq!(arena.last(), Some(&20));
+    assert_eq!(arena.first(), Some(&0));
+}
+
+#[test]
+fn indices_are_stable_across_chunks() {
+    let mut arena = Arena::with_capacity(1);
+    let mut indices = Vec::new();
+    for i in 0..50 {
+        if i % 7 == 0 {
+            // Leave some gaps at the end of chunks.
+            arena.alloc_extend(vec![100; i / 7]);
+            arena.reserve_extend(i);
+        }
+        let (index, value) = arena.alloc_indexed(i);
+        assert_eq!(*value, i);
+        indices.push(index);
+    }
+    for (i, &index) in indices.iter().enumerate() {
+        assert_eq!(arena.get(index), Some(&i));
+    }
+    let len = arena.len();
+    assert_eq!(arena.get(len), None);
+    *arena.get_mut(len - 1).unwrap() = 1000;
+    assert_eq!(arena.last(), Some(&1000));
+}
+
+#[test]
+fn contains() {
+    let arena = Arena::with_capacity(2);
+    let values: Vec<&u64> = (0..10).map(|i| &*arena.alloc(i)).collect();
+    assert!(values.iter().all(|value| arena.contains(value)));
+
+    let outside = 3;
+    assert!(!arena.contains(&outside));
+
+    let units = Arena::new();
+    let unit = units.alloc(());
+    assert!(!units.contains(unit));
+}
+
+#[test]
+fn pinned_values_stay_in_place() {
+    use std::marker::PhantomPinned;
+
+    #[allow(dead_code)]
+    struct Pinned<'a>(u32, DropTracker<'a>, PhantomPinned);
+
+    let drop_counter = Cell::new(0);
+    {
+        let arena = PinnedArena::with_capacity(1);
+        let mut addresses = Vec::new();
+        for i in 0..100 {
+            let pinned = arena.alloc_pinned(Pinned(i, DropTracker(&drop_counter), PhantomPinned));
+            addresses.push((&*pinned as *const Pinned, i));
+        }
+        assert_eq!(arena.len(), 100);
+        for &(address, i) in &addresses {
+            assert_eq!(unsafe { (*address).0 }, i);
+        }
+        assert_eq!(drop_counter.get(), 0);
+    }
+    assert_eq!(drop_counter.get(), 100);
+}
@@ -0,0 +1,114 @@
+#[cfg(feature = "allocator_api")]
+use crate::{Allocator, Global};
+use crate::{Arena, ChunkList};
+
+use core::cell::RefMut;
+use core::fmt;
+use core::mem::MaybeUninit;
+
+/// The slot of a value being initialized in place at the end of an arena,
+/// created by [`Arena::alloc_uninit`].
+///
+/// The slot only becomes a value of the arena once it is initialized, with
+/// [`write`][UninitSlot::write] or [`assume_init`][UninitSlot::assume_init].
+/// Dropping the slot instead leaves the arena as it was, and so does
+/// leaking it with `mem::forget`: nothing is ever dropped in its place.
+///
+/// The slot keeps the arena borrowed: allocating anything else in the arena
+/// before it is initialized panics.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::Arena;
+///
+/// let arena = Arena::new();
+/// let mut slot = arena.alloc_uninit();
+/// let address = slot.as_mut_ptr();
+/// let value = slot.write(42);
+/// assert_eq!(value as *mut i32, address);
+/// assert_eq!(arena.len(), 1);
+/// ```
+pub struct UninitSlot<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone = Global> {
+    chunks: RefMut<'a, in_alloc!(ChunkList<T>)>,
+}
+
+impl<T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(Arena<T>) {
+    /// Allocates space for a single value, but doesn't initialize it.
+    ///
+    /// This allows two-phase initialization, for example to get the address
+    /// of a node before filling it in. See [`UninitSlot`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the arena is borrowed, for example by another `UninitSlot`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use typed_arena::Arena;
+    ///
+    /// struct Node<'a> {
+    ///     id: u32,
+    ///     parent: Cell<Option<&'a Node<'a>>>,
+    /// }
+    ///
+    /// let arena = Arena::new();
+    /// let root = arena.alloc(Node { id: 0, parent: Cell::new(None) });
+    /// let mut slot = arena.alloc_uninit();
+    /// slot.as_mut_uninit().write(Node { id: 1, parent: Cell::new(Some(root)) });
+    /// let child = unsafe { slot.assume_init() };
+    /// assert_eq!(child.parent.get().unwrap().id, 0);
+    /// ```
+    pub fn alloc_uninit(&self) -> in_alloc!(UninitSlot<'_, T>) {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.current.len() == chunks.current.capacity() {
+            chunks.reserve(1);
+        }
+        UninitSlot { chunks }
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> in_alloc!(UninitSlot<'a, T>) {
+    /// Returns a pointer to the slot, which is where the value will be.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        let current = &mut self.chunks.current;
+        // Right past the values of the chunk, which have room for one more.
+        unsafe { current.as_mut_ptr().add(current.len()) }
+    }
+
+    /// Returns the slot, to initialize it.
+    pub fn as_mut_uninit(&mut self) -> &mut MaybeUninit<T> {
+        unsafe { &mut *(self.as_mut_ptr() as *mut MaybeUninit<T>) }
+    }
+
+    /// Initializes the slot with `value`, which becomes a value of the
+    /// arena, and returns it.
+    pub fn write(mut self, value: T) -> &'a mut T {
+        self.as_mut_uninit().write(value);
+        unsafe { self.assume_init() }
+    }
+
+    /// Makes the initialized slot a value of the arena, and returns it.
+    ///
+    /// ## Safety
+    ///
+    /// The value must have been fully initialized, as with
+    /// [`MaybeUninit::assume_init_mut`].
+    pub unsafe fn assume_init(mut self) -> &'a mut T {
+        let value = self.as_mut_ptr();
+        let len = self.chunks.current.len();
+        self.chunks.current.set_len(len + 1);
+        // The borrow of the arena is released along with `self`.
+        &mut *value
+    }
+}
+
+impl<'a, T, #[cfg(feature = "allocator_api")] A: Allocator + Clone> fmt::Debug
+    for in_alloc!(UninitSlot<'a, T>)
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UninitSlot").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,254 @@
+use crate::Bump;
+
+use core::alloc::Layout;
+use core::cmp;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// A growable vector whose buffer is allocated in a [`Bump`].
+///
+/// It works like a `Vec<T>`, but takes its memory from the bump arena, so
+/// lists built while constructing a tree don't go through the heap. When
+/// it grows, the values move to a larger buffer, and the old one stays
+/// unused in the bump until the bump is dropped.
+///
+/// [`into_slice`][ArenaVec::into_slice] turns it into a slice that lives as
+/// long as the bump. Otherwise, dropping the vector drops its values.
+///
+/// ## Example
+///
+/// ```
+/// use typed_arena::{ArenaVec, Bump};
+///
+/// let bump = Bump::new();
+/// let mut args = ArenaVec::new_in(&bump);
+/// for arg in "a b c".split(' ') {
+///     args.push(arg);
+/// }
+/// assert_eq!(args.pop(), Some("c"));
+/// let args: &[&str] = args.into_slice();
+/// assert_eq!(args, ["a", "b"]);
+/// ```
+pub struct ArenaVec<'a, T> {
+    bump: &'a Bump,
+    ptr: NonNull<T>,
+    len: usize,
+    capacity: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T> ArenaVec<'a, T> {
+    /// Construct a new, empty vector in `bump`.
+    ///
+    /// It doesn't allocate until the first value is pushed.
+    pub fn new_in(bump: &'a Bump) -> ArenaVec<'a, T> {
+        ArenaVec {
+            bump,
+            ptr: NonNull::dangling(),
+            len: 0,
+            capacity: if mem::size_of::<T>() == 0 {
+                usize::MAX
+            } else {
+                0
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Construct a new vector in `bump`, with room for `capacity` values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaVec, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let mut squares = ArenaVec::with_capacity_in(10, &bump);
+    /// squares.extend((0..10).map(|i| i * i));
+    /// assert_eq!(squares.capacity(), 10);
+    /// ```
+    pub fn with_capacity_in(capacity: usize, bump: &'a Bump) -> ArenaVec<'a, T> {
+        let mut vec = ArenaVec::new_in(bump);
+        vec.reserve(capacity);
+        vec
+    }
+
+    /// Return the number of values in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of values the vector can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Makes room for at least `additional` more values.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the buffer would be larger than `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len.checked_add(additional).expect("capacity overflow");
+        if needed <= self.capacity {
+            return;
+        }
+        let capacity = cmp::max(needed, cmp::max(self.capacity * 2, 4));
+        let layout = Layout::array::<T>(capacity).expect("capacity overflow");
+        let ptr = self.bump.alloc_layout(layout).cast::<T>();
+        unsafe { ptr::copy_nonoverlapping(self.ptr.as_ptr(), ptr.as_ptr(), self.len) };
+        self.ptr = ptr;
+        self.capacity = capacity;
+    }
+
+    /// Appends a value to the vector.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaVec, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let mut vec = ArenaVec::new_in(&bump);
+    /// vec.push(1);
+    /// vec.push(2);
+    /// assert_eq!(vec, [1, 2]);
+    /// ```
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity {
+            self.reserve(1);
+        }
+        unsafe { ptr::write(self.ptr.as_ptr().add(self.len), value) };
+        self.len += 1;
+    }
+
+    /// Removes the last value of the vector, and returns it, or `None` if
+    /// the vector is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { ptr::read(self.ptr.as_ptr().add(self.len)) })
+    }
+
+    /// Drops the values past the first `len`.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail =
+            ptr::slice_from_raw_parts_mut(unsafe { self.ptr.as_ptr().add(len) }, self.len - len);
+        // Shorten first, so that a panicking destructor can't cause a
+        // double drop.
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Drops all the values of the vector, and keeps its buffer.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Converts the vector into a slice that lives as long as the bump.
+    ///
+    /// Like other values in the bump, the values will not be dropped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaVec, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let mut vec = ArenaVec::new_in(&bump);
+    /// vec.extend(0..3);
+    /// let slice: &mut [i32] = vec.into_slice();
+    /// slice[0] = 5;
+    /// assert_eq!(slice, [5, 1, 2]);
+    /// ```
+    pub fn into_slice(self) -> &'a mut [T] {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe { slice::from_raw_parts_mut(this.ptr.as_ptr(), this.len) }
+    }
+}
+
+impl<'a, T: Copy> ArenaVec<'a, T> {
+    /// Appends a copy of each value of `values`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use typed_arena::{ArenaVec, Bump};
+    ///
+    /// let bump = Bump::new();
+    /// let mut vec = ArenaVec::new_in(&bump);
+    /// vec.extend_from_slice(&[1, 2]);
+    /// vec.extend_from_slice(&[3]);
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        self.reserve(values.len());
+        unsafe {
+            let end = self.ptr.as_ptr().add(self.len);
+            ptr::copy_nonoverlapping(values.as_ptr(), end, values.len());
+        }
+        self.len += values.len();
+    }
+}
+
+impl<'a, T> Drop for ArenaVec<'a, T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T> Deref for ArenaVec<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, T> DerefMut for ArenaVec<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a, T> Extend<T> for ArenaVec<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for ArenaVec<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<'a, T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for ArenaVec<'a, T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        **self == other[..]
+    }
+}
+
+impl<'a, T: PartialEq<U>, U> PartialEq<[U]> for ArenaVec<'a, T> {
+    fn eq(&self, other: &[U]) -> bool {
+        **self == *other
+    }
+}